@@ -0,0 +1,168 @@
+/// Region analytics module
+/// 
+/// **Learning Point**: Tile counts alone cannot tell one big lake from twenty puddles.
+/// Flood-filling each tile type into connected regions gives the shape metrics
+/// (size, centroid, perimeter) that balancing and the LLM feedback loop reason about,
+/// and the road degree of every road hex summarizes the network topology.
+
+use wasm_bindgen::prelude::*;
+use std::collections::{HashMap, HashSet};
+use crate::state::WFC_STATE;
+use crate::types::TileType;
+use crate::hex_utils::{generate_hex_grid, get_hex_neighbors, connected_components};
+
+/// One connected region of a single tile type
+pub struct Region {
+    pub tile_type: TileType,
+    /// Region hexes, flood fill order starting from the smallest hex
+    pub hexes: Vec<(i32, i32)>,
+    /// Mean axial coordinate
+    pub centroid: (f64, f64),
+    /// Number of hex edges shared with a different tile type or the edge of the area
+    pub perimeter: usize,
+}
+
+impl Region {
+    fn new(tile_type: TileType, hexes: Vec<(i32, i32)>) -> Self {
+        let members: HashSet<(i32, i32)> = hexes.iter().copied().collect();
+        let perimeter = hexes
+            .iter()
+            .map(|&(q, r)| get_hex_neighbors(q, r).iter().filter(|neighbor| !members.contains(neighbor)).count())
+            .sum();
+        let count = hexes.len().max(1) as f64;
+        let centroid = (
+            hexes.iter().map(|hex| hex.0 as f64).sum::<f64>() / count,
+            hexes.iter().map(|hex| hex.1 as f64).sum::<f64>() / count,
+        );
+        Region { tile_type, hexes, centroid, perimeter }
+    }
+    
+    pub fn to_json(&self) -> String {
+        let (q, r) = self.hexes[0];
+        format!(
+            r#"{{"size":{},"centroid":{{"q":{:.3},"r":{:.3}}},"perimeter":{},"start":{{"q":{},"r":{}}}}}"#,
+            self.hexes.len(), self.centroid.0, self.centroid.1, self.perimeter, q, r
+        )
+    }
+}
+
+/// Road network topology
+pub struct RoadStats {
+    pub road_hexes: usize,
+    /// Adjacent road pairs (total length of the network in hex steps)
+    pub edges: usize,
+    pub components: usize,
+    /// Road hexes with exactly one road neighbor
+    pub dead_ends: usize,
+    /// Road hexes with three or more road neighbors
+    pub junctions: usize,
+    /// Road hex count by number of road neighbors (0-6)
+    pub degree_histogram: [usize; 7],
+}
+
+impl RoadStats {
+    fn new(roads: &HashSet<(i32, i32)>) -> Self {
+        let mut degree_histogram = [0usize; 7];
+        for &(q, r) in roads {
+            let degree = get_hex_neighbors(q, r).iter().filter(|neighbor| roads.contains(neighbor)).count();
+            degree_histogram[degree] += 1;
+        }
+        let degree_sum: usize = degree_histogram.iter().enumerate().map(|(degree, count)| degree * count).sum();
+        
+        RoadStats {
+            road_hexes: roads.len(),
+            edges: degree_sum / 2,
+            components: connected_components(roads).len(),
+            dead_ends: degree_histogram[1],
+            junctions: degree_histogram[3..].iter().sum(),
+            degree_histogram,
+        }
+    }
+    
+    pub fn to_json(&self) -> String {
+        let histogram: Vec<String> = self.degree_histogram.iter().map(|count| count.to_string()).collect();
+        format!(
+            r#"{{"hexes":{},"length":{},"components":{},"deadEnds":{},"junctions":{},"degreeHistogram":[{}]}}"#,
+            self.road_hexes, self.edges, self.components, self.dead_ends, self.junctions, histogram.join(",")
+        )
+    }
+}
+
+/// Flood-fill every tile type into connected regions
+/// Regions of each type are ordered largest first (ties by smallest hex)
+pub fn analyze_regions(tiles: &HashMap<(i32, i32), TileType>) -> Vec<Region> {
+    let mut regions = Vec::new();
+    for tile_type in [TileType::Grass, TileType::Building, TileType::Road, TileType::Forest, TileType::Water] {
+        let hexes: HashSet<(i32, i32)> = tiles
+            .iter()
+            .filter(|(_, &tile)| tile == tile_type)
+            .map(|(hex, _)| *hex)
+            .collect();
+        for component in connected_components(&hexes) {
+            regions.push(Region::new(tile_type, component));
+        }
+    }
+    regions
+}
+
+/// Get region analytics for a chunk (or the whole layout)
+/// 
+/// **Learning Point**: Only tiles inside the area are considered, so a lake cut by the
+/// chunk border counts its border edges as perimeter. Per-type region lists are
+/// largest first; "largestLake" and "largestForest" are null when there is none.
+/// 
+/// @param center_q - Chunk center q coordinate
+/// @param center_r - Chunk center r coordinate
+/// @param rings - Number of rings per chunk, negative for the whole layout
+/// @returns JSON string: {"tiles":0,"regions":{"grass":[{"size":0,"centroid":{"q":0.0,"r":0.0},
+///          "perimeter":0,"start":{"q":0,"r":0}},...],"building":[...],...},
+///          "largestLake":{...}|null,"largestForest":{...}|null,
+///          "roads":{"hexes":0,"length":0,"components":0,"deadEnds":0,"junctions":0,
+///          "degreeHistogram":[0,0,0,0,0,0,0]}}
+#[wasm_bindgen]
+pub fn get_region_analytics(center_q: i32, center_r: i32, rings: i32) -> String {
+    let state = WFC_STATE.lock().unwrap();
+    let tiles: HashMap<(i32, i32), TileType> = if rings < 0 {
+        state.tiles().collect()
+    } else {
+        generate_hex_grid(rings, center_q, center_r)
+            .iter()
+            .filter_map(|hex| state.get_tile(hex.q, hex.r).map(|tile_type| ((hex.q, hex.r), tile_type)))
+            .collect()
+    };
+    drop(state);
+    
+    let regions = analyze_regions(&tiles);
+    let largest = |tile_type: TileType| {
+        regions
+            .iter()
+            .find(|region| region.tile_type == tile_type)
+            .map(|region| region.to_json())
+            .unwrap_or_else(|| "null".to_string())
+    };
+    
+    let mut region_parts = Vec::new();
+    for tile_type in [TileType::Grass, TileType::Building, TileType::Road, TileType::Forest, TileType::Water] {
+        let json_parts: Vec<String> = regions
+            .iter()
+            .filter(|region| region.tile_type == tile_type)
+            .map(|region| region.to_json())
+            .collect();
+        region_parts.push(format!(r#""{}":[{}]"#, tile_type.as_str(), json_parts.join(",")));
+    }
+    
+    let roads: HashSet<(i32, i32)> = tiles
+        .iter()
+        .filter(|(_, &tile_type)| tile_type == TileType::Road)
+        .map(|(hex, _)| *hex)
+        .collect();
+    
+    format!(
+        r#"{{"tiles":{},"regions":{{{}}},"largestLake":{},"largestForest":{},"roads":{}}}"#,
+        tiles.len(),
+        region_parts.join(","),
+        largest(TileType::Water),
+        largest(TileType::Forest),
+        RoadStats::new(&roads).to_json()
+    )
+}
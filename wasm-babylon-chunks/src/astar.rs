@@ -2,8 +2,10 @@
 
 use wasm_bindgen::prelude::*;
 use std::collections::{HashMap, HashSet, BinaryHeap};
+use crate::state::WFC_STATE;
 use crate::types::AStarNode;
-use crate::hex_utils::{get_hex_neighbors, parse_valid_terrain_json, axial_to_cube, cube_distance, hex_distance};
+use crate::json::coords_to_json;
+use crate::hex_utils::{get_hex_neighbors, parse_valid_terrain_json, parse_tile_types_json, axial_to_cube, cube_distance, hex_distance};
 
 /// Hex A* pathfinding between two road tiles
 /// Returns path length, or -1 if unreachable
@@ -89,7 +91,12 @@ pub fn hex_astar_path(
     -1
 }
 
-/// Hex A* pathfinding that returns full path
+/// Hex A* pathfinding that returns the full path over any passable predicate
+/// 
+/// **Learning Point**: Takes a closure instead of an owned/serialized terrain set so
+/// callers can search borrowed `HashSet`s or the loaded `WfcState` grid directly.
+/// Repeated searches (e.g. road generation) then cost nothing extra to set up.
+/// 
 /// Matches TypeScript hexAStar algorithm exactly:
 /// - Uses cube coordinates for distance calculation (cube_distance)
 /// - Maintains open set as BinaryHeap (min-heap by f score, then h score)
@@ -97,31 +104,25 @@ pub fn hex_astar_path(
 /// - Maintains g_scores as HashMap
 /// - Stores parent pointers for path reconstruction
 /// 
-/// @param start_q - Start q coordinate (axial)
-/// @param start_r - Start r coordinate (axial)
-/// @param goal_q - Goal q coordinate (axial)
-/// @param goal_r - Goal r coordinate (axial)
-/// @param valid_terrain_json - JSON string with array of valid terrain coordinates: [{"q":0,"r":0},...]
-/// @returns JSON string with path array [{"q":0,"r":0},...] or "null" if no path found
-#[wasm_bindgen]
-pub fn hex_astar(
+/// @returns Path from start to goal (both inclusive), or None if no path found
+pub fn find_path<F>(
     start_q: i32,
     start_r: i32,
     goal_q: i32,
     goal_r: i32,
-    valid_terrain_json: String,
-) -> String {
-    // Parse valid terrain from JSON
-    let valid_terrain = parse_valid_terrain_json(&valid_terrain_json);
-    
-    // Check if start and goal are in valid terrain
-    if !valid_terrain.contains(&(start_q, start_r)) || !valid_terrain.contains(&(goal_q, goal_r)) {
-        return "null".to_string();
+    is_passable: F,
+) -> Option<Vec<(i32, i32)>>
+where
+    F: Fn(i32, i32) -> bool,
+{
+    // Check if start and goal are passable
+    if !is_passable(start_q, start_r) || !is_passable(goal_q, goal_r) {
+        return None;
     }
     
     // If start equals goal, return path with single node
     if start_q == goal_q && start_r == goal_r {
-        return format!(r#"[{{"q":{},"r":{}}}]"#, start_q, start_r);
+        return Some(vec![(start_q, start_r)]);
     }
     
     // Convert goal to cube for distance calculation (matches TypeScript)
@@ -156,42 +157,17 @@ pub fn hex_astar(
         
         // Check if we reached the goal
         if current.q == goal_q && current.r == goal_r {
-            // Reconstruct path by following parent pointers
-            let mut path: Vec<(i32, i32)> = Vec::new();
+            // Reconstruct path by following parent pointers from goal to start
+            let mut path: Vec<(i32, i32)> = vec![(goal_q, goal_r)];
             let mut node_key = (goal_q, goal_r);
-            
-            // Follow parent pointers from goal to start
-            loop {
-                path.push(node_key);
-                
-                // Get parent for this node
-                if let Some(parent_key) = parents.get(&node_key) {
-                    // If parent is the start, add it and break
-                    if parent_key.0 == start_q && parent_key.1 == start_r {
-                        path.push((start_q, start_r));
-                        break;
-                    }
-                    node_key = *parent_key;
-                } else {
-                    // No parent in map means we're at start (shouldn't happen in normal flow)
-                    // But handle it just in case
-                    if node_key.0 != start_q || node_key.1 != start_r {
-                        path.push((start_q, start_r));
-                    }
-                    break;
-                }
+            while let Some(parent_key) = parents.get(&node_key) {
+                path.push(*parent_key);
+                node_key = *parent_key;
             }
             
             // Reverse path to get start-to-goal order
             path.reverse();
-            
-            // Build JSON string
-            let mut json_parts = Vec::new();
-            for (q, r) in path {
-                json_parts.push(format!(r#"{{"q":{},"r":{}}}"#, q, r));
-            }
-            
-            return format!("[{}]", json_parts.join(","));
+            return Some(path);
         }
         
         // Explore neighbors
@@ -199,13 +175,13 @@ pub fn hex_astar(
         for (nq, nr) in neighbors {
             let neighbor_key = (nq, nr);
             
-            // Skip if not in valid terrain
-            if !valid_terrain.contains(&neighbor_key) {
+            // Skip if already closed
+            if closed_set.contains(&neighbor_key) {
                 continue;
             }
             
-            // Skip if already closed
-            if closed_set.contains(&neighbor_key) {
+            // Skip if not passable
+            if !is_passable(nq, nr) {
                 continue;
             }
             
@@ -225,7 +201,75 @@ pub fn hex_astar(
     }
     
     // No path found
-    "null".to_string()
+    None
+}
+
+/// Hex A* pathfinding over a borrowed terrain set
+/// Convenience wrapper around find_path for callers holding a HashSet
+pub fn find_path_in_set(
+    start: (i32, i32),
+    goal: (i32, i32),
+    valid_terrain: &HashSet<(i32, i32)>,
+) -> Option<Vec<(i32, i32)>> {
+    find_path(start.0, start.1, goal.0, goal.1, |q, r| valid_terrain.contains(&(q, r)))
+}
+
+/// Hex A* pathfinding that returns full path
+/// 
+/// @param start_q - Start q coordinate (axial)
+/// @param start_r - Start r coordinate (axial)
+/// @param goal_q - Goal q coordinate (axial)
+/// @param goal_r - Goal r coordinate (axial)
+/// @param valid_terrain_json - JSON string with array of valid terrain coordinates: [{"q":0,"r":0},...]
+/// @returns JSON string with path array [{"q":0,"r":0},...] or "null" if no path found
+#[wasm_bindgen]
+pub fn hex_astar(
+    start_q: i32,
+    start_r: i32,
+    goal_q: i32,
+    goal_r: i32,
+    valid_terrain_json: String,
+) -> String {
+    // Parse valid terrain from JSON
+    let valid_terrain = parse_valid_terrain_json(&valid_terrain_json);
+    
+    match find_path_in_set((start_q, start_r), (goal_q, goal_r), &valid_terrain) {
+        Some(path) => coords_to_json(&path),
+        None => "null".to_string(),
+    }
+}
+
+/// Find a path directly against the loaded layout grid
+/// 
+/// **Learning Point**: Queries `WFC_STATE` in place, so TypeScript never has to
+/// serialize the valid terrain set. A hex is passable when it exists in the grid
+/// and its tile type is one of `passable_types`.
+/// 
+/// @param start_q - Start q coordinate (axial)
+/// @param start_r - Start r coordinate (axial)
+/// @param goal_q - Goal q coordinate (axial)
+/// @param goal_r - Goal r coordinate (axial)
+/// @param passable_types_json - JSON array of passable tile types: [0,2] (TileType values)
+/// @returns JSON string with path array [{"q":0,"r":0},...] or "null" if no path found
+#[wasm_bindgen]
+pub fn find_path_on_layout(
+    start_q: i32,
+    start_r: i32,
+    goal_q: i32,
+    goal_r: i32,
+    passable_types_json: String,
+) -> String {
+    let passable_types = parse_tile_types_json(&passable_types_json);
+    let state = WFC_STATE.lock().unwrap();
+    
+    let is_passable = |q: i32, r: i32| -> bool {
+        state.get_tile(q, r).is_some_and(|tile| passable_types.contains(&tile))
+    };
+    
+    match find_path(start_q, start_r, goal_q, goal_r, is_passable) {
+        Some(path) => coords_to_json(&path),
+        None => "null".to_string(),
+    }
 }
 
 /// Build a path between two road points using A* pathfinding
@@ -246,91 +290,14 @@ pub fn build_path_between_roads(
     end_r: i32,
     valid_terrain_json: String,
 ) -> String {
-    // Call hex_astar to get full path
-    let full_path_json = hex_astar(start_q, start_r, end_q, end_r, valid_terrain_json);
-    
-    // If no path, return null
-    if full_path_json == "null" || full_path_json.is_empty() {
-        return "null".to_string();
-    }
-    
-    // Parse the path JSON
-    // Simple parsing: extract all {"q":X,"r":Y} patterns and skip first one
-    let trimmed = full_path_json.trim();
-    if trimmed == "[]" || trimmed.len() < 3 {
-        return "null".to_string();
-    }
-    
-    // Find all coordinate pairs
-    let mut coords: Vec<(i32, i32)> = Vec::new();
-    let mut i = 0;
-    let chars: Vec<char> = trimmed.chars().collect();
-    while i < chars.len() {
-        if chars[i] == '{' {
-            let mut q_value: Option<i32> = None;
-            let mut r_value: Option<i32> = None;
-            
-            i += 1;
-            while i < chars.len() && chars[i] != '}' {
-                if i + 3 < chars.len() && chars[i] == '"' && chars[i + 1] == 'q' && chars[i + 2] == '"' {
-                    i += 3;
-                    while i < chars.len() && (chars[i] == ':' || chars[i] == ' ' || chars[i] == '\t') {
-                        i += 1;
-                    }
-                    if i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '-') {
-                        let start = i;
-                        i += 1;
-                        while i < chars.len() && chars[i].is_ascii_digit() {
-                            i += 1;
-                        }
-                        let num_str: String = chars[start..i].iter().collect();
-                        if let Ok(num) = num_str.parse::<i32>() {
-                            q_value = Some(num);
-                        }
-                    }
-                } else if i + 3 < chars.len() && chars[i] == '"' && chars[i + 1] == 'r' && chars[i + 2] == '"' {
-                    i += 3;
-                    while i < chars.len() && (chars[i] == ':' || chars[i] == ' ' || chars[i] == '\t') {
-                        i += 1;
-                    }
-                    if i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '-') {
-                        let start = i;
-                        i += 1;
-                        while i < chars.len() && chars[i].is_ascii_digit() {
-                            i += 1;
-                        }
-                        let num_str: String = chars[start..i].iter().collect();
-                        if let Ok(num) = num_str.parse::<i32>() {
-                            r_value = Some(num);
-                        }
-                    }
-                } else {
-                    i += 1;
-                }
-            }
-            
-            if let (Some(q), Some(r)) = (q_value, r_value) {
-                coords.push((q, r));
-            }
-        }
-        i += 1;
-    }
+    let valid_terrain = parse_valid_terrain_json(&valid_terrain_json);
     
     // If path has less than 2 nodes, return null
-    if coords.len() < 2 {
-        return "null".to_string();
+    match find_path_in_set((start_q, start_r), (end_q, end_r), &valid_terrain) {
+        // Return path excluding start (first element), including end (last element)
+        Some(path) if path.len() >= 2 => coords_to_json(&path[1..]),
+        _ => "null".to_string(),
     }
-    
-    // Return path excluding start (first element), including end (last element)
-    let path_without_start = &coords[1..];
-    
-    // Build JSON string
-    let mut json_parts = Vec::new();
-    for (q, r) in path_without_start {
-        json_parts.push(format!(r#"{{"q":{},"r":{}}}"#, q, r));
-    }
-    
-    format!("[{}]", json_parts.join(","))
 }
 
 /// Validate that all road tiles are reachable from each other using A* pathfinding
@@ -0,0 +1,302 @@
+/// Building placement module
+/// 
+/// **Learning Point**: Buildings are multi-hex footprints anchored on a hex that fronts a
+/// road. The footprint extends away from the road the building faces, so entrances stay
+/// on the street. Footprints never overlap each other, roads or occupied hexes.
+
+use wasm_bindgen::prelude::*;
+use std::collections::HashSet;
+use crate::state::WFC_STATE;
+use crate::hex_utils::{parse_valid_terrain_json, hex_distance, CUBE_DIRECTIONS};
+use crate::seed::SeededRng;
+use crate::utils::content_seed;
+use crate::json::{json_int_field, json_string_field, coords_to_json};
+
+/// Building size hint (matches CONSTRAINTS.defaultBuildingSizeHint)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BuildingSize {
+    /// Single hex
+    Small,
+    /// Two hexes, anchor plus one behind it
+    Medium,
+    /// Four hexes in a rhombus behind the anchor
+    Large,
+}
+
+impl BuildingSize {
+    pub fn from_str(value: &str) -> Option<BuildingSize> {
+        match value {
+            "small" => Some(BuildingSize::Small),
+            "medium" => Some(BuildingSize::Medium),
+            "large" => Some(BuildingSize::Large),
+            _ => None,
+        }
+    }
+    
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BuildingSize::Small => "small",
+            BuildingSize::Medium => "medium",
+            BuildingSize::Large => "large",
+        }
+    }
+    
+    /// Number of hexes in the footprint
+    pub fn hex_count(&self) -> usize {
+        match self {
+            BuildingSize::Small => 1,
+            BuildingSize::Medium => 2,
+            BuildingSize::Large => 4,
+        }
+    }
+    
+    /// Next smaller size to fall back to when a footprint does not fit
+    fn smaller(&self) -> Option<BuildingSize> {
+        match self {
+            BuildingSize::Small => None,
+            BuildingSize::Medium => Some(BuildingSize::Small),
+            BuildingSize::Large => Some(BuildingSize::Medium),
+        }
+    }
+    
+    /// Candidate footprints for an anchor facing direction `facing`
+    /// Each footprint starts with the anchor and extends away from the facing direction
+    fn footprints(&self, anchor: (i32, i32), facing: usize) -> Vec<Vec<(i32, i32)>> {
+        let step = |hex: (i32, i32), direction: usize| {
+            let offset = CUBE_DIRECTIONS[direction % 6];
+            (hex.0 + offset.q, hex.1 + offset.r)
+        };
+        let back = (facing + 3) % 6;
+        let back_left = (facing + 2) % 6;
+        let back_right = (facing + 4) % 6;
+        
+        match self {
+            BuildingSize::Small => vec![vec![anchor]],
+            BuildingSize::Medium => vec![
+                vec![anchor, step(anchor, back)],
+                vec![anchor, step(anchor, back_left)],
+                vec![anchor, step(anchor, back_right)],
+            ],
+            BuildingSize::Large => vec![
+                vec![anchor, step(anchor, back), step(anchor, back_left), step(step(anchor, back), back_left)],
+                vec![anchor, step(anchor, back), step(anchor, back_right), step(step(anchor, back), back_right)],
+            ],
+        }
+    }
+}
+
+/// Order in which candidate lots are filled (matches CONSTRAINTS.defaultClustering)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Clustering {
+    /// Next building goes next to the buildings already placed
+    Clustered,
+    /// Next building goes as far as possible from the buildings already placed
+    Distributed,
+    /// Seeded random order
+    Random,
+}
+
+impl Clustering {
+    pub fn from_str(value: &str) -> Option<Clustering> {
+        match value {
+            "clustered" => Some(Clustering::Clustered),
+            "distributed" => Some(Clustering::Distributed),
+            "random" => Some(Clustering::Random),
+            _ => None,
+        }
+    }
+}
+
+/// Building placement rules
+/// 
+/// Parsed from JSON: {"minAdjacentRoads":1,"sizeHint":"medium","clustering":"random"}
+pub struct BuildingRules {
+    /// Minimum number of road hexes adjacent to the anchor hex
+    pub min_adjacent_roads: usize,
+    /// Preferred footprint; smaller footprints are used where it does not fit
+    pub size_hint: BuildingSize,
+    pub clustering: Clustering,
+}
+
+impl Default for BuildingRules {
+    fn default() -> Self {
+        BuildingRules {
+            min_adjacent_roads: 1,
+            size_hint: BuildingSize::Medium,
+            clustering: Clustering::Random,
+        }
+    }
+}
+
+impl BuildingRules {
+    pub fn from_json(rules_json: &str) -> Self {
+        let defaults = BuildingRules::default();
+        BuildingRules {
+            min_adjacent_roads: json_int_field(rules_json, "minAdjacentRoads")
+                .map(|value| value.clamp(0, 6) as usize)
+                .unwrap_or(defaults.min_adjacent_roads),
+            size_hint: json_string_field(rules_json, "sizeHint")
+                .and_then(|value| BuildingSize::from_str(&value))
+                .unwrap_or(defaults.size_hint),
+            clustering: json_string_field(rules_json, "clustering")
+                .and_then(|value| Clustering::from_str(&value))
+                .unwrap_or(defaults.clustering),
+        }
+    }
+}
+
+/// A placed building
+#[derive(Clone, Debug, PartialEq)]
+pub struct BuildingRecord {
+    /// Road-fronting hex the footprint grows from
+    pub anchor: (i32, i32),
+    /// Footprint hexes, anchor first
+    pub hexes: Vec<(i32, i32)>,
+    /// Direction index (into CUBE_DIRECTIONS) from the anchor to the road it faces
+    pub facing: usize,
+    pub size: BuildingSize,
+}
+
+impl BuildingRecord {
+    pub fn to_json(&self, id: usize) -> String {
+        format!(
+            r#"{{"id":{},"q":{},"r":{},"facing":{},"size":"{}","hexes":{}}}"#,
+            id, self.anchor.0, self.anchor.1, self.facing, self.size.as_str(), coords_to_json(&self.hexes)
+        )
+    }
+}
+
+/// Pick the index of the next anchor to try according to the clustering mode
+/// Ties keep the (seeded) candidate order
+fn next_anchor_index(anchors: &[(i32, i32)], placed: &[BuildingRecord], clustering: Clustering) -> usize {
+    if placed.is_empty() || clustering == Clustering::Random {
+        return 0;
+    }
+    
+    let nearest_building = |hex: &(i32, i32)| {
+        placed
+            .iter()
+            .map(|building| hex_distance(hex.0, hex.1, building.anchor.0, building.anchor.1))
+            .min()
+            .unwrap_or(0)
+    };
+    
+    let mut best_index = 0;
+    let mut best_distance = nearest_building(&anchors[0]);
+    for (index, hex) in anchors.iter().enumerate().skip(1) {
+        let distance = nearest_building(hex);
+        let better = match clustering {
+            Clustering::Clustered => distance < best_distance,
+            _ => distance > best_distance,
+        };
+        if better {
+            best_index = index;
+            best_distance = distance;
+        }
+    }
+    best_index
+}
+
+/// Place up to `target_count` buildings on buildable hexes fronting the road network
+/// 
+/// **Learning Point**: Anchors are hexes with at least one road neighbor (and at least
+/// `min_adjacent_roads`). Each building faces the first adjacent road hex in direction
+/// order and tries its footprints from the size hint down to a single hex, so dense
+/// areas still get small buildings. Claimed hexes are tracked so footprints never overlap.
+/// 
+/// `buildable` must already exclude roads and occupied hexes.
+pub fn place_buildings(
+    buildable: &HashSet<(i32, i32)>,
+    roads: &HashSet<(i32, i32)>,
+    rules: &BuildingRules,
+    target_count: usize,
+    rng: &mut SeededRng,
+) -> Vec<BuildingRecord> {
+    let road_directions = |hex: (i32, i32)| -> Vec<usize> {
+        (0..6)
+            .filter(|&direction| {
+                let offset = CUBE_DIRECTIONS[direction];
+                roads.contains(&(hex.0 + offset.q, hex.1 + offset.r))
+            })
+            .collect()
+    };
+    
+    // Sorted first so set iteration order never leaks into the shuffle
+    let mut anchors: Vec<(i32, i32)> = buildable
+        .iter()
+        .copied()
+        .filter(|&hex| {
+            let adjacent_roads = road_directions(hex).len();
+            adjacent_roads > 0 && adjacent_roads >= rules.min_adjacent_roads
+        })
+        .collect();
+    anchors.sort();
+    rng.shuffle(&mut anchors);
+    
+    let mut claimed: HashSet<(i32, i32)> = HashSet::new();
+    let mut buildings: Vec<BuildingRecord> = Vec::new();
+    
+    while buildings.len() < target_count && !anchors.is_empty() {
+        let anchor = anchors.remove(next_anchor_index(&anchors, &buildings, rules.clustering));
+        if claimed.contains(&anchor) {
+            continue;
+        }
+        let facing = road_directions(anchor)[0];
+        
+        let mut size = Some(rules.size_hint);
+        while let Some(current) = size {
+            let footprint = current
+                .footprints(anchor, facing)
+                .into_iter()
+                .find(|hexes| hexes.iter().all(|hex| buildable.contains(hex) && !claimed.contains(hex)));
+            if let Some(hexes) = footprint {
+                claimed.extend(hexes.iter().copied());
+                buildings.push(BuildingRecord { anchor, hexes, facing, size: current });
+                break;
+            }
+            size = current.smaller();
+        }
+    }
+    
+    buildings
+}
+
+/// Generate buildings with multi-hex footprints on valid terrain fronting roads
+/// 
+/// @param valid_terrain_json - JSON array of valid terrain: [{"q":0,"r":0},...]
+/// @param road_network_json - JSON array of road coordinates: [{"q":0,"r":0},...]
+/// @param occupied_json - JSON array of occupied hexes: [{"q":0,"r":0},...]
+/// @param building_rules_json - JSON object (see BuildingRules::from_json), "{}" for defaults
+/// @param target_count - Target number of buildings to place
+/// @returns JSON array of buildings:
+///          [{"id":0,"q":0,"r":0,"facing":0,"size":"medium","hexes":[{"q":0,"r":0},...]},...]
+#[wasm_bindgen]
+pub fn generate_buildings(
+    valid_terrain_json: String,
+    road_network_json: String,
+    occupied_json: String,
+    building_rules_json: String,
+    target_count: i32,
+) -> String {
+    let roads = parse_valid_terrain_json(&road_network_json);
+    let occupied = parse_valid_terrain_json(&occupied_json);
+    let buildable: HashSet<(i32, i32)> = parse_valid_terrain_json(&valid_terrain_json)
+        .into_iter()
+        .filter(|hex| !roads.contains(hex) && !occupied.contains(hex))
+        .collect();
+    let rules = BuildingRules::from_json(&building_rules_json);
+    
+    let mut sorted_buildable: Vec<(i32, i32)> = buildable.iter().copied().collect();
+    sorted_buildable.sort();
+    let world_seed = WFC_STATE.lock().unwrap().world_seed();
+    let mut rng = SeededRng::new(content_seed(world_seed, &sorted_buildable));
+    
+    let buildings = place_buildings(&buildable, &roads, &rules, target_count.max(0) as usize, &mut rng);
+    let json_parts: Vec<String> = buildings
+        .iter()
+        .enumerate()
+        .map(|(id, building)| building.to_json(id))
+        .collect();
+    
+    format!("[{}]", json_parts.join(","))
+}
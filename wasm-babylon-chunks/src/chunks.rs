@@ -1,24 +1,101 @@
 /// Chunk management module
 
 use wasm_bindgen::prelude::*;
-use crate::hex_utils::{parse_valid_terrain_json, hex_distance};
+use std::collections::HashMap;
+use crate::state::WFC_STATE;
+use crate::types::{CubeCoord, TileType};
+use crate::seed::hash_coords;
+use crate::hex_utils::{parse_valid_terrain_json, hex_distance, cube_ring, get_hex_neighbors, generate_hex_grid, hex_rotate};
+use crate::json::{json_objects, json_int_field, coords_to_json};
 
 /// Calculate chunk radius for distance threshold calculations
 /// The chunk radius is the distance from chunk center to the outer boundary
 /// 
+/// Chunks tile the plane exactly (see chunk_lattice_center), so every tile of a chunk
+/// is within `rings` of its center and no tile is within `rings` of two centers.
+/// 
 /// @param rings - Number of rings per chunk
 /// @returns Chunk radius in hex distance units
 #[wasm_bindgen]
 pub fn calculate_chunk_radius(rings: i32) -> i32 {
-    rings
+    rings.max(0)
+}
+
+/// Lattice basis vectors of the chunk tiling for a given ring count
+/// 
+/// **Learning Point**: A hexagon of radius R holds 3R² + 3R + 1 tiles. The vectors
+/// u = (R, R+1) and v = (2R+1, -R) (u rotated 60 degrees clockwise) span a lattice whose
+/// cell has exactly that area (|det| = 3R² + 3R + 1), and every lattice point is at
+/// hex distance 2R+1 from its six nearest neighbors, so the hexagons centered on the
+/// lattice points touch without overlapping and leave no gaps.
+fn chunk_lattice_basis(rings: i32) -> ((i32, i32), (i32, i32)) {
+    let rings = rings.max(0);
+    ((rings, rings + 1), (2 * rings + 1, -rings))
+}
+
+/// Number of tiles in one chunk (the lattice cell area)
+pub fn chunk_tile_count(rings: i32) -> i32 {
+    let rings = rings.max(0);
+    3 * rings * rings + 3 * rings + 1
+}
+
+/// Chunk center for lattice index (i, j): i * u + j * v
+/// The chunk at index (0, 0) is centered on the origin
+pub fn chunk_lattice_center(i: i32, j: i32, rings: i32) -> (i32, i32) {
+    let ((uq, ur), (vq, vr)) = chunk_lattice_basis(rings);
+    (i * uq + j * vq, i * ur + j * vr)
+}
+
+/// Lattice index (i, j) of the chunk that owns tile (q, r)
+/// 
+/// **Learning Point**: Solving (q, r) = i * u + j * v gives fractional coordinates
+/// i = (R*q + (2R+1)*r) / N and j = ((R+1)*q - R*r) / N with N = 3R² + 3R + 1.
+/// The owning center is one of the lattice points around that fractional position;
+/// exactly one of them is within `rings` of the tile.
+pub fn chunk_lattice_index(q: i32, r: i32, rings: i32) -> (i32, i32) {
+    let rings = rings.max(0);
+    let cell_area = chunk_tile_count(rings) as i64;
+    let (q64, r64, rings64) = (q as i64, r as i64, rings as i64);
+    let base_i = (rings64 * q64 + (2 * rings64 + 1) * r64).div_euclid(cell_area) as i32;
+    let base_j = ((rings64 + 1) * q64 - rings64 * r64).div_euclid(cell_area) as i32;
+    
+    for di in -1..=2 {
+        for dj in -1..=2 {
+            let (i, j) = (base_i + di, base_j + dj);
+            let (center_q, center_r) = chunk_lattice_center(i, j, rings);
+            if hex_distance(q, r, center_q, center_r) <= rings {
+                return (i, j);
+            }
+        }
+    }
+    
+    // Unreachable for an exact tiling; fall back to the nearest candidate center
+    (-1..=2)
+        .flat_map(|di| (-1..=2).map(move |dj| (base_i + di, base_j + dj)))
+        .min_by_key(|&(i, j)| {
+            let (center_q, center_r) = chunk_lattice_center(i, j, rings);
+            hex_distance(q, r, center_q, center_r)
+        })
+        .unwrap_or((base_i, base_j))
 }
 
-/// Calculate chunk neighbor positions using offset vector rotation
+/// Center of the chunk that owns tile (q, r)
+pub fn chunk_center_for_tile(q: i32, r: i32, rings: i32) -> (i32, i32) {
+    let (i, j) = chunk_lattice_index(q, r, rings);
+    chunk_lattice_center(i, j, rings)
+}
+
+/// Check whether tile (q, r) belongs to the chunk centered at (center_q, center_r)
+/// Only true for lattice centers, since a tile belongs to exactly one chunk
+pub fn chunk_owns_tile(q: i32, r: i32, center_q: i32, center_r: i32, rings: i32) -> bool {
+    chunk_center_for_tile(q, r, rings) == (center_q, center_r)
+}
+
+/// Calculate chunk neighbor positions on the chunk lattice
 /// Returns exactly 6 neighbor hex coordinates, one in each of the 6 directions
 /// 
-/// Uses the offset vector (rings, rings+1) for rings>0, or (1, 0) for rings=0, and rotates
-/// it 60 degrees clockwise 6 times. This ensures chunks are packed without gaps - 
-/// each direction has exactly one neighbor. The outer boundaries of adjacent chunks touch.
+/// The neighbor offsets are the six shortest lattice vectors: u = (rings, rings+1)
+/// and its rotations by 60 degrees. Adjacent chunks touch along a full side.
 /// 
 /// @param center_q - Center q coordinate
 /// @param center_r - Center r coordinate
@@ -26,47 +103,26 @@ pub fn calculate_chunk_radius(rings: i32) -> i32 {
 /// @returns JSON string with array of 6 neighbor coordinates: [{"q":0,"r":0},...]
 #[wasm_bindgen]
 pub fn calculate_chunk_neighbors(center_q: i32, center_r: i32, rings: i32) -> String {
-    let mut neighbors = Vec::new();
-    
-    // Base offset vector: (rings, rings+1) for rings>0, or (1, 0) for rings=0
-    let (mut offset_q, mut offset_r) = if rings == 0 {
-        (1, 0)
-    } else {
-        (rings, rings + 1)
-    };
-    
-    // Rotate the starting offset by -120 degrees (4 steps clockwise) to correct angular alignment
-    // This compensates for the 120-degree offset in the coordinate system
-    for _i in 0..4 {
-        let next_q = offset_q + offset_r;
-        let next_r = -offset_q;
-        offset_q = next_q;
-        offset_r = next_r;
-    }
-    
-    // Rotate the offset vector 60 degrees clockwise 6 times
-    // Rotation formula in axial coordinates for clockwise: (q, r) -> (q+r, -q)
-    let mut current_q = offset_q;
-    let mut current_r = offset_r;
+    let neighbors = chunk_neighbor_centers(center_q, center_r, rings);
+    coords_to_json(&neighbors)
+}
+
+/// Calculate the 6 neighbor chunk centers (see calculate_chunk_neighbors)
+/// 
+/// The index into the returned array is the chunk's side number: side k of this chunk
+/// faces neighbors[k], and that neighbor sees this chunk on its side (k + 3) % 6.
+/// Side 0 is the lattice step -v, and each following side is rotated 60 degrees clockwise.
+pub fn chunk_neighbor_centers(center_q: i32, center_r: i32, rings: i32) -> [(i32, i32); 6] {
+    let (_, (vq, vr)) = chunk_lattice_basis(rings);
+    let mut neighbors = [(0, 0); 6];
     
-    for _i in 0..6 {
-        // Add the current offset to the center
-        neighbors.push((center_q + current_q, center_r + current_r));
-        
-        // Rotate 60 degrees clockwise: (q, r) -> (q+r, -q)
-        let next_q = current_q + current_r;
-        let next_r = -current_q;
-        current_q = next_q;
-        current_r = next_r;
+    // Side k is -v rotated 60 degrees clockwise k times
+    for (side, neighbor) in neighbors.iter_mut().enumerate() {
+        let (offset_q, offset_r) = hex_rotate(-vq, -vr, side as i32);
+        *neighbor = (center_q + offset_q, center_r + offset_r);
     }
     
-    // Convert to JSON
-    let mut json_parts = Vec::new();
-    for (q, r) in neighbors {
-        json_parts.push(format!(r#"{{"q":{},"r":{}}}"#, q, r));
-    }
-    
-    format!("[{}]", json_parts.join(","))
+    neighbors
 }
 
 /// Find the immediate neighbor chunk of the current chunk that is nearest to the current tile
@@ -92,12 +148,7 @@ pub fn find_nearest_neighbor_chunk(
     let existing_chunks = parse_valid_terrain_json(&existing_chunks_json);
     
     // Calculate immediate neighbors
-    let neighbors_json = calculate_chunk_neighbors(current_chunk_q, current_chunk_r, rings);
-    let neighbors = parse_valid_terrain_json(&neighbors_json);
-    
-    if neighbors.is_empty() {
-        return "null".to_string();
-    }
+    let neighbors = chunk_neighbor_centers(current_chunk_q, current_chunk_r, rings);
     
     // Find which of the immediate neighbors is closest to the current tile (in hex distance)
     let mut nearest_neighbor: Option<(i32, i32)> = None;
@@ -261,6 +312,9 @@ pub fn disable_distant_chunks(
 /// Calculate which chunk contains a given tile
 /// Returns chunk position that contains the tile, or null if not found
 /// 
+/// The owning chunk is computed in closed form (see chunk_lattice_index); the position
+/// list only decides whether that chunk is known to the caller.
+/// 
 /// @param tile_q - Hex q coordinate of the tile
 /// @param tile_r - Hex r coordinate of the tile
 /// @param rings - Number of rings per chunk
@@ -273,39 +327,314 @@ pub fn calculate_chunk_for_tile(
     rings: i32,
     chunk_positions_json: String,
 ) -> String {
-    // Parse chunk positions
     let chunk_positions = parse_valid_terrain_json(&chunk_positions_json);
+    let (chunk_q, chunk_r) = chunk_center_for_tile(tile_q, tile_r, rings);
     
-    if chunk_positions.is_empty() {
-        return "null".to_string();
+    if chunk_positions.contains(&(chunk_q, chunk_r)) {
+        format!(r#"{{"q":{},"r":{}}}"#, chunk_q, chunk_r)
+    } else {
+        "null".to_string()
     }
+}
+
+/// Get the chunk that owns a tile, whether or not it has been created
+/// 
+/// @param tile_q - Hex q coordinate of the tile
+/// @param tile_r - Hex r coordinate of the tile
+/// @param rings - Number of rings per chunk
+/// @returns JSON string: {"q":0,"r":0,"i":0,"j":0} (center and lattice index)
+#[wasm_bindgen]
+pub fn get_chunk_for_tile(tile_q: i32, tile_r: i32, rings: i32) -> String {
+    let (i, j) = chunk_lattice_index(tile_q, tile_r, rings);
+    let (chunk_q, chunk_r) = chunk_lattice_center(i, j, rings);
+    format!(r#"{{"q":{},"r":{},"i":{},"j":{}}}"#, chunk_q, chunk_r, i, j)
+}
+
+/// Get all tiles owned by the chunk at a center, in ring order
+/// 
+/// @param center_q - Chunk center q coordinate (should be a lattice center)
+/// @param center_r - Chunk center r coordinate
+/// @param rings - Number of rings per chunk
+/// @returns JSON array: [{"q":0,"r":0},...]
+#[wasm_bindgen]
+pub fn get_chunk_hexes(center_q: i32, center_r: i32, rings: i32) -> String {
+    let hexes: Vec<(i32, i32)> = generate_hex_grid(rings.max(0), center_q, center_r)
+        .iter()
+        .map(|hex| (hex.q, hex.r))
+        .collect();
+    coords_to_json(&hexes)
+}
+
+/// Check whether a tile belongs to the chunk at a given center
+/// 
+/// @returns true if the chunk owns the tile
+#[wasm_bindgen]
+pub fn is_tile_in_chunk(tile_q: i32, tile_r: i32, center_q: i32, center_r: i32, rings: i32) -> bool {
+    chunk_owns_tile(tile_q, tile_r, center_q, center_r, rings)
+}
+
+/// Get the boundary hexes of a chunk that touch the neighbor chunk on the given side
+/// Hexes are returned in ring order (the order produced by cube_ring)
+pub fn chunk_border_hexes(center_q: i32, center_r: i32, rings: i32, side: usize) -> Vec<(i32, i32)> {
+    let (neighbor_q, neighbor_r) = chunk_neighbor_centers(center_q, center_r, rings)[side % 6];
+    let center = CubeCoord { q: center_q, r: center_r, s: -center_q - center_r };
     
-    let mut closest_chunk: Option<(i32, i32)> = None;
-    let mut min_distance = i32::MAX;
+    cube_ring(center, rings)
+        .into_iter()
+        .map(|cube| (cube.q, cube.r))
+        .filter(|&(q, r)| {
+            get_hex_neighbors(q, r)
+                .into_iter()
+                .any(|(nq, nr)| hex_distance(nq, nr, neighbor_q, neighbor_r) <= rings)
+        })
+        .collect()
+}
+
+/// Get all adjacent (inside, outside) hex pairs along one side of a chunk
+/// 
+/// **Learning Point**: The pairs are sorted in a canonical order that depends only on
+/// the two chunk centers, not on which of the two chunks asks. Both chunks therefore
+/// see the same list and pick the same exits from it.
+fn border_seam_pairs(center_q: i32, center_r: i32, rings: i32, side: usize) -> Vec<((i32, i32), (i32, i32))> {
+    let neighbor = chunk_neighbor_centers(center_q, center_r, rings)[side % 6];
+    let this_is_first = (center_q, center_r) < neighbor;
     
-    // Find chunk whose center is closest to the tile and within the chunk's boundary
-    for chunk_pos in &chunk_positions {
-        let distance = hex_distance(tile_q, tile_r, chunk_pos.0, chunk_pos.1);
-        
-        // If tile is exactly at chunk center, return immediately
-        if distance == 0 {
-            return format!(r#"{{"q":{},"r":{}}}"#, chunk_pos.0, chunk_pos.1);
-        }
-        
-        // Check if tile is within this chunk's boundary (distance <= rings)
-        if distance <= rings {
-            // If multiple chunks contain this tile (overlap at boundaries), prefer the closest center
-            if distance < min_distance {
-                min_distance = distance;
-                closest_chunk = Some(*chunk_pos);
+    let mut pairs = Vec::new();
+    for (q, r) in chunk_border_hexes(center_q, center_r, rings, side) {
+        for outside in get_hex_neighbors(q, r) {
+            if hex_distance(outside.0, outside.1, neighbor.0, neighbor.1) <= rings {
+                pairs.push(((q, r), outside));
             }
         }
     }
     
-    if let Some(chunk) = closest_chunk {
-        format!(r#"{{"q":{},"r":{}}}"#, chunk.0, chunk.1)
-    } else {
-        "null".to_string()
+    // Canonical key: (hex in the lower chunk, hex in the higher chunk)
+    pairs.sort_by_key(|&(inside, outside)| if this_is_first { (inside, outside) } else { (outside, inside) });
+    pairs
+}
+
+/// Deterministic hash for the seam between two chunk centers (order independent)
+fn seam_hash(world_seed: u64, a: (i32, i32), b: (i32, i32), salt: u64) -> u64 {
+    let (low, high) = if a < b { (a, b) } else { (b, a) };
+    let low_hash = hash_coords(world_seed ^ salt, low.0, low.1);
+    hash_coords(low_hash, high.0, high.1)
+}
+
+/// Road exit chosen on one side of a chunk
+pub struct BorderExit {
+    pub side: usize,
+    pub inside: (i32, i32),
+    pub outside: (i32, i32),
+    /// True if the exit continues a road already present on the neighbor's edge
+    pub matched: bool,
+}
+
+/// Choose the road exits on one side of a chunk
+/// 
+/// Roads already present on the neighbor's edge take precedence so the new chunk
+/// continues them. Otherwise `roads_per_border` exits are picked from evenly sized
+/// segments of the canonical seam, skipping hexes the neighbor has made impassable.
+pub fn select_side_exits(
+    world_seed: u64,
+    center_q: i32,
+    center_r: i32,
+    rings: i32,
+    side: usize,
+    roads_per_border: usize,
+    neighbor_edges: &HashMap<(i32, i32), TileType>,
+) -> Vec<BorderExit> {
+    let neighbor = chunk_neighbor_centers(center_q, center_r, rings)[side];
+    let pairs = border_seam_pairs(center_q, center_r, rings, side);
+    let mut exits: Vec<BorderExit> = Vec::new();
+    
+    // Continue roads that already reach the seam from the neighbor
+    for &(inside, outside) in &pairs {
+        if neighbor_edges.get(&outside) == Some(&TileType::Road) && !exits.iter().any(|exit| exit.outside == outside) {
+            exits.push(BorderExit { side, inside, outside, matched: true });
+        }
+    }
+    if !exits.is_empty() || roads_per_border == 0 || pairs.is_empty() {
+        return exits;
     }
+    
+    // Skip seam pairs that would run into water or buildings on a generated neighbor
+    let passable: Vec<((i32, i32), (i32, i32))> = pairs
+        .iter()
+        .copied()
+        .filter(|(_, outside)| {
+            !matches!(neighbor_edges.get(outside), Some(TileType::Water) | Some(TileType::Building))
+        })
+        .collect();
+    let candidates = if passable.is_empty() { pairs } else { passable };
+    
+    let count = roads_per_border.min(candidates.len());
+    let segment = candidates.len() / count;
+    for i in 0..count {
+        let offset = (seam_hash(world_seed, (center_q, center_r), neighbor, i as u64) % segment as u64) as usize;
+        let (inside, outside) = candidates[i * segment + offset];
+        exits.push(BorderExit { side, inside, outside, matched: false });
+    }
+    
+    exits
 }
 
+/// Get the boundary hexes of a chunk on one of its 6 sides
+/// Side k faces the k-th neighbor returned by calculate_chunk_neighbors
+/// 
+/// @param center_q - Chunk center q coordinate
+/// @param center_r - Chunk center r coordinate
+/// @param rings - Number of rings per chunk
+/// @param side - Side index (0-5)
+/// @returns JSON array of boundary hexes in ring order: [{"q":0,"r":0},...]
+#[wasm_bindgen]
+pub fn get_chunk_border_hexes(center_q: i32, center_r: i32, rings: i32, side: i32) -> String {
+    if !(0..6).contains(&side) {
+        return "[]".to_string();
+    }
+    
+    coords_to_json(&chunk_border_hexes(center_q, center_r, rings, side as usize))
+}
+
+/// Calculate road exit points on all 6 sides of a chunk
+/// 
+/// **Learning Point**: Exits are derived from the world seed and the seam between two
+/// chunk centers, so a chunk and its neighbor always agree on where roads cross,
+/// regardless of which one is generated first. When a neighbor was already generated, its edge tiles are passed in
+/// and any road reaching the seam is continued.
+/// 
+/// The returned exits can be passed straight to generate_road_network_mst as endpoints;
+/// that generator paves and connects every endpoint that is not occupied.
+/// 
+/// @param center_q - Chunk center q coordinate
+/// @param center_r - Chunk center r coordinate
+/// @param rings - Number of rings per chunk
+/// @param roads_per_border - Number of exits per side when the neighbor has no roads at the seam
+/// @param neighbor_edges_json - Edge tiles of generated neighbors: [{"q":0,"r":0,"tileType":2},...]
+/// @returns JSON string: {"exits":[{"side":0,"q":0,"r":0,"outsideQ":0,"outsideR":0,"matched":false},...]}
+#[wasm_bindgen]
+pub fn calculate_border_exits(
+    center_q: i32,
+    center_r: i32,
+    rings: i32,
+    roads_per_border: i32,
+    neighbor_edges_json: String,
+) -> String {
+    let mut neighbor_edges: HashMap<(i32, i32), TileType> = HashMap::new();
+    for object in json_objects(&neighbor_edges_json) {
+        if let (Some(q), Some(r), Some(tile_type)) = (
+            json_int_field(object, "q"),
+            json_int_field(object, "r"),
+            json_int_field(object, "tileType").and_then(TileType::from_i32),
+        ) {
+            neighbor_edges.insert((q, r), tile_type);
+        }
+    }
+    
+    let world_seed = WFC_STATE.lock().unwrap().world_seed();
+    let roads_per_border = roads_per_border.max(0) as usize;
+    let mut json_parts = Vec::new();
+    for side in 0..6 {
+        for exit in select_side_exits(world_seed, center_q, center_r, rings, side, roads_per_border, &neighbor_edges) {
+            json_parts.push(format!(
+                r#"{{"side":{},"q":{},"r":{},"outsideQ":{},"outsideR":{},"matched":{}}}"#,
+                exit.side, exit.inside.0, exit.inside.1, exit.outside.0, exit.outside.1, exit.matched
+            ));
+        }
+    }
+    
+    format!(r#"{{"exits":[{}]}}"#, json_parts.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generation::{generate_chunk_tiles, ChunkGenerationParams};
+    
+    /// Lattice indices searched for owners (wide enough to cover the test window)
+    const LATTICE_SPAN: i32 = 8;
+    
+    fn lattice_centers(rings: i32) -> Vec<(i32, i32)> {
+        (-LATTICE_SPAN..=LATTICE_SPAN)
+            .flat_map(|i| (-LATTICE_SPAN..=LATTICE_SPAN).map(move |j| chunk_lattice_center(i, j, rings)))
+            .collect()
+    }
+    
+    #[test]
+    fn every_tile_has_exactly_one_owner() {
+        for rings in 0..=6 {
+            let centers = lattice_centers(rings);
+            // Window off any lattice point, spanning several chunks in every direction
+            let (window_q, window_r) = chunk_lattice_center(1, -1, rings);
+            for hex in generate_hex_grid(3 * (2 * rings + 1), window_q + 1, window_r) {
+                let owners: Vec<(i32, i32)> = centers
+                    .iter()
+                    .copied()
+                    .filter(|&(q, r)| hex_distance(hex.q, hex.r, q, r) <= rings)
+                    .collect();
+                assert_eq!(owners.len(), 1, "rings {} tile ({}, {})", rings, hex.q, hex.r);
+                assert_eq!(chunk_center_for_tile(hex.q, hex.r, rings), owners[0]);
+                for &(q, r) in &centers {
+                    assert_eq!(chunk_owns_tile(hex.q, hex.r, q, r, rings), (q, r) == owners[0]);
+                }
+            }
+        }
+    }
+    
+    #[test]
+    fn center_tile_center_round_trip() {
+        for rings in 0..=6 {
+            for i in -3..=3 {
+                for j in -3..=3 {
+                    let (center_q, center_r) = chunk_lattice_center(i, j, rings);
+                    assert_eq!(chunk_lattice_index(center_q, center_r, rings), (i, j));
+                    let hexes = generate_hex_grid(rings, center_q, center_r);
+                    assert_eq!(hexes.len() as i32, chunk_tile_count(rings));
+                    for hex in hexes {
+                        assert_eq!(chunk_center_for_tile(hex.q, hex.r, rings), (center_q, center_r));
+                    }
+                }
+            }
+        }
+    }    
+    #[test]
+    fn adjacent_chunks_pair_their_exits_across_the_seam() {
+        const RINGS: i32 = 4;
+        let params = ChunkGenerationParams::from_json(r#"{"roadsPerBorder":2}"#);
+        let center = generate_chunk_tiles(7, 0, 0, RINGS, &params, &HashMap::new());
+        let center_tiles: HashMap<(i32, i32), TileType> = center.tiles.into_iter().collect();
+        
+        for (side, (q, r)) in chunk_neighbor_centers(0, 0, RINGS).into_iter().enumerate() {
+            let back_side = chunk_neighbor_centers(q, r, RINGS)
+                .iter()
+                .position(|&neighbor| neighbor == (0, 0))
+                .expect("neighbors are mutual");
+            let no_edges = HashMap::new();
+            let mut ours: Vec<((i32, i32), (i32, i32))> = select_side_exits(7, 0, 0, RINGS, side, 2, &no_edges)
+                .iter()
+                .map(|exit| (exit.inside, exit.outside))
+                .collect();
+            let mut theirs: Vec<((i32, i32), (i32, i32))> = select_side_exits(7, q, r, RINGS, back_side, 2, &no_edges)
+                .iter()
+                .map(|exit| (exit.outside, exit.inside))
+                .collect();
+            ours.sort();
+            theirs.sort();
+            assert_eq!(ours.len(), 2, "side {}", side);
+            assert_eq!(ours, theirs, "side {}", side);
+            
+            // Both generated chunks pave their half of every exit pair; lake hexes can't hold a road
+            let neighbor = generate_chunk_tiles(7, q, r, RINGS, &params, &HashMap::new());
+            let neighbor_tiles: HashMap<(i32, i32), TileType> = neighbor.tiles.into_iter().collect();
+            for (inside, outside) in ours {
+                assert_eq!(hex_distance(inside.0, inside.1, outside.0, outside.1), 1);
+                for (tiles, hex) in [(&center_tiles, inside), (&neighbor_tiles, outside)] {
+                    let tile_type = tiles.get(&hex).copied();
+                    assert!(
+                        tile_type == Some(TileType::Road) || tile_type == Some(TileType::Water),
+                        "side {} at {:?} is {:?}", side, hex, tile_type
+                    );
+                }
+            }
+        }
+    }
+}
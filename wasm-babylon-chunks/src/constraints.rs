@@ -0,0 +1,509 @@
+/// Layout constraints module
+/// 
+/// **Learning Point**: Text-to-layout generation parses the model output into a
+/// LayoutConstraints object (see types.ts). Converting those high-level constraints into
+/// tiles used to take several WASM round trips plus JSON juggling in TypeScript; here the
+/// whole pipeline (Voronoi terrain, grass ratio, roads, buildings) runs in one call and is
+/// seeded from the world seed, so the same constraints always give the same layout.
+
+use wasm_bindgen::prelude::*;
+use std::collections::{HashMap, HashSet};
+use crate::state::WFC_STATE;
+use crate::types::{HexCoord, TileType};
+use crate::hex_utils::{generate_hex_grid, get_hex_neighbors};
+use crate::voronoi::{pick_voronoi_seeds, assign_voronoi_regions};
+use crate::roads::grow_road_tree;
+use crate::buildings::{place_buildings, BuildingRecord, BuildingRules, BuildingSize, Clustering};
+use crate::seed::{chunk_seed, SeededRng};
+use crate::flow_field::{invalidate_all_flow_fields, invalidate_flow_fields_for_chunk};
+use crate::grid_shape::{GridShape, PlacedShape};
+use crate::json::{json_int_field, json_number_field, json_string_field, json_object_field, json_string_array_field};
+
+/// Largest grid the constraints may ask for (matches CONSTRAINTS.maxRings)
+const MAX_RINGS: i32 = 50;
+
+/// Share of the target road count used as growing tree seeds (matches CONSTRAINTS.road.seedPointRatio)
+pub const ROAD_SEED_RATIO: f64 = 0.25;
+
+/// Building density level (matches CONSTRAINTS.buildingDensity)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BuildingDensity {
+    Sparse,
+    Medium,
+    Dense,
+}
+
+impl BuildingDensity {
+    pub fn from_str(value: &str) -> Option<BuildingDensity> {
+        match value {
+            "sparse" => Some(BuildingDensity::Sparse),
+            "medium" => Some(BuildingDensity::Medium),
+            "dense" => Some(BuildingDensity::Dense),
+            _ => None,
+        }
+    }
+    
+    /// Fraction of road-fronting lots that receive a building
+    pub fn ratio(&self) -> f64 {
+        match self {
+            BuildingDensity::Sparse => 0.05,
+            BuildingDensity::Medium => 0.1,
+            BuildingDensity::Dense => 0.15,
+        }
+    }
+}
+
+/// Voronoi seed counts per region type
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct VoronoiSeedCounts {
+    pub forest: i32,
+    pub water: i32,
+    pub grass: i32,
+}
+
+impl VoronoiSeedCounts {
+    /// Drop excluded region types and boost the primary type
+    /// (same adjustments constraintsToPreConstraints made in TypeScript)
+    pub fn adjusted(self, exclude: &[TileType], primary: Option<TileType>) -> Self {
+        let mut counts = self;
+        match primary {
+            Some(TileType::Forest) => {
+                counts.forest = (counts.forest * 2).max(8);
+                counts.water = (counts.water / 2).max(1);
+                counts.grass = (counts.grass / 2).max(2);
+            },
+            Some(TileType::Water) => {
+                counts.water = (counts.water * 2).max(6);
+                counts.forest = (counts.forest / 2).max(1);
+                counts.grass = (counts.grass / 2).max(2);
+            },
+            Some(TileType::Grass) => {
+                counts.grass = (counts.grass * 2).max(10);
+                counts.forest = (counts.forest / 2).max(1);
+                counts.water = (counts.water / 2).max(1);
+            },
+            _ => {},
+        }
+        
+        // Exclusions win over the primary boost
+        for tile_type in exclude {
+            match tile_type {
+                TileType::Forest => counts.forest = 0,
+                TileType::Water => counts.water = 0,
+                TileType::Grass => counts.grass = 0,
+                _ => {},
+            }
+        }
+        counts
+    }
+}
+
+/// Parsed layout constraints (mirrors LayoutConstraints in types.ts)
+/// 
+/// Parsed from JSON: {"rings":5,"centerQ":0,"centerR":0,"buildingDensity":"medium",
+///                    "clustering":"random","grassRatio":0.3,"buildingSizeHint":"medium",
+///                    "voronoiSeeds":{"forest":4,"water":3,"grass":6},"roadDensity":0.1,
+///                    "buildingRules":{"minAdjacentRoads":1},"buildingCount":12,
+///                    "excludeTileTypes":["water"],"primaryTileType":"forest",
+///                    "shape":{"type":"rectangle","width":20,"height":12}}
+/// "maxLayer" is accepted as a deprecated alias for "rings". A hexagon "shape" sets
+/// rings and center; other shapes (see PlacedShape::from_json) replace the hexagon grid.
+pub struct LayoutConstraints {
+    pub rings: i32,
+    pub center: (i32, i32),
+    pub building_density: BuildingDensity,
+    /// Minimum fraction of grass in the terrain before roads and buildings are laid
+    pub grass_ratio: f64,
+    /// Footprint size, clustering and road frontage rules for buildings
+    pub building_rules: BuildingRules,
+    pub voronoi_seeds: VoronoiSeedCounts,
+    /// Target fraction of land hexes that are roads
+    pub road_density: f64,
+    /// Exact building count; overrides building_density when present
+    pub building_count: Option<usize>,
+    pub exclude_tile_types: Vec<TileType>,
+    pub primary_tile_type: Option<TileType>,
+    /// Non-hexagon grid shape; None for the hexagon of `rings` around `center`
+    pub shape: Option<PlacedShape>,
+}
+
+impl Default for LayoutConstraints {
+    fn default() -> Self {
+        LayoutConstraints {
+            rings: 5,
+            center: (0, 0),
+            building_density: BuildingDensity::Medium,
+            grass_ratio: 0.3,
+            building_rules: BuildingRules::default(),
+            voronoi_seeds: VoronoiSeedCounts { forest: 4, water: 3, grass: 6 },
+            road_density: 0.1,
+            building_count: None,
+            exclude_tile_types: Vec::new(),
+            primary_tile_type: None,
+            shape: None,
+        }
+    }
+}
+
+impl LayoutConstraints {
+    pub fn from_json(constraints_json: &str) -> Self {
+        let defaults = LayoutConstraints::default();
+        
+        let voronoi_seeds = match json_object_field(constraints_json, "voronoiSeeds") {
+            Some(seeds_json) => VoronoiSeedCounts {
+                forest: json_int_field(seeds_json, "forest").unwrap_or(defaults.voronoi_seeds.forest),
+                water: json_int_field(seeds_json, "water").unwrap_or(defaults.voronoi_seeds.water),
+                grass: json_int_field(seeds_json, "grass").unwrap_or(defaults.voronoi_seeds.grass),
+            },
+            None => defaults.voronoi_seeds,
+        };
+        
+        // Nested buildingRules carries road frontage; size and clustering are top level
+        let mut building_rules = BuildingRules::from_json(json_object_field(constraints_json, "buildingRules").unwrap_or("{}"));
+        if let Some(size_hint) = json_string_field(constraints_json, "buildingSizeHint").and_then(|value| BuildingSize::from_str(&value)) {
+            building_rules.size_hint = size_hint;
+        }
+        if let Some(clustering) = json_string_field(constraints_json, "clustering").and_then(|value| Clustering::from_str(&value)) {
+            building_rules.clustering = clustering;
+        }
+        
+        let mut constraints = LayoutConstraints {
+            rings: json_int_field(constraints_json, "rings")
+                .or_else(|| json_int_field(constraints_json, "maxLayer"))
+                .unwrap_or(defaults.rings)
+                .clamp(0, MAX_RINGS),
+            center: (
+                json_int_field(constraints_json, "centerQ").unwrap_or(defaults.center.0),
+                json_int_field(constraints_json, "centerR").unwrap_or(defaults.center.1),
+            ),
+            building_density: json_string_field(constraints_json, "buildingDensity")
+                .and_then(|value| BuildingDensity::from_str(&value))
+                .unwrap_or(defaults.building_density),
+            grass_ratio: json_number_field(constraints_json, "grassRatio")
+                .unwrap_or(defaults.grass_ratio)
+                .clamp(0.0, 1.0),
+            building_rules,
+            voronoi_seeds,
+            road_density: json_number_field(constraints_json, "roadDensity")
+                .unwrap_or(defaults.road_density)
+                .clamp(0.0, 1.0),
+            building_count: json_int_field(constraints_json, "buildingCount").map(|count| count.max(0) as usize),
+            exclude_tile_types: json_string_array_field(constraints_json, "excludeTileTypes")
+                .iter()
+                .filter_map(|name| TileType::from_name(name))
+                .collect(),
+            primary_tile_type: json_string_field(constraints_json, "primaryTileType")
+                .and_then(|name| TileType::from_name(&name)),
+            shape: json_object_field(constraints_json, "shape").and_then(PlacedShape::from_json),
+        };
+        
+        // A hexagon is the regular chunk grid
+        if let Some(PlacedShape { shape: GridShape::Hexagon { radius }, origin }) = constraints.shape {
+            constraints.rings = radius.min(MAX_RINGS);
+            constraints.center = origin;
+            constraints.shape = None;
+        }
+        constraints
+    }
+    
+    /// Grid hexes in generation order (ring order for the hexagon)
+    pub fn grid_hexes(&self) -> Vec<(i32, i32)> {
+        match &self.shape {
+            Some(shape) => shape.hexes(),
+            None => generate_hex_grid(self.rings, self.center.0, self.center.1)
+                .iter()
+                .map(|hex| (hex.q, hex.r))
+                .collect(),
+        }
+    }
+    
+    /// Hex whose chunk seed seeds the layout (hexagon center or shape origin)
+    pub fn seed_hex(&self) -> (i32, i32) {
+        self.shape.map_or(self.center, |shape| shape.origin)
+    }
+}
+
+/// What the pipeline produced, for logging and UI feedback
+pub struct LayoutSummary {
+    pub voronoi_seeds: VoronoiSeedCounts,
+    /// Forest hexes turned into grass to reach the grass ratio
+    pub grass_converted: usize,
+    pub road_count: usize,
+    pub target_building_count: usize,
+    pub building_count: usize,
+}
+
+/// Output of the constraints pipeline
+pub struct ConstrainedLayout {
+    /// Tiles in grid order (ring order for hexagons, center first)
+    pub tiles: Vec<((i32, i32), TileType)>,
+    /// Buildings in placement order
+    pub buildings: Vec<BuildingRecord>,
+    /// Water hexes of the terrain (roads and buildings never cover them here)
+    pub water: Vec<(i32, i32)>,
+    pub summary: LayoutSummary,
+}
+
+/// Turn forest into grass until the grass ratio is reached
+/// 
+/// Forest hexes bordering grass are converted first, so grass regions grow outward
+/// instead of appearing as scattered single hexes. Excluded grass is never added.
+pub fn enforce_grass_ratio(
+    hexes: &[(i32, i32)],
+    terrain: &mut HashMap<(i32, i32), TileType>,
+    grass_ratio: f64,
+    rng: &mut SeededRng,
+) -> usize {
+    let target = (hexes.len() as f64 * grass_ratio).ceil() as usize;
+    let mut grass_count = hexes.iter().filter(|hex| terrain.get(hex) == Some(&TileType::Grass)).count();
+    let mut converted = 0;
+    
+    while grass_count < target {
+        let forest: Vec<(i32, i32)> = hexes
+            .iter()
+            .copied()
+            .filter(|hex| terrain.get(hex) == Some(&TileType::Forest))
+            .collect();
+        if forest.is_empty() {
+            break;
+        }
+        let frontier: Vec<(i32, i32)> = forest
+            .iter()
+            .copied()
+            .filter(|&(q, r)| {
+                get_hex_neighbors(q, r)
+                    .iter()
+                    .any(|neighbor| terrain.get(neighbor) == Some(&TileType::Grass))
+            })
+            .collect();
+        let candidates = if frontier.is_empty() { &forest } else { &frontier };
+        
+        let hex = candidates[rng.next_index(candidates.len())];
+        terrain.insert(hex, TileType::Grass);
+        grass_count += 1;
+        converted += 1;
+    }
+    
+    converted
+}
+
+/// Generate a complete layout from parsed constraints
+/// 
+/// Steps:
+/// 1. Voronoi terrain with the seed counts adjusted for excluded and primary tile types
+/// 2. Forest converted to grass until the grass ratio is met, then the pinned tiles on top
+/// 3. A connected road tree grown from the pinned roads and seeded points over land (never
+///    water), never paving other pinned hexes
+/// 4. Building footprints on unpinned land fronting the roads, counted from buildingCount
+///    or the density level
+pub fn generate_constrained_layout(
+    world_seed: u64,
+    constraints: &LayoutConstraints,
+    pinned: &HashMap<(i32, i32), TileType>,
+) -> ConstrainedLayout {
+    let hexes = constraints.grid_hexes();
+    let hex_grid: Vec<HexCoord> = hexes.iter().map(|&(q, r)| HexCoord { q, r }).collect();
+    let (seed_q, seed_r) = constraints.seed_hex();
+    let mut rng = SeededRng::new(chunk_seed(world_seed, seed_q, seed_r));
+    
+    // Step 1: Voronoi terrain
+    let voronoi_seeds = constraints
+        .voronoi_seeds
+        .adjusted(&constraints.exclude_tile_types, constraints.primary_tile_type);
+    let mut seeds = pick_voronoi_seeds(&hexes, voronoi_seeds.forest, voronoi_seeds.water, voronoi_seeds.grass, &mut rng);
+    if seeds.is_empty() {
+        seeds = pick_voronoi_seeds(&hexes, 0, 0, 1, &mut rng);
+    }
+    let mut terrain: HashMap<(i32, i32), TileType> = assign_voronoi_regions(&hex_grid, &seeds).into_iter().collect();
+    
+    // Step 2: Grass ratio
+    let grass_converted = if constraints.exclude_tile_types.contains(&TileType::Grass) {
+        0
+    } else {
+        enforce_grass_ratio(&hexes, &mut terrain, constraints.grass_ratio, &mut rng)
+    };
+    
+    // Pinned tiles (pre-constraints, placed prefabs) replace the generated terrain
+    let mut pinned: Vec<((i32, i32), TileType)> = pinned
+        .iter()
+        .filter(|(hex, _)| terrain.contains_key(hex))
+        .map(|(hex, tile_type)| (*hex, *tile_type))
+        .collect();
+    pinned.sort_by_key(|(hex, _)| *hex);
+    terrain.extend(pinned.iter().copied());
+    let pinned_roads: Vec<(i32, i32)> = pinned
+        .iter()
+        .filter(|(_, tile_type)| *tile_type == TileType::Road)
+        .map(|(hex, _)| *hex)
+        .collect();
+    let occupied: HashSet<(i32, i32)> = pinned
+        .iter()
+        .filter(|(_, tile_type)| *tile_type != TileType::Road)
+        .map(|(hex, _)| *hex)
+        .collect();
+    
+    // Step 3: Roads on unpinned land, joined to the pinned roads
+    let land: Vec<(i32, i32)> = hexes
+        .iter()
+        .copied()
+        .filter(|hex| !occupied.contains(hex))
+        .filter(|hex| matches!(terrain.get(hex), Some(TileType::Grass) | Some(TileType::Forest) | Some(TileType::Road)))
+        .collect();
+    let land_set: HashSet<(i32, i32)> = land.iter().copied().collect();
+    let target_road_count = (land.len() as f64 * constraints.road_density).floor() as usize;
+    let seed_count = ((target_road_count as f64 * ROAD_SEED_RATIO).floor() as usize).max(1);
+    let mut shuffled_land = land.clone();
+    rng.shuffle(&mut shuffled_land);
+    shuffled_land.truncate(seed_count);
+    let road_seeds: Vec<(i32, i32)> = pinned_roads.iter().copied().chain(shuffled_land).collect();
+    let roads = if target_road_count > 0 || !pinned_roads.is_empty() {
+        grow_road_tree(&road_seeds, &land_set, target_road_count)
+    } else {
+        HashSet::new()
+    };
+    for hex in &roads {
+        terrain.insert(*hex, TileType::Road);
+    }
+    
+    // Step 4: Buildings on the remaining land fronting a road
+    let buildable: HashSet<(i32, i32)> = land.iter().copied().filter(|hex| !roads.contains(hex)).collect();
+    let target_building_count = constraints.building_count.unwrap_or_else(|| {
+        let lots = buildable
+            .iter()
+            .filter(|&&(q, r)| {
+                let adjacent_roads = get_hex_neighbors(q, r).iter().filter(|neighbor| roads.contains(neighbor)).count();
+                adjacent_roads > 0 && adjacent_roads >= constraints.building_rules.min_adjacent_roads
+            })
+            .count();
+        (lots as f64 * constraints.building_density.ratio()).floor() as usize
+    });
+    let buildings = place_buildings(&buildable, &roads, &constraints.building_rules, target_building_count, &mut rng);
+    for building in &buildings {
+        for hex in &building.hexes {
+            terrain.insert(*hex, TileType::Building);
+        }
+    }
+    
+    ConstrainedLayout {
+        tiles: hexes
+            .iter()
+            .filter_map(|hex| terrain.get(hex).map(|&tile_type| (*hex, tile_type)))
+            .collect(),
+        summary: LayoutSummary {
+            voronoi_seeds,
+            grass_converted,
+            road_count: roads.len(),
+            target_building_count,
+            building_count: buildings.len(),
+        },
+        buildings,
+        water: hexes
+            .iter()
+            .copied()
+            .filter(|hex| terrain.get(hex) == Some(&TileType::Water))
+            .collect(),
+    }
+}
+
+/// Generate a layout end to end from LayoutConstraints and store it in the layout state
+/// 
+/// **Learning Point**: Replaces constraintsToPreConstraints in layoutGeneration.ts.
+/// The layout is stored as a chunk at the constraints' center (replacing any chunk
+/// there), so get_tile_at, validate_layout and the persistence exports see it directly.
+/// Non-hexagon shapes are written to the grid without a chunk record.
+/// Pre-constraints inside the grid are fixed before roads and buildings are laid: roads
+/// connect to pre-constrained roads and never pave other pre-constrained hexes.
+/// 
+/// @param constraints_json - JSON object (see LayoutConstraints::from_json), "{}" for defaults
+/// @returns JSON string: {"tiles":[{"q":0,"r":0,"tileType":0},...],"buildings":[...],"summary":{"shape":"hexagon",
+///          "rings":5,"tileCount":91,"counts":{"grass":0,...},"voronoiSeeds":{"forest":4,"water":3,"grass":6},
+///          "grassConverted":0,"roads":0,"buildings":0,"targetBuildings":0}}
+///          ("buildings" uses the generate_buildings format and is stored with the chunk)
+#[wasm_bindgen]
+pub fn generate_chunk_from_constraints(constraints_json: String) -> String {
+    let constraints = LayoutConstraints::from_json(&constraints_json);
+    let mut state = WFC_STATE.lock().unwrap();
+    
+    let pre_constraints: HashMap<(i32, i32), TileType> = state.pre_constraints().collect();
+    let ConstrainedLayout { tiles, buildings, water, summary } = generate_constrained_layout(state.world_seed(), &constraints, &pre_constraints);
+    
+    let mut counts = [0usize; 5];
+    let mut tile_parts = Vec::with_capacity(tiles.len());
+    for ((q, r), tile_type) in &tiles {
+        counts[*tile_type as usize] += 1;
+        tile_parts.push(format!(r#"{{"q":{},"r":{},"tileType":{}}}"#, q, r, *tile_type as i32));
+    }
+    let count_parts: Vec<String> = (0..5)
+        .filter_map(|index| TileType::from_i32(index as i32).map(|tile_type| (index, tile_type)))
+        .map(|(index, tile_type)| format!(r#""{}":{}"#, tile_type.as_str(), counts[index]))
+        .collect();
+    
+    let summary_json = format!(
+        r#"{{"shape":"{}","rings":{},"tileCount":{},"counts":{{{}}},"voronoiSeeds":{{"forest":{},"water":{},"grass":{}}},"grassConverted":{},"roads":{},"buildings":{},"targetBuildings":{}}}"#,
+        constraints.shape.map_or("hexagon", |shape| shape.type_name()),
+        constraints.rings,
+        tiles.len(),
+        count_parts.join(","),
+        summary.voronoi_seeds.forest,
+        summary.voronoi_seeds.water,
+        summary.voronoi_seeds.grass,
+        summary.grass_converted,
+        summary.road_count,
+        summary.building_count,
+        summary.target_building_count
+    );
+    
+    let building_parts: Vec<String> = buildings
+        .iter()
+        .enumerate()
+        .map(|(id, building)| building.to_json(id))
+        .collect();
+    
+    if constraints.shape.is_some() {
+        // Shaped maps are whole layouts, not streaming chunks: tiles go straight into the grid
+        for ((q, r), tile_type) in tiles {
+            state.insert_tile(q, r, tile_type);
+        }
+        drop(state);
+        invalidate_all_flow_fields();
+    } else {
+        state.insert_chunk(constraints.center, constraints.rings, tiles, buildings, water.into_iter().collect());
+        drop(state);
+        invalidate_flow_fields_for_chunk(constraints.center, constraints.rings);
+    }
+    format!(
+        r#"{{"tiles":[{}],"buildings":[{}],"summary":{}}}"#,
+        tile_parts.join(","),
+        building_parts.join(","),
+        summary_json
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn pinned_hexes_survive_roads_and_buildings() {
+        let constraints = LayoutConstraints::from_json(r#"{"rings":6,"buildingDensity":"dense","roadDensity":0.3}"#);
+        assert_eq!(constraints.building_density, BuildingDensity::Dense);
+        let mut pinned: HashMap<(i32, i32), TileType> = HashMap::new();
+        for (q, r) in [(0, 0), (1, 0), (0, 1), (-2, 3)] {
+            pinned.insert((q, r), TileType::Grass);
+        }
+        pinned.insert((3, -1), TileType::Road);
+        
+        let layout = generate_constrained_layout(11, &constraints, &pinned);
+        let tiles: HashMap<(i32, i32), TileType> = layout.tiles.iter().copied().collect();
+        for (hex, tile_type) in &pinned {
+            assert_eq!(tiles.get(hex), Some(tile_type), "pinned hex {:?}", hex);
+        }
+        // Every recorded building is still standing on the final tiles
+        for building in &layout.buildings {
+            for hex in &building.hexes {
+                assert_eq!(tiles.get(hex), Some(&TileType::Building));
+                assert!(!pinned.contains_key(hex));
+            }
+        }
+    }
+}
@@ -0,0 +1,440 @@
+/// Layout edit module
+/// 
+/// **Learning Point**: Every edit is recorded as a list of per-hex before/after values
+/// (tile and pre-constraint). Undo writes the "before" values back, redo writes the
+/// "after" values again, so any operation - a single tile, a filled area, a flood fill
+/// plus the surroundings it regenerated - is reversed in one step without re-running anything.
+/// 
+/// Edits can optionally pin the new tiles as pre-constraints (so regenerating the chunk
+/// keeps them) and regenerate the tiles around the edited hexes with inpaint_region,
+/// which holds the edited hexes and everything beyond the window fixed.
+
+use wasm_bindgen::prelude::*;
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use crate::state::{WfcState, WFC_STATE};
+use crate::types::TileType;
+use crate::hex_utils::{axial_to_cube, cube_ring, generate_hex_grid, get_hex_neighbors, parse_valid_terrain_json};
+use crate::constraints::LayoutConstraints;
+use crate::buildings::BuildingRecord;
+use crate::inpaint::inpaint_region;
+use crate::seed::{chunk_seed, SeededRng};
+use crate::flow_field::invalidate_flow_fields_for_hexes;
+use crate::json::{json_bool_field, json_int_field, json_objects};
+
+/// Number of operations kept for undo (oldest are dropped first)
+const MAX_HISTORY: usize = 100;
+
+/// Largest regeneration radius around edited hexes
+pub const MAX_REGENERATE_RADIUS: i32 = 8;
+
+/// Default flood fill limit (keeps a fill on an open world from running away)
+const DEFAULT_FLOOD_FILL_LIMIT: i32 = 4096;
+
+/// One reversible hex change (None means no tile / no pre-constraint)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HexEdit {
+    pub hex: (i32, i32),
+    pub tile_before: Option<TileType>,
+    pub tile_after: Option<TileType>,
+    pub constraint_before: Option<TileType>,
+    pub constraint_after: Option<TileType>,
+}
+
+/// One undoable operation
+#[derive(Clone)]
+pub struct EditOp {
+    pub label: &'static str,
+    pub edits: Vec<HexEdit>,
+    /// Building records the operation added to chunks
+    pub placed: Vec<BuildingRecord>,
+    /// Building records the operation removed (their footprint was edited)
+    pub dropped: Vec<BuildingRecord>,
+}
+
+impl EditOp {
+    /// Hexes touched by the operation, sorted
+    pub fn hexes(&self) -> Vec<(i32, i32)> {
+        let hexes: BTreeSet<(i32, i32)> = self.edits.iter().map(|edit| edit.hex).collect();
+        hexes.into_iter().collect()
+    }
+}
+
+/// Undo and redo stacks
+pub struct EditHistory {
+    undo: Vec<EditOp>,
+    redo: Vec<EditOp>,
+}
+
+impl EditHistory {
+    pub fn new() -> Self {
+        EditHistory {
+            undo: Vec::new(),
+            redo: Vec::new(),
+        }
+    }
+    
+    /// Record a new operation; a new edit makes the undone operations unreachable
+    pub fn push(&mut self, op: EditOp) {
+        self.redo.clear();
+        self.push_undone(op);
+    }
+    
+    /// Put an operation back on the undo stack without touching the redo stack
+    pub fn push_undone(&mut self, op: EditOp) {
+        self.undo.push(op);
+        if self.undo.len() > MAX_HISTORY {
+            self.undo.remove(0);
+        }
+    }
+    
+    pub fn pop_undo(&mut self) -> Option<EditOp> {
+        self.undo.pop()
+    }
+    
+    pub fn push_redo(&mut self, op: EditOp) {
+        self.redo.push(op);
+    }
+    
+    pub fn pop_redo(&mut self) -> Option<EditOp> {
+        self.redo.pop()
+    }
+    
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+    
+    /// Drop every operation that touched a hex matching `touches` (their before and
+    /// after values no longer describe the grid there)
+    pub fn forget_touching(&mut self, touches: impl Fn((i32, i32)) -> bool) {
+        let untouched = |op: &EditOp| !op.edits.iter().any(|edit| touches(edit.hex));
+        self.undo.retain(untouched);
+        self.redo.retain(untouched);
+    }
+    
+    /// Labels of undoable operations, most recent first
+    pub fn undo_labels(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.undo.iter().rev().map(|op| op.label)
+    }
+    
+    /// Labels of redoable operations, next redo first
+    pub fn redo_labels(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.redo.iter().rev().map(|op| op.label)
+    }
+}
+
+/// Edit options
+/// 
+/// Parsed from JSON: {"pin":false,"regenerateRadius":0,"maxHexes":4096, ...LayoutConstraints fields}
+/// The LayoutConstraints fields only steer the regeneration around the edit.
+pub struct EditOptions {
+    /// Also store edited tiles as pre-constraints (erasing removes the pre-constraint)
+    pub pin: bool,
+    /// Regenerate the loaded tiles within this distance of the edited hexes (0 disables)
+    pub regenerate_radius: i32,
+    /// Flood fill limit
+    pub max_hexes: usize,
+    /// Terrain, road and building settings for the regenerated window
+    pub constraints: LayoutConstraints,
+}
+
+impl EditOptions {
+    pub fn from_json(options_json: &str) -> Self {
+        EditOptions {
+            pin: json_bool_field(options_json, "pin").unwrap_or(false),
+            regenerate_radius: json_int_field(options_json, "regenerateRadius")
+                .unwrap_or(0)
+                .clamp(0, MAX_REGENERATE_RADIUS),
+            max_hexes: json_int_field(options_json, "maxHexes")
+                .unwrap_or(DEFAULT_FLOOD_FILL_LIMIT)
+                .max(1) as usize,
+            constraints: LayoutConstraints::from_json(options_json),
+        }
+    }
+}
+
+/// Turn target values into hex edits against the current state
+/// Later targets for the same hex win; edits that change nothing are dropped
+fn plan_edits(state: &WfcState, targets: &[((i32, i32), Option<TileType>)], pin: bool) -> Vec<HexEdit> {
+    let mut latest: HashMap<(i32, i32), Option<TileType>> = HashMap::new();
+    for &(hex, tile) in targets {
+        latest.insert(hex, tile);
+    }
+    let mut hexes: Vec<(i32, i32)> = latest.keys().copied().collect();
+    hexes.sort();
+    
+    hexes
+        .into_iter()
+        .map(|(q, r)| {
+            let constraint_before = state.get_pre_constraint(q, r);
+            HexEdit {
+                hex: (q, r),
+                tile_before: state.get_tile(q, r),
+                tile_after: latest[&(q, r)],
+                constraint_before,
+                constraint_after: if pin { latest[&(q, r)] } else { constraint_before },
+            }
+        })
+        .filter(|edit| edit.tile_before != edit.tile_after || edit.constraint_before != edit.constraint_after)
+        .collect()
+}
+
+/// Plan, write and record one edit operation
+/// `placed` are the buildings whose footprints the targets lay down; they are recorded
+/// with their chunks together with the buildings of a regenerated window, and buildings
+/// whose footprint the edit breaks are dropped from their chunks
+/// Returns the recorded operation (None if nothing changed)
+pub fn run_edit(
+    state: &mut WfcState,
+    label: &'static str,
+    targets: &[((i32, i32), Option<TileType>)],
+    mut placed: Vec<BuildingRecord>,
+    options: &EditOptions,
+) -> Option<EditOp> {
+    let mut edits = plan_edits(state, targets, options.pin);
+    if edits.is_empty() {
+        return None;
+    }
+    
+    let edited: HashSet<(i32, i32)> = edits.iter().map(|edit| edit.hex).collect();
+    let window: HashSet<(i32, i32)> = if options.regenerate_radius > 0 {
+        edited
+            .iter()
+            .flat_map(|&(q, r)| generate_hex_grid(options.regenerate_radius, q, r))
+            .map(|hex| (hex.q, hex.r))
+            .filter(|&(q, r)| !edited.contains(&(q, r)) && state.get_tile(q, r).is_some())
+            .collect()
+    } else {
+        HashSet::new()
+    };
+    
+    let mut dropped = state.write_hex_edits(&edits, true);
+    
+    // Regenerate the window around the edit; the edited hexes are its fixed boundary
+    if !window.is_empty() {
+        let anchor = edited.iter().min().copied().unwrap_or_default();
+        let mut rng = SeededRng::new(chunk_seed(state.world_seed(), anchor.0, anchor.1));
+        let inpainted = inpaint_region(state, &window, &options.constraints, &mut rng);
+        let regenerated: Vec<((i32, i32), Option<TileType>)> = inpainted
+            .tiles
+            .into_iter()
+            .map(|(hex, tile_type)| (hex, Some(tile_type)))
+            .collect();
+        // Regenerated tiles are generated, not authored, so they are never pinned
+        let regenerated_edits = plan_edits(state, &regenerated, false);
+        dropped.extend(state.write_hex_edits(&regenerated_edits, true));
+        edits.extend(regenerated_edits);
+        placed.extend(inpainted.buildings);
+        state.mark_water(&inpainted.summary.bridges);
+    }
+    state.add_building_records(&placed);
+    
+    let op = EditOp { label, edits, placed, dropped };
+    state.record_edit(op.clone());
+    Some(op)
+}
+
+/// Hexes of the same tile type connected to a start hex (breadth first, up to `limit`)
+fn flood_region(state: &WfcState, start: (i32, i32), limit: usize) -> Vec<(i32, i32)> {
+    let Some(tile_type) = state.get_tile(start.0, start.1) else {
+        return Vec::new();
+    };
+    
+    let mut visited = HashSet::from([start]);
+    let mut queue = VecDeque::from([start]);
+    let mut region = Vec::new();
+    while let Some((q, r)) = queue.pop_front() {
+        region.push((q, r));
+        if region.len() >= limit {
+            break;
+        }
+        for neighbor in get_hex_neighbors(q, r) {
+            if state.get_tile(neighbor.0, neighbor.1) == Some(tile_type) && visited.insert(neighbor) {
+                queue.push_back(neighbor);
+            }
+        }
+    }
+    region
+}
+
+/// Lock the state, run an edit and serialize the result
+pub fn edit_and_report(label: &'static str, options_json: &str, targets: impl FnOnce(&WfcState) -> Vec<((i32, i32), Option<TileType>)>) -> String {
+    let options = EditOptions::from_json(options_json);
+    let mut state = WFC_STATE.lock().unwrap();
+    let targets = targets(&state);
+    let Some(op) = run_edit(&mut state, label, &targets, Vec::new(), &options) else {
+        return r#"{"changed":[],"regenerated":0}"#.to_string();
+    };
+    
+    let hexes = op.hexes();
+    let changed_json = changes_to_json(&state, &hexes);
+    drop(state);
+    
+    invalidate_flow_fields_for_hexes(&hexes);
+    let targeted: HashSet<(i32, i32)> = targets.iter().map(|(hex, _)| *hex).collect();
+    let regenerated = hexes.iter().filter(|hex| !targeted.contains(hex)).count();
+    format!(r#"{{"changed":{},"regenerated":{}}}"#, changed_json, regenerated)
+}
+
+/// Current tile of each hex as JSON: [{"q":0,"r":0,"tileType":2},...] (-1 for no tile)
+pub fn changes_to_json(state: &WfcState, hexes: &[(i32, i32)]) -> String {
+    let mut json_parts = Vec::new();
+    for &(q, r) in hexes {
+        let tile_type = state.get_tile(q, r).map_or(-1, |tile| tile as i32);
+        json_parts.push(format!(r#"{{"q":{},"r":{},"tileType":{}}}"#, q, r, tile_type));
+    }
+    format!("[{}]", json_parts.join(","))
+}
+
+/// Set tiles (creating them where the grid is empty)
+/// 
+/// @param tiles_json - JSON array: [{"q":0,"r":0,"tileType":2},...] (invalid tile types are skipped)
+/// @param options_json - JSON object (see EditOptions::from_json), "{}" for defaults
+/// @returns JSON string: {"changed":[{"q":0,"r":0,"tileType":2},...],"regenerated":0}
+///          ("changed" lists edited and regenerated hexes with their new tile, -1 if erased)
+#[wasm_bindgen]
+pub fn edit_set_tiles(tiles_json: String, options_json: String) -> String {
+    edit_and_report("set_tiles", &options_json, |_| {
+        json_objects(&tiles_json)
+            .filter_map(|object| {
+                let q = json_int_field(object, "q")?;
+                let r = json_int_field(object, "r")?;
+                let tile_type = json_int_field(object, "tileType").and_then(TileType::from_i32)?;
+                Some(((q, r), Some(tile_type)))
+            })
+            .collect()
+    })
+}
+
+/// Erase tiles from the grid
+/// 
+/// @param hexes_json - JSON array: [{"q":0,"r":0},...]
+/// @param options_json - JSON object (see EditOptions::from_json); with "pin" the
+///                       pre-constraints of the hexes are removed too
+/// @returns JSON string (see edit_set_tiles)
+#[wasm_bindgen]
+pub fn edit_erase_tiles(hexes_json: String, options_json: String) -> String {
+    edit_and_report("erase_tiles", &options_json, |_| {
+        parse_valid_terrain_json(&hexes_json).into_iter().map(|hex| (hex, None)).collect()
+    })
+}
+
+/// Paint the existing tiles on one ring around a hex
+/// 
+/// @param center_q - Ring center q coordinate
+/// @param center_r - Ring center r coordinate
+/// @param radius - Ring distance from the center (0 paints the center only)
+/// @param tile_type - Tile type as i32 (0-4, matching TileType enum)
+/// @param options_json - JSON object (see EditOptions::from_json)
+/// @returns JSON string (see edit_set_tiles); nothing changes for an invalid tile type
+#[wasm_bindgen]
+pub fn edit_fill_ring(center_q: i32, center_r: i32, radius: i32, tile_type: i32, options_json: String) -> String {
+    let tile = TileType::from_i32(tile_type);
+    edit_and_report("fill_ring", &options_json, |state| {
+        let Some(tile) = tile else {
+            return Vec::new();
+        };
+        cube_ring(axial_to_cube(center_q, center_r), radius.max(0))
+            .iter()
+            .filter(|hex| state.get_tile(hex.q, hex.r).is_some())
+            .map(|hex| ((hex.q, hex.r), Some(tile)))
+            .collect()
+    })
+}
+
+/// Paint the existing tiles within a distance of a hex
+/// 
+/// @param center_q - Area center q coordinate
+/// @param center_r - Area center r coordinate
+/// @param radius - Largest distance from the center
+/// @param tile_type - Tile type as i32 (0-4, matching TileType enum)
+/// @param options_json - JSON object (see EditOptions::from_json)
+/// @returns JSON string (see edit_set_tiles); nothing changes for an invalid tile type
+#[wasm_bindgen]
+pub fn edit_fill_area(center_q: i32, center_r: i32, radius: i32, tile_type: i32, options_json: String) -> String {
+    let tile = TileType::from_i32(tile_type);
+    edit_and_report("fill_area", &options_json, |state| {
+        let Some(tile) = tile else {
+            return Vec::new();
+        };
+        generate_hex_grid(radius.max(0), center_q, center_r)
+            .iter()
+            .filter(|hex| state.get_tile(hex.q, hex.r).is_some())
+            .map(|hex| ((hex.q, hex.r), Some(tile)))
+            .collect()
+    })
+}
+
+/// Replace the connected region of tiles sharing the start hex's type
+/// 
+/// @param q - Start hex q coordinate
+/// @param r - Start hex r coordinate
+/// @param tile_type - Tile type as i32 (0-4, matching TileType enum)
+/// @param options_json - JSON object (see EditOptions::from_json); "maxHexes" caps the region
+/// @returns JSON string (see edit_set_tiles); nothing changes if the start hex is empty
+#[wasm_bindgen]
+pub fn edit_flood_fill(q: i32, r: i32, tile_type: i32, options_json: String) -> String {
+    let tile = TileType::from_i32(tile_type);
+    let limit = EditOptions::from_json(&options_json).max_hexes;
+    edit_and_report("flood_fill", &options_json, |state| {
+        let Some(tile) = tile else {
+            return Vec::new();
+        };
+        flood_region(state, (q, r), limit).into_iter().map(|hex| (hex, Some(tile))).collect()
+    })
+}
+
+/// Undo the most recent edit
+/// 
+/// @returns JSON string: {"changed":[{"q":0,"r":0,"tileType":2},...],"label":"fill_area"}
+///          or "null" if there is nothing to undo
+#[wasm_bindgen]
+pub fn undo_edit() -> String {
+    history_step(true)
+}
+
+/// Redo the most recently undone edit
+/// 
+/// @returns JSON string (see undo_edit) or "null" if there is nothing to redo
+#[wasm_bindgen]
+pub fn redo_edit() -> String {
+    history_step(false)
+}
+
+fn history_step(undo: bool) -> String {
+    let mut state = WFC_STATE.lock().unwrap();
+    let label = if undo {
+        state.edit_history().undo_labels().next()
+    } else {
+        state.edit_history().redo_labels().next()
+    };
+    let hexes = if undo { state.undo_edit() } else { state.redo_edit() };
+    let (Some(label), Some(hexes)) = (label, hexes) else {
+        return "null".to_string();
+    };
+    
+    let changed_json = changes_to_json(&state, &hexes);
+    drop(state);
+    
+    invalidate_flow_fields_for_hexes(&hexes);
+    format!(r#"{{"changed":{},"label":"{}"}}"#, changed_json, label)
+}
+
+/// Get the labels of the undo and redo stacks
+/// 
+/// @returns JSON string: {"undo":["fill_area",...],"redo":[...]} (next operation first)
+#[wasm_bindgen]
+pub fn get_edit_history() -> String {
+    let state = WFC_STATE.lock().unwrap();
+    let history = state.edit_history();
+    let undo: Vec<String> = history.undo_labels().map(|label| format!(r#""{}""#, label)).collect();
+    let redo: Vec<String> = history.redo_labels().map(|label| format!(r#""{}""#, label)).collect();
+    format!(r#"{{"undo":[{}],"redo":[{}]}}"#, undo.join(","), redo.join(","))
+}
+
+/// Forget all recorded edits (the tiles stay as they are)
+#[wasm_bindgen]
+pub fn clear_edit_history() {
+    let mut state = WFC_STATE.lock().unwrap();
+    state.clear_edit_history();
+}
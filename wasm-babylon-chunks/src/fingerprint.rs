@@ -0,0 +1,256 @@
+/// Layout fingerprint module
+/// 
+/// **Learning Point**: Two layouts that only differ by a rotation, a mirror or a shift
+/// are the same layout for caching purposes. Applying each of the 12 hex symmetries,
+/// moving the smallest hex to the origin and hashing the sorted tiles gives 12 candidate
+/// hashes; the smallest one is the canonical fingerprint, identical for every rotated or
+/// reflected copy. Near matches are scored separately from a tile-type histogram and the
+/// best tile overlap over all symmetries, so the pattern cache can skip the embedding
+/// model for layouts it has effectively seen before.
+
+use wasm_bindgen::prelude::*;
+use std::collections::HashMap;
+use crate::state::WFC_STATE;
+use crate::types::TileType;
+use crate::hex_utils::{generate_hex_grid, hex_symmetry, cube_round};
+use crate::json::{json_objects, json_int_field};
+use crate::seed::{mix64, hash_coords};
+
+/// Tile types in histogram order
+const TILE_TYPES: [TileType; 5] = [TileType::Grass, TileType::Building, TileType::Road, TileType::Forest, TileType::Water];
+
+/// Number of symmetries of the hex grid (6 rotations, each optionally mirrored)
+const SYMMETRY_COUNT: usize = 12;
+
+/// Width of one signature bucket in percent of the tile count
+const SIGNATURE_BUCKET_PERCENT: f64 = 10.0;
+
+/// Share of the histogram similarity in the combined score (the rest is tile overlap)
+const HISTOGRAM_WEIGHT: f64 = 0.3;
+
+/// Canonical fingerprint and tile histogram of a layout
+pub struct LayoutFingerprint {
+    /// Smallest hash over all 12 symmetries
+    pub hash: u64,
+    /// Tile counts in TILE_TYPES order
+    pub histogram: [usize; 5],
+}
+
+impl LayoutFingerprint {
+    pub fn new(tiles: &HashMap<(i32, i32), TileType>) -> Self {
+        let hash = (0..SYMMETRY_COUNT)
+            .map(|symmetry| hash_tiles(&anchored_tiles(tiles, symmetry)))
+            .min()
+            .unwrap_or(0);
+        LayoutFingerprint { hash, histogram: histogram(tiles) }
+    }
+    
+    /// Coarse histogram key: first letter of each tile type and its share in 10% buckets
+    /// Example: "g4b0r1f3w2" (layouts with the same key have similar tile proportions)
+    pub fn signature(&self) -> String {
+        let total = self.histogram.iter().sum::<usize>().max(1) as f64;
+        TILE_TYPES
+            .iter()
+            .zip(self.histogram.iter())
+            .map(|(tile_type, &count)| {
+                let bucket = (count as f64 * 100.0 / total / SIGNATURE_BUCKET_PERCENT).round() as i32;
+                format!("{}{}", &tile_type.as_str()[..1], bucket)
+            })
+            .collect()
+    }
+    
+    pub fn to_json(&self) -> String {
+        let counts: Vec<String> = TILE_TYPES
+            .iter()
+            .zip(self.histogram.iter())
+            .map(|(tile_type, count)| format!(r#""{}":{}"#, tile_type.as_str(), count))
+            .collect();
+        format!(
+            r#"{{"hash":"{:016x}","tiles":{},"histogram":{{{}}},"signature":"{}"}}"#,
+            self.hash,
+            self.histogram.iter().sum::<usize>(),
+            counts.join(","),
+            self.signature()
+        )
+    }
+}
+
+/// Similarity between two layouts
+pub struct LayoutSimilarity {
+    /// 1 - half the L1 distance between the tile-type proportions (0-1)
+    pub histogram: f64,
+    /// Best matching tiles / hexes in either layout over all symmetries (0-1)
+    pub spatial: f64,
+    /// Symmetry of the second layout that gave the best overlap (see hex_symmetry)
+    pub symmetry: usize,
+    /// Same canonical fingerprint and tile count
+    pub identical: bool,
+}
+
+impl LayoutSimilarity {
+    pub fn new(a: &HashMap<(i32, i32), TileType>, b: &HashMap<(i32, i32), TileType>) -> Self {
+        let fractions = |tiles: &HashMap<(i32, i32), TileType>| {
+            let total = tiles.len().max(1) as f64;
+            histogram(tiles).map(|count| count as f64 / total)
+        };
+        let (fractions_a, fractions_b) = (fractions(a), fractions(b));
+        let distance: f64 = fractions_a.iter().zip(fractions_b.iter()).map(|(x, y)| (x - y).abs()).sum();
+        
+        // Both layouts are centered on their rounded centroid, so a shifted copy lines up
+        let centered_a = centered_tiles(a, 0);
+        let mut spatial = 0.0;
+        let mut best_symmetry = 0;
+        for symmetry in 0..SYMMETRY_COUNT {
+            let centered_b = centered_tiles(b, symmetry);
+            let matches = centered_b
+                .iter()
+                .filter(|(hex, tile_type)| centered_a.get(hex) == Some(tile_type))
+                .count();
+            let union = centered_a.len() + centered_b.keys().filter(|hex| !centered_a.contains_key(hex)).count();
+            let overlap = if union == 0 { 1.0 } else { matches as f64 / union as f64 };
+            if overlap > spatial {
+                spatial = overlap;
+                best_symmetry = symmetry;
+            }
+        }
+        
+        let identical = a.len() == b.len() && LayoutFingerprint::new(a).hash == LayoutFingerprint::new(b).hash;
+        LayoutSimilarity {
+            histogram: (1.0 - distance / 2.0).max(0.0),
+            spatial: if identical { 1.0 } else { spatial },
+            symmetry: best_symmetry,
+            identical,
+        }
+    }
+    
+    /// Weighted combination of histogram and spatial similarity (0-1)
+    pub fn score(&self) -> f64 {
+        HISTOGRAM_WEIGHT * self.histogram + (1.0 - HISTOGRAM_WEIGHT) * self.spatial
+    }
+    
+    pub fn to_json(&self) -> String {
+        format!(
+            r#"{{"score":{:.4},"histogram":{:.4},"spatial":{:.4},"rotation":{},"reflected":{},"identical":{}}}"#,
+            self.score(),
+            self.histogram,
+            self.spatial,
+            self.symmetry % 6,
+            self.symmetry >= 6,
+            self.identical
+        )
+    }
+}
+
+fn histogram(tiles: &HashMap<(i32, i32), TileType>) -> [usize; 5] {
+    let mut counts = [0; 5];
+    for tile_type in tiles.values() {
+        counts[*tile_type as usize] += 1;
+    }
+    counts
+}
+
+/// Apply a symmetry and shift the smallest (r, q) hex to the origin; sorted by hex
+fn anchored_tiles(tiles: &HashMap<(i32, i32), TileType>, symmetry: usize) -> Vec<((i32, i32), TileType)> {
+    let mut transformed: Vec<((i32, i32), TileType)> = tiles
+        .iter()
+        .map(|(&(q, r), &tile_type)| (hex_symmetry(q, r, symmetry), tile_type))
+        .collect();
+    let Some(&((anchor_q, anchor_r), _)) = transformed.iter().min_by_key(|((q, r), _)| (*r, *q)) else {
+        return transformed;
+    };
+    
+    for ((q, r), _) in transformed.iter_mut() {
+        *q -= anchor_q;
+        *r -= anchor_r;
+    }
+    transformed.sort_by_key(|((q, r), _)| (*r, *q));
+    transformed
+}
+
+/// Order-dependent hash of a sorted tile list
+fn hash_tiles(tiles: &[((i32, i32), TileType)]) -> u64 {
+    tiles.iter().fold(mix64(tiles.len() as u64), |hash, &((q, r), tile_type)| {
+        mix64(hash.rotate_left(7) ^ hash_coords(tile_type as u64, q, r))
+    })
+}
+
+/// Apply a symmetry and shift the rounded centroid to the origin
+fn centered_tiles(tiles: &HashMap<(i32, i32), TileType>, symmetry: usize) -> HashMap<(i32, i32), TileType> {
+    let transformed: Vec<((i32, i32), TileType)> = tiles
+        .iter()
+        .map(|(&(q, r), &tile_type)| (hex_symmetry(q, r, symmetry), tile_type))
+        .collect();
+    let count = transformed.len().max(1) as f64;
+    let (center_q, center_r) = cube_round(
+        transformed.iter().map(|((q, _), _)| *q as f64).sum::<f64>() / count,
+        transformed.iter().map(|((_, r), _)| *r as f64).sum::<f64>() / count,
+    );
+    
+    transformed
+        .into_iter()
+        .map(|((q, r), tile_type)| ((q - center_q, r - center_r), tile_type))
+        .collect()
+}
+
+/// Parse [{"q":0,"r":0,"tileType":2},...]; entries with an invalid tile type are skipped
+fn parse_tiles_json(tiles_json: &str) -> HashMap<(i32, i32), TileType> {
+    json_objects(tiles_json)
+        .filter_map(|object| {
+            let q = json_int_field(object, "q")?;
+            let r = json_int_field(object, "r")?;
+            let tile_type = json_int_field(object, "tileType").and_then(TileType::from_i32)?;
+            Some(((q, r), tile_type))
+        })
+        .collect()
+}
+
+/// Fingerprint a layout given as JSON
+/// 
+/// **Learning Point**: The hash is a 64-bit value, which does not fit in a JS number,
+/// so it is returned as 16 hex digits and can be used directly as a cache key.
+/// 
+/// @param tiles_json - JSON array: [{"q":0,"r":0,"tileType":2},...] (e.g. from get_chunk_tiles)
+/// @returns JSON string: {"hash":"0123456789abcdef","tiles":0,
+///          "histogram":{"grass":0,"building":0,"road":0,"forest":0,"water":0},"signature":"g4b0r1f3w2"}
+#[wasm_bindgen]
+pub fn fingerprint_layout(tiles_json: String) -> String {
+    LayoutFingerprint::new(&parse_tiles_json(&tiles_json)).to_json()
+}
+
+/// Fingerprint the current layout
+/// 
+/// @param center_q - Chunk center q coordinate
+/// @param center_r - Chunk center r coordinate
+/// @param rings - Number of rings per chunk, negative for the whole layout
+/// @returns JSON string (see fingerprint_layout)
+#[wasm_bindgen]
+pub fn get_layout_fingerprint(center_q: i32, center_r: i32, rings: i32) -> String {
+    let state = WFC_STATE.lock().unwrap();
+    let tiles: HashMap<(i32, i32), TileType> = if rings < 0 {
+        state.tiles().collect()
+    } else {
+        generate_hex_grid(rings, center_q, center_r)
+            .iter()
+            .filter_map(|hex| state.get_tile(hex.q, hex.r).map(|tile_type| ((hex.q, hex.r), tile_type)))
+            .collect()
+    };
+    drop(state);
+    
+    LayoutFingerprint::new(&tiles).to_json()
+}
+
+/// Score how similar two layouts are
+/// 
+/// **Learning Point**: "spatial" compares tiles hex by hex after centering both layouts
+/// and trying every rotation and mirror of the second one, so a rotated copy scores 1.
+/// "histogram" only compares tile proportions and stays meaningful for layouts of
+/// different shapes or sizes.
+/// 
+/// @param a_json - JSON array: [{"q":0,"r":0,"tileType":2},...]
+/// @param b_json - JSON array: [{"q":0,"r":0,"tileType":2},...]
+/// @returns JSON string: {"score":0.0,"histogram":0.0,"spatial":0.0,"rotation":0,
+///          "reflected":false,"identical":false} (rotation/reflected map b onto a)
+#[wasm_bindgen]
+pub fn layout_similarity(a_json: String, b_json: String) -> String {
+    LayoutSimilarity::new(&parse_tiles_json(&a_json), &parse_tiles_json(&b_json)).to_json()
+}
@@ -0,0 +1,380 @@
+/// Flow field module
+/// 
+/// **Learning Point**: A* answers one start/goal pair per search. A flow field runs a
+/// single Dijkstra outward from all goals at once (the integration field) and stores,
+/// for every hex, the direction of its cheapest neighbor. Any number of NPCs heading to
+/// the same goals then steer with one lookup per frame instead of one search each.
+/// 
+/// Fields cover a set of chunks and are kept in a registry by id. When tiles in a chunk
+/// change, every field overlapping that chunk is marked dirty and recomputed lazily on
+/// its next query, so editing one chunk never touches unrelated fields.
+
+use wasm_bindgen::prelude::*;
+use std::cmp::Reverse;
+use std::sync::{LazyLock, Mutex};
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use crate::state::{WfcState, WFC_STATE};
+use crate::types::TileType;
+use crate::hex_utils::{generate_hex_grid, hex_distance, parse_tile_types_json, parse_valid_terrain_json, CUBE_DIRECTIONS};
+use crate::json::{json_array_field, json_number_field, json_object_field};
+
+/// Direction value for a goal hex (NPC has arrived)
+pub const FLOW_GOAL: i8 = 6;
+
+/// Direction value for hexes outside the field or unable to reach a goal
+pub const FLOW_NONE: i8 = -1;
+
+/// Movement rules for a flow field
+/// 
+/// Parsed from JSON: {"passable":[0,2,3],"costs":{"grass":2,"road":1,"forest":4}}
+/// Passable defaults to grass, road and forest; costs default to 1 and are at least 1.
+pub struct FlowFieldConfig {
+    pub passable: Vec<TileType>,
+    /// Cost of walking across a hex, indexed by TileType value
+    pub costs: [u32; 5],
+}
+
+impl FlowFieldConfig {
+    pub fn from_json(config_json: &str) -> Self {
+        let passable = match json_array_field(config_json, "passable") {
+            Some(passable_json) => parse_tile_types_json(passable_json),
+            None => vec![TileType::Grass, TileType::Road, TileType::Forest],
+        };
+        
+        let mut costs = [1u32; 5];
+        if let Some(costs_json) = json_object_field(config_json, "costs") {
+            for (index, cost) in costs.iter_mut().enumerate() {
+                let Some(tile_type) = TileType::from_i32(index as i32) else {
+                    continue;
+                };
+                if let Some(value) = json_number_field(costs_json, tile_type.as_str()).filter(|value| value.is_finite()) {
+                    *cost = value.round().clamp(1.0, u16::MAX as f64) as u32;
+                }
+            }
+        }
+        
+        FlowFieldConfig { passable, costs }
+    }
+}
+
+/// Integration and direction field over a set of chunks
+pub struct FlowField {
+    goals: Vec<(i32, i32)>,
+    /// Chunk centers covered by the field
+    chunks: Vec<(i32, i32)>,
+    rings: i32,
+    config: FlowFieldConfig,
+    /// Covered hexes (union of the chunk hexagons)
+    area: HashSet<(i32, i32)>,
+    /// Cheapest total cost to a goal; hexes that cannot reach a goal are absent
+    costs: HashMap<(i32, i32), u32>,
+    /// Direction index per reachable hex (see FLOW_GOAL)
+    directions: HashMap<(i32, i32), i8>,
+    dirty: bool,
+}
+
+impl FlowField {
+    pub fn new(goals: Vec<(i32, i32)>, chunks: Vec<(i32, i32)>, rings: i32, config: FlowFieldConfig) -> Self {
+        let area = chunks
+            .iter()
+            .flat_map(|&(q, r)| generate_hex_grid(rings, q, r))
+            .map(|hex| (hex.q, hex.r))
+            .collect();
+        FlowField {
+            goals,
+            chunks,
+            rings,
+            config,
+            area,
+            costs: HashMap::new(),
+            directions: HashMap::new(),
+            dirty: true,
+        }
+    }
+    
+    /// Whether the field overlaps the chunk hexagon at `center` with `rings` rings
+    pub fn overlaps_chunk(&self, center: (i32, i32), rings: i32) -> bool {
+        self.chunks
+            .iter()
+            .any(|&(q, r)| hex_distance(q, r, center.0, center.1) <= self.rings + rings)
+    }
+    
+    pub fn invalidate(&mut self) {
+        self.dirty = true;
+    }
+    
+    /// Recompute the field if tiles changed since the last computation
+    pub fn refresh(&mut self, state: &WfcState) {
+        if self.dirty {
+            self.compute(state);
+            self.dirty = false;
+        }
+    }
+    
+    /// Dijkstra from all goals, then point every hex at its cheapest neighbor
+    /// 
+    /// Goals are seeded even when their own tile is impassable (a market building can
+    /// be a destination); paths only continue through passable hexes inside the area.
+    fn compute(&mut self, state: &WfcState) {
+        let walk_cost = |hex: (i32, i32)| -> Option<u32> {
+            if !self.area.contains(&hex) {
+                return None;
+            }
+            let tile_type = state.get_tile(hex.0, hex.1)?;
+            self.config.passable.contains(&tile_type).then(|| self.config.costs[tile_type as usize])
+        };
+        
+        let mut costs: HashMap<(i32, i32), u32> = HashMap::new();
+        let mut open = BinaryHeap::new();
+        for &goal in &self.goals {
+            if self.area.contains(&goal) && costs.insert(goal, 0).is_none() {
+                open.push(Reverse((0u32, goal)));
+            }
+        }
+        
+        while let Some(Reverse((cost, (q, r)))) = open.pop() {
+            if costs.get(&(q, r)).is_some_and(|&best| cost > best) {
+                continue;
+            }
+            // The search runs backwards from the goals: a hex is charged its own cost
+            // when it is walked across, the goal itself is free
+            for direction in CUBE_DIRECTIONS {
+                let neighbor = (q + direction.q, r + direction.r);
+                let Some(step) = walk_cost(neighbor) else {
+                    continue;
+                };
+                let neighbor_cost = cost + step;
+                if costs.get(&neighbor).is_none_or(|&best| neighbor_cost < best) {
+                    costs.insert(neighbor, neighbor_cost);
+                    open.push(Reverse((neighbor_cost, neighbor)));
+                }
+            }
+        }
+        
+        let mut directions = HashMap::with_capacity(costs.len());
+        for (&(q, r), &cost) in &costs {
+            if cost == 0 {
+                directions.insert((q, r), FLOW_GOAL);
+                continue;
+            }
+            // Lowest neighbor cost wins; ties go to the lowest direction index
+            let best = CUBE_DIRECTIONS
+                .iter()
+                .enumerate()
+                .filter_map(|(index, direction)| {
+                    costs.get(&(q + direction.q, r + direction.r)).map(|&neighbor_cost| (neighbor_cost, index))
+                })
+                .min();
+            if let Some((neighbor_cost, index)) = best {
+                if neighbor_cost < cost {
+                    directions.insert((q, r), index as i8);
+                }
+            }
+        }
+        
+        self.costs = costs;
+        self.directions = directions;
+    }
+    
+    /// Direction index toward the goals (0-5, FLOW_GOAL or FLOW_NONE)
+    pub fn direction(&self, q: i32, r: i32) -> i8 {
+        self.directions.get(&(q, r)).copied().unwrap_or(FLOW_NONE)
+    }
+    
+    /// Total cost to the nearest goal, None if unreachable or outside the field
+    pub fn cost(&self, q: i32, r: i32) -> Option<u32> {
+        self.costs.get(&(q, r)).copied()
+    }
+}
+
+/// All live flow fields by id
+pub struct FlowFieldRegistry {
+    next_id: u32,
+    fields: HashMap<u32, FlowField>,
+}
+
+impl FlowFieldRegistry {
+    pub fn new() -> Self {
+        FlowFieldRegistry {
+            next_id: 1,
+            fields: HashMap::new(),
+        }
+    }
+    
+    /// Store a field and return its id (ids start at 1 and are never reused)
+    pub fn insert(&mut self, field: FlowField) -> u32 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.fields.insert(id, field);
+        id
+    }
+    
+    pub fn remove(&mut self, id: u32) -> bool {
+        self.fields.remove(&id).is_some()
+    }
+    
+    pub fn clear(&mut self) {
+        self.fields.clear();
+    }
+    
+    /// Get a field, recomputed first if it is dirty
+    pub fn get(&mut self, id: u32, state: &WfcState) -> Option<&FlowField> {
+        let field = self.fields.get_mut(&id)?;
+        field.refresh(state);
+        Some(field)
+    }
+    
+    /// Mark every field overlapping a chunk dirty
+    /// Returns the number of fields invalidated
+    pub fn invalidate_chunk(&mut self, center: (i32, i32), rings: i32) -> usize {
+        let mut count = 0;
+        for field in self.fields.values_mut() {
+            if field.overlaps_chunk(center, rings) {
+                field.invalidate();
+                count += 1;
+            }
+        }
+        count
+    }
+    
+    /// Mark every field dirty (the whole layout changed)
+    pub fn invalidate_all(&mut self) {
+        for field in self.fields.values_mut() {
+            field.invalidate();
+        }
+    }
+}
+
+/// Global flow field registry (thread-safe)
+/// Lock order: FLOW_FIELDS before WFC_STATE
+pub static FLOW_FIELDS: LazyLock<Mutex<FlowFieldRegistry>> = LazyLock::new(|| Mutex::new(FlowFieldRegistry::new()));
+
+/// Mark flow fields overlapping a chunk dirty (call after the chunk's tiles change)
+/// Callers must not hold WFC_STATE
+pub fn invalidate_flow_fields_for_chunk(center: (i32, i32), rings: i32) -> usize {
+    FLOW_FIELDS.lock().unwrap().invalidate_chunk(center, rings)
+}
+
+/// Mark flow fields covering any of the hexes dirty (call after editing single tiles)
+/// Callers must not hold WFC_STATE
+pub fn invalidate_flow_fields_for_hexes(hexes: &[(i32, i32)]) -> usize {
+    let mut fields = FLOW_FIELDS.lock().unwrap();
+    let mut count = 0;
+    for field in fields.fields.values_mut() {
+        if hexes.iter().any(|&hex| field.overlaps_chunk(hex, 0)) {
+            field.invalidate();
+            count += 1;
+        }
+    }
+    count
+}
+
+/// Mark all flow fields dirty (call after the whole layout is replaced)
+/// Callers must not hold WFC_STATE
+pub fn invalidate_all_flow_fields() {
+    FLOW_FIELDS.lock().unwrap().invalidate_all();
+}
+
+/// Create a flow field toward one or more goals over a set of chunks
+/// 
+/// **Learning Point**: The field is computed on first use and cached; steer each NPC
+/// with flow_direction (or a whole chunk with get_flow_field_chunk) every frame.
+/// 
+/// @param goals_json - JSON array of goal hexes: [{"q":0,"r":0},...]
+/// @param chunks_json - JSON array of chunk centers covered by the field: [{"q":0,"r":0},...]
+/// @param rings - Number of rings per chunk
+/// @param config_json - JSON object (see FlowFieldConfig::from_json), "{}" for defaults
+/// @returns Flow field id (used by the other flow field functions)
+#[wasm_bindgen]
+pub fn create_flow_field(goals_json: String, chunks_json: String, rings: i32, config_json: String) -> u32 {
+    let mut goals: Vec<(i32, i32)> = parse_valid_terrain_json(&goals_json).into_iter().collect();
+    let mut chunks: Vec<(i32, i32)> = parse_valid_terrain_json(&chunks_json).into_iter().collect();
+    goals.sort();
+    chunks.sort();
+    
+    let field = FlowField::new(goals, chunks, rings.max(0), FlowFieldConfig::from_json(&config_json));
+    FLOW_FIELDS.lock().unwrap().insert(field)
+}
+
+/// Get the step direction for an NPC standing on a hex
+/// 
+/// @param id - Flow field id
+/// @param q - Hex q coordinate
+/// @param r - Hex r coordinate
+/// @returns Direction index 0-5 (CUBE_DIRECTIONS: (1,0),(1,-1),(0,-1),(-1,0),(-1,1),(0,1)),
+///          6 on a goal, -1 if the hex cannot reach a goal or the id is unknown
+#[wasm_bindgen]
+pub fn flow_direction(id: u32, q: i32, r: i32) -> i32 {
+    let mut fields = FLOW_FIELDS.lock().unwrap();
+    let state = WFC_STATE.lock().unwrap();
+    fields.get(id, &state).map_or(FLOW_NONE, |field| field.direction(q, r)) as i32
+}
+
+/// Get the total cost from a hex to its nearest goal
+/// 
+/// @param id - Flow field id
+/// @param q - Hex q coordinate
+/// @param r - Hex r coordinate
+/// @returns Integration cost (0 on a goal), -1 if unreachable or the id is unknown
+#[wasm_bindgen]
+pub fn flow_cost(id: u32, q: i32, r: i32) -> i32 {
+    let mut fields = FLOW_FIELDS.lock().unwrap();
+    let state = WFC_STATE.lock().unwrap();
+    fields
+        .get(id, &state)
+        .and_then(|field| field.cost(q, r))
+        .map_or(-1, |cost| cost.min(i32::MAX as u32) as i32)
+}
+
+/// Get the directions for every hex of one chunk
+/// 
+/// **Learning Point**: One typed array per chunk lets TypeScript cache the directions
+/// and index them by the NPC's ring-order tile index without crossing into WASM per NPC.
+/// 
+/// @param id - Flow field id
+/// @param center_q - Chunk center q coordinate
+/// @param center_r - Chunk center r coordinate
+/// @returns Int8Array of directions in ring order (center first, see flow_direction);
+///          empty if the id is unknown
+#[wasm_bindgen]
+pub fn get_flow_field_chunk(id: u32, center_q: i32, center_r: i32) -> Vec<i8> {
+    let mut fields = FLOW_FIELDS.lock().unwrap();
+    let state = WFC_STATE.lock().unwrap();
+    let Some(field) = fields.get(id, &state) else {
+        return Vec::new();
+    };
+    
+    generate_hex_grid(field.rings, center_q, center_r)
+        .iter()
+        .map(|hex| field.direction(hex.q, hex.r))
+        .collect()
+}
+
+/// Mark flow fields overlapping a chunk for recomputation
+/// 
+/// generate_chunk, evict_chunk and generate_chunk_from_constraints do this
+/// automatically; call it after changing tiles of a chunk any other way.
+/// 
+/// @param center_q - Chunk center q coordinate
+/// @param center_r - Chunk center r coordinate
+/// @param rings - Number of rings per chunk
+/// @returns Number of fields invalidated
+#[wasm_bindgen]
+pub fn invalidate_flow_fields(center_q: i32, center_r: i32, rings: i32) -> u32 {
+    invalidate_flow_fields_for_chunk((center_q, center_r), rings.max(0)) as u32
+}
+
+/// Remove a flow field
+/// 
+/// @param id - Flow field id
+/// @returns true if the field existed
+#[wasm_bindgen]
+pub fn remove_flow_field(id: u32) -> bool {
+    FLOW_FIELDS.lock().unwrap().remove(id)
+}
+
+/// Remove all flow fields
+#[wasm_bindgen]
+pub fn clear_flow_fields() {
+    FLOW_FIELDS.lock().unwrap().clear();
+}
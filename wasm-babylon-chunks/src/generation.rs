@@ -0,0 +1,565 @@
+/// Seeded chunk generation pipeline module
+
+use wasm_bindgen::prelude::*;
+use std::collections::{HashMap, HashSet};
+use crate::state::WFC_STATE;
+use crate::types::{TileType, Zone};
+use crate::types::HexCoord;
+use crate::hex_utils::{generate_hex_grid, hex_distance};
+use crate::grid_shape::{GridShape, PlacedShape};
+use crate::voronoi::{pick_voronoi_seeds, assign_voronoi_regions};
+use crate::roads::{connect_endpoints, RoadNetworkParams};
+use crate::chunks::select_side_exits;
+use crate::buildings::{place_buildings, BuildingRecord, BuildingRules};
+use crate::zoning::{assign_zones, zone_building_factor, ZoningConfig};
+use crate::hydrology::{rivers_in_area, HydrologyConfig};
+use crate::seed::{chunk_seed, SeededRng};
+use crate::noise::{generate_noise_tiles, NoiseConfig};
+use crate::flow_field::{invalidate_all_flow_fields, invalidate_flow_fields_for_chunk};
+use crate::json::{coords_to_json, json_int_field, json_number_field, json_object_field, json_string_field, json_bool_field};
+
+/// Parameters for seeded chunk generation
+/// Defaults mirror CONSTRAINTS in constraints.ts
+pub struct ChunkGenerationParams {
+    pub forest_seeds: i32,
+    pub water_seeds: i32,
+    pub grass_seeds: i32,
+    /// Target fraction of chunk hexes that are roads (controls interior road hubs)
+    pub road_density: f64,
+    /// Road exits per chunk side (so roads continue into neighbor chunks)
+    pub roads_per_border: usize,
+    /// Fraction of chunk hexes that become buildings (limited by road frontage)
+    pub building_density: f64,
+    /// Footprint size, clustering and road frontage rules for buildings
+    pub building_rules: BuildingRules,
+    /// District thresholds; each zone scales building_density
+    pub zoning: ZoningConfig,
+    pub road_params: RoadNetworkParams,
+    /// Noise terrain settings; None keeps the plain Voronoi terrain pass
+    pub noise: Option<NoiseConfig>,
+    /// River tracing settings; None skips the hydrology pass
+    /// Rivers follow the noise elevation (default NoiseConfig when terrain is Voronoi)
+    pub hydrology: Option<HydrologyConfig>,
+    /// Rectangle, parallelogram or triangle replacing the chunk hexagon; its origin is
+    /// relative to the chunk center. None (or a hexagon) keeps the hexagon of `rings`
+    pub shape: Option<PlacedShape>,
+}
+
+impl Default for ChunkGenerationParams {
+    fn default() -> Self {
+        ChunkGenerationParams {
+            forest_seeds: 4,
+            water_seeds: 3,
+            grass_seeds: 6,
+            road_density: 0.1,
+            roads_per_border: 1,
+            building_density: 0.1,
+            building_rules: BuildingRules::default(),
+            zoning: ZoningConfig::default(),
+            road_params: RoadNetworkParams::default(),
+            noise: None,
+            hydrology: None,
+            shape: None,
+        }
+    }
+}
+
+impl ChunkGenerationParams {
+    /// Parse from JSON, falling back to defaults for missing fields
+    /// Format: {"forestSeeds":4,"waterSeeds":3,"grassSeeds":6,"roadDensity":0.1,"roadsPerBorder":1,
+    ///          "buildingDensity":0.1,"minAdjacentRoads":1,"sizeHint":"medium","clustering":"random",
+    ///          "straightness":0.0,"maxBranches":6, ...ZoningConfig fields,
+    ///          "terrain":"voronoi"|"noise", ...NoiseConfig fields,
+    ///          "rivers":true, ...HydrologyConfig fields, "riverCrossing":"bridge"|"avoid",
+    ///          "shape":{"type":"rectangle","width":20,"height":12}}
+    pub fn from_json(params_json: &str) -> Self {
+        let defaults = ChunkGenerationParams::default();
+        ChunkGenerationParams {
+            forest_seeds: json_int_field(params_json, "forestSeeds").unwrap_or(defaults.forest_seeds),
+            water_seeds: json_int_field(params_json, "waterSeeds").unwrap_or(defaults.water_seeds),
+            grass_seeds: json_int_field(params_json, "grassSeeds").unwrap_or(defaults.grass_seeds),
+            road_density: json_number_field(params_json, "roadDensity")
+                .unwrap_or(defaults.road_density)
+                .clamp(0.0, 1.0),
+            roads_per_border: json_int_field(params_json, "roadsPerBorder")
+                .map(|value| value.max(0) as usize)
+                .unwrap_or(defaults.roads_per_border),
+            building_density: json_number_field(params_json, "buildingDensity")
+                .unwrap_or(defaults.building_density)
+                .clamp(0.0, 1.0),
+            building_rules: BuildingRules::from_json(params_json),
+            zoning: ZoningConfig::from_json(params_json),
+            road_params: RoadNetworkParams::from_json(params_json),
+            noise: match json_string_field(params_json, "terrain").as_deref() {
+                Some("noise") => Some(NoiseConfig::from_json(params_json)),
+                _ => defaults.noise,
+            },
+            hydrology: match json_bool_field(params_json, "rivers") {
+                Some(true) => Some(HydrologyConfig::from_json(params_json)),
+                _ => defaults.hydrology,
+            },
+            // A hexagon is the regular chunk grid, sized by `rings`
+            shape: json_object_field(params_json, "shape")
+                .and_then(PlacedShape::from_json)
+                .filter(|shape| !matches!(shape.shape, GridShape::Hexagon { .. })),
+        }
+    }
+}
+
+/// Output of the chunk generation pipeline
+pub struct GeneratedChunk {
+    /// Tiles in ring order (center first)
+    pub tiles: Vec<((i32, i32), TileType)>,
+    /// Zone of every zoned hex, in the same order
+    pub zones: Vec<((i32, i32), Zone)>,
+    /// River hexes (under the roads and bridges built on them), sorted
+    pub rivers: Vec<(i32, i32)>,
+    /// Road hexes crossing a river, sorted
+    pub bridges: Vec<(i32, i32)>,
+    /// Buildings in placement order (market, residential, then farmland)
+    pub buildings: Vec<BuildingRecord>,
+    /// Hexes that were water before roads and buildings were laid (rivers under bridges
+    /// included), in the same order as the tiles
+    pub water: Vec<(i32, i32)>,
+}
+
+/// Generate a complete chunk (terrain, roads, zones, buildings) from the world seed
+/// 
+/// **Learning Point**: Every random choice draws from one RNG seeded with
+/// chunk_seed(world_seed, center), and every intermediate collection is iterated in a
+/// fixed order. The output is therefore a pure function of (world seed, center, rings,
+/// params, pinned tiles): regenerating an evicted chunk reproduces it exactly.
+/// 
+/// Steps:
+/// 1. Voronoi terrain from seeded seed points (or seamless noise terrain, optionally blended),
+///    then optional rivers and lakes carved as water, then the pinned tiles on top
+/// 2. Roads connecting the border exits (shared with neighbor chunks), pinned road hexes and
+///    a few seeded hubs, bridging or avoiding rivers and never paving other pinned hexes
+/// 3. Zoning into market, residential, farmland and park districts
+/// 4. Building footprints on seeded grass hexes that front a road, scaled per zone
+/// 
+/// With a shape, noise, rivers and zoning run over the smallest hexagon around the center
+/// that holds the shape and are cut to it; a shaped map has no border exits, so its roads
+/// only join hubs and pinned roads.
+pub fn generate_chunk_tiles(
+    world_seed: u64,
+    center_q: i32,
+    center_r: i32,
+    rings: i32,
+    params: &ChunkGenerationParams,
+    pinned: &HashMap<(i32, i32), TileType>,
+) -> GeneratedChunk {
+    let hexes: Vec<(i32, i32)> = match &params.shape {
+        Some(shape) => shape.hexes().into_iter().map(|(q, r)| (center_q + q, center_r + r)).collect(),
+        None => generate_hex_grid(rings.max(0), center_q, center_r).iter().map(|hex| (hex.q, hex.r)).collect(),
+    };
+    let hex_grid: Vec<HexCoord> = hexes.iter().map(|&(q, r)| HexCoord { q, r }).collect();
+    let in_grid: HashSet<(i32, i32)> = hexes.iter().copied().collect();
+    // Radius of the hexagon area the noise, river and zoning passes cover
+    let area_rings = match &params.shape {
+        Some(_) => hexes.iter().map(|&(q, r)| hex_distance(center_q, center_r, q, r)).max().unwrap_or(0),
+        None => rings.max(0),
+    };
+    let mut rng = SeededRng::new(chunk_seed(world_seed, center_q, center_r));
+    
+    // Step 1: Voronoi or noise terrain
+    let mut seeds = pick_voronoi_seeds(&hexes, params.forest_seeds, params.water_seeds, params.grass_seeds, &mut rng);
+    if seeds.is_empty() {
+        seeds = pick_voronoi_seeds(&hexes, 0, 0, 1, &mut rng);
+    }
+    let terrain_tiles = match &params.noise {
+        Some(config) => generate_noise_tiles(world_seed, center_q, center_r, area_rings, config, &seeds),
+        None => assign_voronoi_regions(&hex_grid, &seeds),
+    };
+    let mut terrain: HashMap<(i32, i32), TileType> = terrain_tiles
+        .into_iter()
+        .filter(|(hex, _)| in_grid.contains(hex))
+        .collect();
+    
+    // Rivers and lakes traced over the world elevation field (seamless across chunks)
+    let rivers: HashSet<(i32, i32)> = match &params.hydrology {
+        Some(config) => {
+            let default_noise = NoiseConfig::default();
+            let noise = params.noise.as_ref().unwrap_or(&default_noise);
+            let system = rivers_in_area(world_seed, center_q, center_r, area_rings, noise, config);
+            for hex in system.rivers.iter().chain(system.lakes.iter()).filter(|hex| in_grid.contains(hex)) {
+                terrain.insert(*hex, TileType::Water);
+            }
+            system.rivers.into_iter().filter(|hex| in_grid.contains(hex)).collect()
+        },
+        None => HashSet::new(),
+    };
+    
+    // Pinned tiles (pre-constraints, placed prefabs) replace the generated terrain
+    let mut pinned: Vec<((i32, i32), TileType)> = pinned
+        .iter()
+        .filter(|(hex, _)| terrain.contains_key(hex))
+        .map(|(hex, tile_type)| (*hex, *tile_type))
+        .collect();
+    pinned.sort_by_key(|(hex, _)| *hex);
+    terrain.extend(pinned.iter().copied());
+    let occupied: HashSet<(i32, i32)> = pinned
+        .iter()
+        .filter(|(_, tile_type)| *tile_type != TileType::Road)
+        .map(|(hex, _)| *hex)
+        .collect();
+    
+    let water: Vec<(i32, i32)> = hexes
+        .iter()
+        .copied()
+        .filter(|hex| terrain.get(hex) == Some(&TileType::Water))
+        .collect();
+    
+    // Step 2: Roads between border exits, pinned roads and interior hubs
+    let no_neighbor_edges = HashMap::new();
+    let mut endpoints: Vec<(i32, i32)> = Vec::new();
+    let sides = if params.shape.is_some() { 0 } else { 6 };
+    for side in 0..sides {
+        for exit in select_side_exits(world_seed, center_q, center_r, rings, side, params.roads_per_border, &no_neighbor_edges) {
+            endpoints.push(exit.inside);
+        }
+    }
+    endpoints.extend(pinned.iter().filter(|(_, tile_type)| *tile_type == TileType::Road).map(|(hex, _)| *hex));
+    
+    let mut grass_hexes: Vec<(i32, i32)> = hexes
+        .iter()
+        .copied()
+        .filter(|hex| terrain.get(hex) == Some(&TileType::Grass) && !occupied.contains(hex))
+        .collect();
+    rng.shuffle(&mut grass_hexes);
+    // A quarter of the target road count becomes hubs (matches CONSTRAINTS.road.seedPointRatio)
+    let hub_count = (hexes.len() as f64 * params.road_density * 0.25).ceil() as usize;
+    endpoints.extend(grass_hexes.iter().take(hub_count));
+    
+    let network = connect_endpoints(&endpoints, &terrain, &occupied, &rivers, &params.road_params);
+    for hex in &network.roads {
+        terrain.insert(*hex, TileType::Road);
+    }
+    
+    // Step 3: Zoning from road density and distance to the chunk center
+    let zones = assign_zones(center_q, center_r, area_rings, &terrain, &params.zoning);
+    
+    // Step 4: Building footprints on grass hexes that front a road, per zone
+    let roads: HashSet<(i32, i32)> = network.roads.iter().copied().collect();
+    let mut buildings: Vec<BuildingRecord> = Vec::new();
+    for zone in [Zone::Market, Zone::Residential, Zone::Farmland] {
+        let zone_hexes: Vec<(i32, i32)> = hexes.iter().copied().filter(|hex| zones.get(hex) == Some(&zone)).collect();
+        let buildable: HashSet<(i32, i32)> = zone_hexes
+            .iter()
+            .copied()
+            .filter(|hex| terrain.get(hex) == Some(&TileType::Grass) && !occupied.contains(hex))
+            .collect();
+        // Density counts hexes; convert to a building count for the preferred footprint
+        let density = params.building_density * zone_building_factor(zone);
+        let building_hexes = (zone_hexes.len() as f64 * density).round() as usize;
+        let building_count = building_hexes.div_ceil(params.building_rules.size_hint.hex_count());
+        for building in place_buildings(&buildable, &roads, &params.building_rules, building_count, &mut rng) {
+            for hex in &building.hexes {
+                terrain.insert(*hex, TileType::Building);
+            }
+            buildings.push(building);
+        }
+    }
+    
+    GeneratedChunk {
+        tiles: hexes
+            .iter()
+            .filter_map(|hex| terrain.get(hex).map(|&tile_type| (*hex, tile_type)))
+            .collect(),
+        zones: hexes
+            .iter()
+            .filter_map(|hex| zones.get(hex).map(|&zone| (*hex, zone)))
+            .collect(),
+        rivers: {
+            let mut rivers: Vec<(i32, i32)> = rivers.into_iter().collect();
+            rivers.sort();
+            rivers
+        },
+        bridges: network.bridges,
+        buildings,
+        water,
+    }
+}
+
+/// Generate a complete chunk layout from the world seed
+/// 
+/// Same world seed + same arguments always returns byte-identical output, so chunks
+/// unloaded by disable_distant_chunks can be regenerated instead of cached.
+/// 
+/// @param center_q - Chunk center q coordinate
+/// @param center_r - Chunk center r coordinate
+/// @param rings - Number of rings per chunk (ignored with a shape)
+/// @param params_json - JSON object (see ChunkGenerationParams::from_json), "{}" for defaults
+/// @returns JSON string: {"tiles":[{"q":0,"r":0,"tileType":0,"zone":1},...],"buildings":[...]}
+///          Tiles are in ring order; river hexes add "river":true and bridges (roads on a
+///          river) add "bridge":true. "buildings" uses the generate_buildings format.
+#[wasm_bindgen]
+pub fn generate_chunk_layout(center_q: i32, center_r: i32, rings: i32, params_json: String) -> String {
+    let world_seed = WFC_STATE.lock().unwrap().world_seed();
+    let params = ChunkGenerationParams::from_json(&params_json);
+    
+    let chunk = generate_chunk_tiles(world_seed, center_q, center_r, rings, &params, &HashMap::new());
+    let zones: HashMap<(i32, i32), Zone> = chunk.zones.into_iter().collect();
+    let rivers: HashSet<(i32, i32)> = chunk.rivers.into_iter().collect();
+    let bridges: HashSet<(i32, i32)> = chunk.bridges.into_iter().collect();
+    
+    let mut json_parts = Vec::new();
+    for ((q, r), tile_type) in chunk.tiles {
+        let zone = zones.get(&(q, r)).copied().unwrap_or(Zone::Unzoned);
+        let mut flags = String::new();
+        if rivers.contains(&(q, r)) {
+            flags.push_str(r#","river":true"#);
+        }
+        if bridges.contains(&(q, r)) {
+            flags.push_str(r#","bridge":true"#);
+        }
+        json_parts.push(format!(
+            r#"{{"q":{},"r":{},"tileType":{},"zone":{}{}}}"#,
+            q, r, tile_type as i32, zone as i32, flags
+        ));
+    }
+    
+    format!(r#"{{"tiles":[{}],"buildings":{}}}"#, json_parts.join(","), buildings_to_json(&chunk.buildings))
+}
+
+/// Building records as JSON in the generate_buildings format (the index is the id)
+fn buildings_to_json(buildings: &[BuildingRecord]) -> String {
+    let json_parts: Vec<String> = buildings
+        .iter()
+        .enumerate()
+        .map(|(id, building)| building.to_json(id))
+        .collect();
+    format!("[{}]", json_parts.join(","))
+}
+
+/// Generate a chunk and store it in the layout state
+/// 
+/// **Learning Point**: Chunks are stored by center, so the streaming world can load
+/// and unload them independently instead of regenerating or clearing everything.
+/// Pre-constraints inside the chunk take precedence over generated tiles, matching
+/// generate_layout; roads connect to pre-constrained roads and never pave over other
+/// pre-constrained hexes. Regenerating a loaded chunk replaces it.
+/// A "shape" in the params makes a whole map rather than a streaming chunk: its tiles
+/// go straight into the grid without a chunk record (as in generate_chunk_from_constraints).
+/// 
+/// @param center_q - Chunk center q coordinate
+/// @param center_r - Chunk center r coordinate
+/// @param rings - Number of rings per chunk (ignored with a shape)
+/// @param params_json - JSON object (see ChunkGenerationParams::from_json), "{}" for defaults
+/// @returns JSON string: {"tiles":0,"rivers":[{"q":0,"r":0},...],"bridges":[{"q":0,"r":0},...],"buildings":[...]}
+///          (tiles is the number of tiles stored; bridges are the road hexes built on a river;
+///          buildings use the generate_buildings format and are stored with the chunk,
+///          unless the params have a shape)
+#[wasm_bindgen]
+pub fn generate_chunk(center_q: i32, center_r: i32, rings: i32, params_json: String) -> String {
+    let params = ChunkGenerationParams::from_json(&params_json);
+    let mut state = WFC_STATE.lock().unwrap();
+    
+    let pre_constraints: HashMap<(i32, i32), TileType> = state.pre_constraints().collect();
+    let GeneratedChunk { tiles, zones, rivers, bridges, buildings, water } = generate_chunk_tiles(state.world_seed(), center_q, center_r, rings, &params, &pre_constraints);
+    
+    let tile_count = tiles.len();
+    let buildings_json = buildings_to_json(&buildings);
+    let shaped = params.shape.is_some();
+    if shaped {
+        for ((q, r), tile_type) in tiles {
+            state.insert_tile(q, r, tile_type);
+        }
+    } else {
+        state.insert_chunk((center_q, center_r), rings, tiles, buildings, water.into_iter().collect());
+    }
+    for ((q, r), zone) in zones {
+        state.set_zone(q, r, zone);
+    }
+    drop(state);
+    
+    if shaped {
+        invalidate_all_flow_fields();
+    } else {
+        invalidate_flow_fields_for_chunk((center_q, center_r), rings);
+    }
+    format!(
+        r#"{{"tiles":{},"rivers":{},"bridges":{},"buildings":{}}}"#,
+        tile_count,
+        coords_to_json(&rivers),
+        coords_to_json(&bridges),
+        buildings_json
+    )
+}
+
+/// Evict a chunk from the layout state
+/// Tiles shared with another loaded chunk stay in the grid
+/// 
+/// @param center_q - Chunk center q coordinate
+/// @param center_r - Chunk center r coordinate
+/// @returns true if the chunk was loaded
+#[wasm_bindgen]
+pub fn evict_chunk(center_q: i32, center_r: i32) -> bool {
+    let mut state = WFC_STATE.lock().unwrap();
+    let Some(rings) = state.chunk((center_q, center_r)).map(|record| record.rings) else {
+        return false;
+    };
+    state.evict_chunk((center_q, center_r));
+    drop(state);
+    
+    invalidate_flow_fields_for_chunk((center_q, center_r), rings);
+    true
+}
+
+/// Get the tiles of a loaded chunk
+/// 
+/// @param center_q - Chunk center q coordinate
+/// @param center_r - Chunk center r coordinate
+/// @returns JSON array in ring order: [{"q":0,"r":0,"tileType":0},...] or "null" if not loaded
+#[wasm_bindgen]
+pub fn get_chunk_tiles(center_q: i32, center_r: i32) -> String {
+    let state = WFC_STATE.lock().unwrap();
+    let Some(record) = state.chunk((center_q, center_r)) else {
+        return "null".to_string();
+    };
+    
+    let mut json_parts = Vec::new();
+    for &(q, r) in &record.tiles {
+        if let Some(tile_type) = state.get_tile(q, r) {
+            json_parts.push(format!(
+                r#"{{"q":{},"r":{},"tileType":{}}}"#,
+                q, r, tile_type as i32
+            ));
+        }
+    }
+    
+    format!("[{}]", json_parts.join(","))
+}
+
+/// Get the buildings stored with a loaded chunk
+/// 
+/// @param center_q - Chunk center q coordinate
+/// @param center_r - Chunk center r coordinate
+/// @returns JSON array in the generate_buildings format or "null" if not loaded
+#[wasm_bindgen]
+pub fn get_chunk_buildings(center_q: i32, center_r: i32) -> String {
+    let state = WFC_STATE.lock().unwrap();
+    state
+        .chunk((center_q, center_r))
+        .map_or("null".to_string(), |record| buildings_to_json(&record.buildings))
+}
+
+/// Get the centers of all loaded chunks
+/// 
+/// @returns JSON array sorted by (q, r): [{"q":0,"r":0,"rings":5},...]
+#[wasm_bindgen]
+pub fn get_loaded_chunks() -> String {
+    let state = WFC_STATE.lock().unwrap();
+    let mut centers: Vec<(i32, i32)> = state.chunk_centers().collect();
+    centers.sort();
+    
+    let mut json_parts = Vec::new();
+    for (q, r) in centers {
+        if let Some(record) = state.chunk((q, r)) {
+            json_parts.push(format!(r#"{{"q":{},"r":{},"rings":{}}}"#, q, r, record.rings));
+        }
+    }
+    
+    format!("[{}]", json_parts.join(","))
+}
+
+/// Get approximate memory usage of the layout state
+/// 
+/// @returns JSON string: {"chunks":0,"tiles":0,"preConstraints":0,"estimatedBytes":0}
+#[wasm_bindgen]
+pub fn get_memory_stats() -> String {
+    let state = WFC_STATE.lock().unwrap();
+    let stats = state.memory_stats();
+    
+    format!(
+        r#"{{"chunks":{},"tiles":{},"preConstraints":{},"estimatedBytes":{}}}"#,
+        stats.chunks, stats.tiles, stats.pre_constraints, stats.estimated_bytes
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chunks::chunk_neighbor_centers;
+    
+    const RINGS: i32 = 4;
+    
+    fn generate_all(centers: &[(i32, i32)], params: &ChunkGenerationParams) -> HashMap<(i32, i32), GeneratedChunk> {
+        centers
+            .iter()
+            .map(|&(q, r)| ((q, r), generate_chunk_tiles(7, q, r, RINGS, params, &HashMap::new())))
+            .collect()
+    }
+    
+    /// Generate every chunk through the wasm export, then read it back and evict it
+    fn generate_and_evict(centers: &[(i32, i32)], params_json: &str) -> HashMap<(i32, i32), String> {
+        let outputs: Vec<String> = centers
+            .iter()
+            .map(|&(q, r)| generate_chunk(q, r, RINGS, params_json.to_string()))
+            .collect();
+        centers
+            .iter()
+            .zip(outputs)
+            .map(|(&(q, r), output)| {
+                let read_back = format!("{} {} {}", output, get_chunk_tiles(q, r), get_chunk_buildings(q, r));
+                assert!(evict_chunk(q, r));
+                ((q, r), read_back)
+            })
+            .collect()
+    }
+    
+    #[test]
+    fn chunks_are_byte_identical_in_any_visit_order() {
+        WFC_STATE.lock().unwrap().set_world_seed(7);
+        let mut centers = vec![(0, 0)];
+        centers.extend(chunk_neighbor_centers(0, 0, RINGS));
+        let mut reversed = centers.clone();
+        reversed.reverse();
+        
+        for params_json in ["{}", r#"{"terrain":"noise","rivers":true,"roadsPerBorder":2}"#] {
+            let first = generate_and_evict(&centers, params_json);
+            let backwards = generate_and_evict(&reversed, params_json);
+            // Generated after its neighbors, then alone with nothing loaded around it
+            let alone = generate_and_evict(&[(0, 0)], params_json);
+            for center in &centers {
+                assert_eq!(first[center], backwards[center], "chunk {:?} with {}", center, params_json);
+            }
+            assert_eq!(first[&(0, 0)], alone[&(0, 0)], "center chunk with {}", params_json);
+            // The comparison covers buildings, not just terrain
+            assert!(first.values().any(|output| output.contains(r#"{"id":0,"#)), "no buildings with {}", params_json);
+        }
+    }
+    
+    #[test]
+    fn shaped_chunk_covers_exactly_its_shape() {
+        let params_json = r#"{"shape":{"type":"rectangle","width":12,"height":7},"terrain":"noise","rivers":true}"#;
+        let params = ChunkGenerationParams::from_json(params_json);
+        let chunk = generate_chunk_tiles(7, 3, -2, RINGS, &params, &HashMap::new());
+        let expected: Vec<(i32, i32)> = params
+            .shape
+            .unwrap()
+            .hexes()
+            .into_iter()
+            .map(|(q, r)| (q + 3, r - 2))
+            .collect();
+        let hexes: Vec<(i32, i32)> = chunk.tiles.iter().map(|(hex, _)| *hex).collect();
+        assert_eq!(hexes, expected);
+        assert!(chunk.rivers.iter().all(|hex| expected.contains(hex)));
+    }
+    
+    #[test]
+    fn bridged_rivers_stay_recorded_as_water() {
+        let mut centers = vec![(0, 0)];
+        centers.extend(chunk_neighbor_centers(0, 0, RINGS));
+        let params = ChunkGenerationParams::from_json(r#"{"terrain":"noise","rivers":true,"roadsPerBorder":2}"#);
+        for (center, chunk) in generate_all(&centers, &params) {
+            let tiles: HashMap<(i32, i32), TileType> = chunk.tiles.iter().copied().collect();
+            for hex in chunk.rivers.iter().chain(chunk.bridges.iter()) {
+                assert!(chunk.water.contains(hex), "river hex {:?} of {:?}", hex, center);
+            }
+            for hex in &chunk.water {
+                assert_ne!(tiles.get(hex), Some(&TileType::Building), "building on water at {:?}", hex);
+            }
+        }
+    }
+}
@@ -0,0 +1,185 @@
+/// Grid shape module
+/// 
+/// **Learning Point**: Axial coordinates make every map shape a simple loop. A hexagon
+/// is the rings around a center, a parallelogram is a plain q/r rectangle in axial space,
+/// a triangle keeps q + r below its size, and a rectangle on screen is a rectangle in
+/// offset coordinates (every other row or column shoved by half a hex) converted back
+/// to axial. Offset coordinates are only used at the edges (map files, designer tools);
+/// all generation keeps working in axial.
+
+use wasm_bindgen::prelude::*;
+use crate::hex_utils::generate_hex_grid;
+use crate::json::{json_int_field, json_string_field, coords_to_json};
+
+/// Largest width/height/size of a shaped grid (keeps one call from allocating millions of hexes)
+const MAX_SHAPE_SIDE: i32 = 256;
+
+/// Offset coordinate layout (which rows or columns are shoved by half a hex)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OffsetLayout {
+    /// Pointy hexes, odd rows shoved right
+    OddR,
+    /// Pointy hexes, even rows shoved right
+    EvenR,
+    /// Flat hexes, odd columns shoved down
+    OddQ,
+    /// Flat hexes, even columns shoved down
+    EvenQ,
+}
+
+impl OffsetLayout {
+    pub fn from_str(value: &str) -> Option<OffsetLayout> {
+        match value {
+            "odd-r" => Some(OffsetLayout::OddR),
+            "even-r" => Some(OffsetLayout::EvenR),
+            "odd-q" => Some(OffsetLayout::OddQ),
+            "even-q" => Some(OffsetLayout::EvenQ),
+            _ => None,
+        }
+    }
+    
+    /// Axial (q, r) to offset (col, row)
+    /// `x & 1` is the parity for negative values too, so the shove stays symmetric around 0
+    pub fn axial_to_offset(&self, q: i32, r: i32) -> (i32, i32) {
+        match self {
+            OffsetLayout::OddR => (q + (r - (r & 1)) / 2, r),
+            OffsetLayout::EvenR => (q + (r + (r & 1)) / 2, r),
+            OffsetLayout::OddQ => (q, r + (q - (q & 1)) / 2),
+            OffsetLayout::EvenQ => (q, r + (q + (q & 1)) / 2),
+        }
+    }
+    
+    /// Offset (col, row) to axial (q, r)
+    pub fn offset_to_axial(&self, col: i32, row: i32) -> (i32, i32) {
+        match self {
+            OffsetLayout::OddR => (col - (row - (row & 1)) / 2, row),
+            OffsetLayout::EvenR => (col - (row + (row & 1)) / 2, row),
+            OffsetLayout::OddQ => (col, row - (col - (col & 1)) / 2),
+            OffsetLayout::EvenQ => (col, row - (col + (col & 1)) / 2),
+        }
+    }
+}
+
+/// Shape of a generated grid
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GridShape {
+    /// All hexes within `radius` of the origin
+    Hexagon { radius: i32 },
+    /// `width` x `height` hexes in offset coordinates, origin at offset (0, 0)
+    Rectangle { width: i32, height: i32, offset: OffsetLayout },
+    /// `width` x `height` hexes along the q and r axes
+    Parallelogram { width: i32, height: i32 },
+    /// Hexes with q >= 0, r >= 0 and q + r < size (relative to the origin)
+    Triangle { size: i32 },
+}
+
+/// A shape placed on the axial grid
+/// 
+/// Parsed from JSON: {"type":"hexagon","radius":5} | {"type":"rectangle","width":20,
+///                   "height":12,"offset":"odd-r"} | {"type":"parallelogram","width":10,
+///                   "height":10} | {"type":"triangle","size":10}, each with optional
+///                   "originQ":0,"originR":0 (the hexagon center, otherwise the first hex)
+/// The origin translates the shape in axial space.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PlacedShape {
+    pub shape: GridShape,
+    pub origin: (i32, i32),
+}
+
+impl PlacedShape {
+    /// Parse a shape object; None if the type is missing or unknown
+    pub fn from_json(shape_json: &str) -> Option<Self> {
+        let side = |key: &str, default: i32| json_int_field(shape_json, key).unwrap_or(default).clamp(0, MAX_SHAPE_SIDE);
+        let shape = match json_string_field(shape_json, "type")?.as_str() {
+            "hexagon" => GridShape::Hexagon { radius: side("radius", 5) },
+            "rectangle" => GridShape::Rectangle {
+                width: side("width", 10),
+                height: side("height", 10),
+                offset: json_string_field(shape_json, "offset")
+                    .and_then(|value| OffsetLayout::from_str(&value))
+                    .unwrap_or(OffsetLayout::OddR),
+            },
+            "parallelogram" => GridShape::Parallelogram {
+                width: side("width", 10),
+                height: side("height", 10),
+            },
+            "triangle" => GridShape::Triangle { size: side("size", 10) },
+            _ => return None,
+        };
+        Some(PlacedShape {
+            shape,
+            origin: (
+                json_int_field(shape_json, "originQ").unwrap_or(0),
+                json_int_field(shape_json, "originR").unwrap_or(0),
+            ),
+        })
+    }
+    
+    pub fn type_name(&self) -> &'static str {
+        match self.shape {
+            GridShape::Hexagon { .. } => "hexagon",
+            GridShape::Rectangle { .. } => "rectangle",
+            GridShape::Parallelogram { .. } => "parallelogram",
+            GridShape::Triangle { .. } => "triangle",
+        }
+    }
+    
+    /// Hexes of the shape in a fixed order (ring order for hexagons, row by row otherwise)
+    pub fn hexes(&self) -> Vec<(i32, i32)> {
+        let (origin_q, origin_r) = self.origin;
+        match self.shape {
+            GridShape::Hexagon { radius } => generate_hex_grid(radius, origin_q, origin_r)
+                .iter()
+                .map(|hex| (hex.q, hex.r))
+                .collect(),
+            GridShape::Rectangle { width, height, offset } => (0..height)
+                .flat_map(|row| (0..width).map(move |col| offset.offset_to_axial(col, row)))
+                .map(|(q, r)| (origin_q + q, origin_r + r))
+                .collect(),
+            GridShape::Parallelogram { width, height } => (0..height)
+                .flat_map(|r| (0..width).map(move |q| (origin_q + q, origin_r + r)))
+                .collect(),
+            GridShape::Triangle { size } => (0..size)
+                .flat_map(|r| (0..size - r).map(move |q| (origin_q + q, origin_r + r)))
+                .collect(),
+        }
+    }
+}
+
+/// Convert axial coordinates to offset coordinates
+/// 
+/// @param offset - "odd-r", "even-r", "odd-q" or "even-q" (unknown values use "odd-r")
+/// @param q - Hex q coordinate (axial)
+/// @param r - Hex r coordinate (axial)
+/// @returns Int32Array [col, row]
+#[wasm_bindgen]
+pub fn axial_to_offset(offset: String, q: i32, r: i32) -> Vec<i32> {
+    let layout = OffsetLayout::from_str(&offset).unwrap_or(OffsetLayout::OddR);
+    let (col, row) = layout.axial_to_offset(q, r);
+    vec![col, row]
+}
+
+/// Convert offset coordinates to axial coordinates
+/// 
+/// @param offset - "odd-r", "even-r", "odd-q" or "even-q" (unknown values use "odd-r")
+/// @param col - Offset column
+/// @param row - Offset row
+/// @returns Int32Array [q, r]
+#[wasm_bindgen]
+pub fn offset_to_axial(offset: String, col: i32, row: i32) -> Vec<i32> {
+    let layout = OffsetLayout::from_str(&offset).unwrap_or(OffsetLayout::OddR);
+    let (q, r) = layout.offset_to_axial(col, row);
+    vec![q, r]
+}
+
+/// Get the hexes of a grid shape
+/// 
+/// @param shape_json - JSON object (see PlacedShape::from_json)
+/// @returns JSON array of hexes: [{"q":0,"r":0},...], empty for an unknown shape type
+#[wasm_bindgen]
+pub fn get_grid_shape_hexes(shape_json: String) -> String {
+    let hexes = PlacedShape::from_json(&shape_json)
+        .map(|shape| shape.hexes())
+        .unwrap_or_default();
+    coords_to_json(&hexes)
+}
@@ -0,0 +1,258 @@
+/// Hex layout module
+/// 
+/// **Learning Point**: A layout maps between axial hex coordinates and world space
+/// (x, z on the BabylonJS ground plane). Orientation supplies the forward matrix
+/// (hex -> world) and its inverse (world -> hex), so picking is the exact inverse of
+/// placement. The babylon-chunks scene uses HexLayout::scene(hexSize), which reproduces
+/// the hexSize / 1.34 scaling and doubled spacing from hexUtils.ts.
+
+use wasm_bindgen::prelude::*;
+use crate::hex_utils::{parse_valid_terrain_json, cube_round};
+use crate::json::{json_number_field, json_string_field};
+
+/// Scale from TILE_CONFIG.hexSize to the scene's hex size (see hexUtils.ts hexToWorld)
+const SCENE_SIZE_DIVISOR: f64 = 1.34;
+
+/// World coordinates beyond this are clamped before picking (matches hexUtils.ts worldToHex)
+const MAX_WORLD_COORD: f64 = 1e6;
+
+/// Hex orientation
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Orientation {
+    /// Vertex pointing along +z
+    Pointy,
+    /// Edge facing +z
+    Flat,
+}
+
+impl Orientation {
+    pub fn from_str(value: &str) -> Option<Orientation> {
+        match value {
+            "pointy" => Some(Orientation::Pointy),
+            "flat" => Some(Orientation::Flat),
+            _ => None,
+        }
+    }
+    
+    /// Forward matrix [f0, f1, f2, f3]: x = f0 * q + f1 * r, z = f2 * q + f3 * r
+    fn forward(&self) -> [f64; 4] {
+        let sqrt3 = 3.0_f64.sqrt();
+        match self {
+            Orientation::Pointy => [sqrt3, sqrt3 / 2.0, 0.0, 1.5],
+            Orientation::Flat => [1.5, 0.0, sqrt3 / 2.0, sqrt3],
+        }
+    }
+    
+    /// Inverse of the forward matrix
+    fn inverse(&self) -> [f64; 4] {
+        let sqrt3 = 3.0_f64.sqrt();
+        match self {
+            Orientation::Pointy => [sqrt3 / 3.0, -1.0 / 3.0, 0.0, 2.0 / 3.0],
+            Orientation::Flat => [2.0 / 3.0, 0.0, -1.0 / 3.0, sqrt3 / 3.0],
+        }
+    }
+    
+    /// Angle of corner 0 in sixths of a full turn
+    fn start_angle(&self) -> f64 {
+        match self {
+            Orientation::Pointy => 0.5,
+            Orientation::Flat => 0.0,
+        }
+    }
+}
+
+/// Mapping between hex coordinates and world space
+/// 
+/// Parsed from JSON: {"orientation":"pointy","hexSize":1.0,"originX":0.0,"originZ":0.0,"spacing":1.0}
+pub struct HexLayout {
+    pub orientation: Orientation,
+    /// Distance from a hex center to its corners
+    pub size: f64,
+    /// World position of hex (0, 0)
+    pub origin: (f64, f64),
+    /// Center distance multiplier; values above 1 leave gaps between tiles
+    pub spacing: f64,
+}
+
+impl Default for HexLayout {
+    fn default() -> Self {
+        HexLayout {
+            orientation: Orientation::Pointy,
+            size: 1.0,
+            origin: (0.0, 0.0),
+            spacing: 1.0,
+        }
+    }
+}
+
+impl HexLayout {
+    /// Layout used by the babylon-chunks scene for a TILE_CONFIG.hexSize
+    pub fn scene(hex_size: f64) -> Self {
+        HexLayout {
+            size: hex_size / SCENE_SIZE_DIVISOR,
+            spacing: 2.0,
+            ..HexLayout::default()
+        }
+    }
+    
+    pub fn from_json(layout_json: &str) -> Self {
+        let defaults = HexLayout::default();
+        HexLayout {
+            orientation: json_string_field(layout_json, "orientation")
+                .and_then(|value| Orientation::from_str(&value))
+                .unwrap_or(defaults.orientation),
+            size: json_number_field(layout_json, "hexSize")
+                .filter(|size| size.is_finite() && *size > 0.0)
+                .unwrap_or(defaults.size),
+            origin: (
+                json_number_field(layout_json, "originX").unwrap_or(defaults.origin.0),
+                json_number_field(layout_json, "originZ").unwrap_or(defaults.origin.1),
+            ),
+            spacing: json_number_field(layout_json, "spacing")
+                .filter(|spacing| spacing.is_finite() && *spacing > 0.0)
+                .unwrap_or(defaults.spacing),
+        }
+    }
+    
+    /// World position of a hex center
+    pub fn hex_to_world(&self, q: i32, r: i32) -> (f64, f64) {
+        let [f0, f1, f2, f3] = self.orientation.forward();
+        let scale = self.size * self.spacing;
+        let (q, r) = (q as f64, r as f64);
+        (
+            self.origin.0 + (f0 * q + f1 * r) * scale,
+            self.origin.1 + (f2 * q + f3 * r) * scale,
+        )
+    }
+    
+    /// Fractional axial coordinates of a world position
+    pub fn world_to_fractional_hex(&self, x: f64, z: f64) -> (f64, f64) {
+        let [b0, b1, b2, b3] = self.orientation.inverse();
+        let scale = self.size * self.spacing;
+        let (px, pz) = ((x - self.origin.0) / scale, (z - self.origin.1) / scale);
+        (b0 * px + b1 * pz, b2 * px + b3 * pz)
+    }
+    
+    /// Hex containing a world position
+    /// Non-finite input picks hex (0, 0); very large input is clamped first
+    pub fn world_to_hex(&self, x: f64, z: f64) -> (i32, i32) {
+        if !x.is_finite() || !z.is_finite() {
+            return (0, 0);
+        }
+        let (q, r) = self.world_to_fractional_hex(
+            x.clamp(-MAX_WORLD_COORD, MAX_WORLD_COORD),
+            z.clamp(-MAX_WORLD_COORD, MAX_WORLD_COORD),
+        );
+        cube_round(q, r)
+    }
+    
+    /// The six corner positions of a hex, counter-clockwise from the start angle
+    pub fn hex_corners(&self, q: i32, r: i32) -> [(f64, f64); 6] {
+        let (center_x, center_z) = self.hex_to_world(q, r);
+        let start_angle = self.orientation.start_angle();
+        let mut corners = [(0.0, 0.0); 6];
+        for (index, corner) in corners.iter_mut().enumerate() {
+            let angle = std::f64::consts::TAU * (start_angle + index as f64) / 6.0;
+            *corner = (center_x + self.size * angle.cos(), center_z + self.size * angle.sin());
+        }
+        corners
+    }
+}
+
+/// Convert one hex to its world position
+/// 
+/// @param layout_json - JSON object (see HexLayout::from_json), "{}" for a unit pointy layout
+/// @param q - Hex q coordinate
+/// @param r - Hex r coordinate
+/// @returns Float64Array [x, z]
+#[wasm_bindgen]
+pub fn hex_to_world(layout_json: String, q: i32, r: i32) -> Vec<f64> {
+    let (x, z) = HexLayout::from_json(&layout_json).hex_to_world(q, r);
+    vec![x, z]
+}
+
+/// Pick the hex containing a world position
+/// 
+/// **Learning Point**: Exact inverse of hex_to_world followed by cube rounding, so
+/// the avatar's tile and mouse picking agree with where tiles are drawn.
+/// 
+/// @param layout_json - JSON object (see HexLayout::from_json)
+/// @param x - World x coordinate
+/// @param z - World z coordinate
+/// @returns Int32Array [q, r]
+#[wasm_bindgen]
+pub fn world_to_hex(layout_json: String, x: f64, z: f64) -> Vec<i32> {
+    let (q, r) = HexLayout::from_json(&layout_json).world_to_hex(x, z);
+    vec![q, r]
+}
+
+/// Get the corner positions of a hex
+/// 
+/// @param layout_json - JSON object (see HexLayout::from_json)
+/// @param q - Hex q coordinate
+/// @param r - Hex r coordinate
+/// @returns Float64Array of 12 values: [x0, z0, x1, z1, ..., x5, z5]
+#[wasm_bindgen]
+pub fn hex_corners(layout_json: String, q: i32, r: i32) -> Vec<f64> {
+    HexLayout::from_json(&layout_json)
+        .hex_corners(q, r)
+        .iter()
+        .flat_map(|&(x, z)| [x, z])
+        .collect()
+}
+
+/// Batch convert hexes to world positions over typed arrays
+/// 
+/// @param layout_json - JSON object (see HexLayout::from_json)
+/// @param hex_coords - Int32Array of pairs: [q0, r0, q1, r1, ...] (a trailing odd value is ignored)
+/// @returns Float64Array of pairs: [x0, z0, x1, z1, ...]
+#[wasm_bindgen]
+pub fn batch_hex_to_world_typed(layout_json: String, hex_coords: &[i32]) -> Vec<f64> {
+    let layout = HexLayout::from_json(&layout_json);
+    let mut positions = Vec::with_capacity(hex_coords.len());
+    for pair in hex_coords.chunks_exact(2) {
+        let (x, z) = layout.hex_to_world(pair[0], pair[1]);
+        positions.push(x);
+        positions.push(z);
+    }
+    positions
+}
+
+/// Batch pick hexes for world positions over typed arrays
+/// 
+/// @param layout_json - JSON object (see HexLayout::from_json)
+/// @param positions - Float64Array of pairs: [x0, z0, x1, z1, ...] (a trailing odd value is ignored)
+/// @returns Int32Array of pairs: [q0, r0, q1, r1, ...]
+#[wasm_bindgen]
+pub fn batch_world_to_hex(layout_json: String, positions: &[f64]) -> Vec<i32> {
+    let layout = HexLayout::from_json(&layout_json);
+    let mut hex_coords = Vec::with_capacity(positions.len());
+    for pair in positions.chunks_exact(2) {
+        let (q, r) = layout.world_to_hex(pair[0], pair[1]);
+        hex_coords.push(q);
+        hex_coords.push(r);
+    }
+    hex_coords
+}
+
+/// Batch convert hex coordinates to world positions using the scene layout
+/// 
+/// @param hex_coords_json - JSON array of hex coordinates: [{"q":0,"r":0},...]
+/// @param hex_size - TILE_CONFIG.hexSize (see HexLayout::scene)
+/// @returns JSON array with world positions: [{"q":0,"r":0,"x":0.0,"z":0.0},...]
+#[wasm_bindgen]
+pub fn batch_hex_to_world(hex_coords_json: String, hex_size: f64) -> String {
+    let layout = HexLayout::scene(hex_size);
+    let hex_coords = parse_valid_terrain_json(&hex_coords_json);
+    
+    let mut json_parts = Vec::new();
+    for (q, r) in hex_coords {
+        let (x, z) = layout.hex_to_world(q, r);
+        json_parts.push(format!(
+            r#"{{"q":{},"r":{},"x":{},"z":{}}}"#,
+            q, r, x, z
+        ));
+    }
+    
+    format!("[{}]", json_parts.join(","))
+}
@@ -1,7 +1,7 @@
 /// Hex coordinate utilities module
 
-use std::collections::HashSet;
-use crate::types::{HexCoord, CubeCoord};
+use std::collections::{HashSet, VecDeque};
+use crate::types::{HexCoord, CubeCoord, HexAxis, TileType};
 
 /// Cube directions for hex grid navigation
 pub const CUBE_DIRECTIONS: [CubeCoord; 6] = [
@@ -23,6 +23,13 @@ pub fn hex_distance(q1: i32, r1: i32, q2: i32, r2: i32) -> i32 {
     ((q1 - q2).abs() + (r1 - r2).abs() + (s1 - s2).abs()) / 2
 }
 
+/// Convert (possibly fractional) axial coordinates to continuous 2D coordinates
+/// Pointy-top layout with unit hex size: x = √3 * (q + r/2), y = 3/2 * r
+pub fn axial_to_plane(q: f64, r: f64) -> (f64, f64) {
+    let sqrt3 = 3.0_f64.sqrt();
+    (sqrt3 * (q + r / 2.0), 1.5 * r)
+}
+
 /// Get all 6 hex neighbors of a coordinate (axial)
 pub fn get_hex_neighbors(q: i32, r: i32) -> Vec<(i32, i32)> {
     vec![
@@ -35,6 +42,34 @@ pub fn get_hex_neighbors(q: i32, r: i32) -> Vec<(i32, i32)> {
     ]
 }
 
+/// Split hexes into connected components (largest first, ties by smallest hex)
+pub fn connected_components(hexes: &HashSet<(i32, i32)>) -> Vec<Vec<(i32, i32)>> {
+    let mut sorted: Vec<(i32, i32)> = hexes.iter().copied().collect();
+    sorted.sort();
+    
+    let mut seen: HashSet<(i32, i32)> = HashSet::new();
+    let mut components = Vec::new();
+    for start in sorted {
+        if !seen.insert(start) {
+            continue;
+        }
+        let mut component = vec![start];
+        let mut queue = VecDeque::from([start]);
+        while let Some((q, r)) = queue.pop_front() {
+            for neighbor in get_hex_neighbors(q, r) {
+                if hexes.contains(&neighbor) && seen.insert(neighbor) {
+                    component.push(neighbor);
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        components.push(component);
+    }
+    
+    components.sort_by_key(|component| std::cmp::Reverse(component.len()));
+    components
+}
+
 /// Convert axial coordinates to cube coordinates
 /// Cube coordinates: (q, r, s) where q + r + s = 0
 pub fn axial_to_cube(q: i32, r: i32) -> CubeCoord {
@@ -52,6 +87,30 @@ pub fn cube_distance(a: CubeCoord, b: CubeCoord) -> i32 {
     (a.q - b.q).abs().max((a.r - b.r).abs()).max((a.s - b.s).abs())
 }
 
+/// Round fractional axial coordinates to the containing hex
+/// 
+/// **Learning Point**: Rounding q and r independently can land on a neighbor. Rounding
+/// all three cube components and then recomputing the one with the largest rounding
+/// error from the other two keeps q + r + s = 0 and always picks the nearest hex.
+pub fn cube_round(q: f64, r: f64) -> (i32, i32) {
+    let s = -q - r;
+    let mut rounded_q = q.round();
+    let mut rounded_r = r.round();
+    let rounded_s = s.round();
+    
+    let q_diff = (rounded_q - q).abs();
+    let r_diff = (rounded_r - r).abs();
+    let s_diff = (rounded_s - s).abs();
+    
+    if q_diff > r_diff && q_diff > s_diff {
+        rounded_q = -rounded_r - rounded_s;
+    } else if r_diff > s_diff {
+        rounded_r = -rounded_q - rounded_s;
+    }
+    
+    (rounded_q as i32, rounded_r as i32)
+}
+
 /// Add two cube coordinates
 pub fn cube_add(a: CubeCoord, b: CubeCoord) -> CubeCoord {
     CubeCoord {
@@ -75,6 +134,35 @@ pub fn cube_neighbor(cube: CubeCoord, direction: usize) -> CubeCoord {
     cube_add(cube, CUBE_DIRECTIONS[direction % 6])
 }
 
+/// Rotate an axial coordinate around the origin by `steps` x 60 degrees
+/// One step turns each CUBE_DIRECTIONS entry into the next one (the same turn
+/// chunk_neighbor_centers walks its sides with); negative steps turn back
+pub fn hex_rotate(q: i32, r: i32, steps: i32) -> (i32, i32) {
+    let mut hex = (q, r);
+    for _ in 0..steps.rem_euclid(6) {
+        // (q, r, s) -> (-s, -q, -r)
+        hex = (hex.0 + hex.1, -hex.0);
+    }
+    hex
+}
+
+/// Mirror an axial coordinate across an axis through the origin
+pub fn hex_reflect(q: i32, r: i32, axis: HexAxis) -> (i32, i32) {
+    let s = -q - r;
+    match axis {
+        HexAxis::Q => (q, s),
+        HexAxis::R => (s, r),
+        HexAxis::S => (r, q),
+    }
+}
+
+/// Apply one of the 12 symmetries of the hex grid around the origin
+/// 0-5 rotate by that many steps, 6-11 reflect across the q axis first and then rotate by (symmetry - 6)
+pub fn hex_symmetry(q: i32, r: i32, symmetry: usize) -> (i32, i32) {
+    let (q, r) = if symmetry >= 6 { hex_reflect(q, r, HexAxis::Q) } else { (q, r) };
+    hex_rotate(q, r, (symmetry % 6) as i32)
+}
+
 /// Generate ring of tiles at specific layer (radius) around center
 pub fn cube_ring(center: CubeCoord, radius: i32) -> Vec<CubeCoord> {
     if radius == 0 {
@@ -102,8 +190,12 @@ pub fn cube_ring(center: CubeCoord, radius: i32) -> Vec<CubeCoord> {
 /// Generate hexagon grid up to max_layer
 /// Returns all hex coordinates within the hexagon pattern
 /// Matches TypeScript implementation using cube coordinates
+/// 
+/// Coordinates are returned in ring order (center first, then each ring as produced
+/// by cube_ring) so seeded generation over the grid is reproducible.
 pub fn generate_hex_grid(max_layer: i32, center_q: i32, center_r: i32) -> Vec<HexCoord> {
     let mut grid_set = HashSet::new();
+    let mut grid = Vec::new();
     let center_cube = CubeCoord {
         q: center_q,
         r: center_r,
@@ -114,17 +206,10 @@ pub fn generate_hex_grid(max_layer: i32, center_q: i32, center_r: i32) -> Vec<He
     for layer in 0..=max_layer {
         let ring = cube_ring(center_cube, layer);
         for cube in ring {
-            // Use tuple of coordinates as hashable key for the set
-            grid_set.insert((cube.q, cube.r, cube.s));
-        }
-    }
-    
-    // Convert set to array of HexCoord, verifying cube coordinate constraint
-    let mut grid = Vec::new();
-    for (q, r, s) in grid_set {
-        // Verify cube coordinate is valid (q + r + s = 0)
-        if q + r + s == 0 {
-            grid.push(HexCoord { q, r });
+            // Verify cube coordinate is valid (q + r + s = 0) and skip duplicates
+            if cube.q + cube.r + cube.s == 0 && grid_set.insert((cube.q, cube.r)) {
+                grid.push(HexCoord { q: cube.q, r: cube.r });
+            }
         }
     }
     
@@ -205,74 +290,28 @@ pub fn parse_valid_terrain_json(valid_terrain_json: &str) -> HashSet<(i32, i32)>
     valid_terrain
 }
 
-/// Parse path JSON and return vector of coordinates
-/// Format: [{"q":0,"r":0},{"q":1,"r":0},...]
-pub fn parse_path_json(path_json: &str) -> Vec<(i32, i32)> {
-    let mut path = Vec::new();
+/// Parse tile type list JSON into a vector of TileType
+/// Format: [0,2,3] (integers matching the TileType enum)
+/// Unknown tile type values are skipped
+pub fn parse_tile_types_json(tile_types_json: &str) -> Vec<TileType> {
+    let mut tile_types = Vec::new();
     
-    if path_json == "null" || path_json.is_empty() {
-        return path;
-    }
-    
-    let trimmed = path_json.trim();
-    if trimmed == "[]" || trimmed.len() < 3 {
-        return path;
+    let trimmed = tile_types_json.trim();
+    if trimmed.is_empty() || trimmed == "[]" {
+        return tile_types;
     }
     
-    // Simple JSON parsing: find all {"q":X,"r":Y} patterns
-    let mut i = 0;
-    let chars: Vec<char> = trimmed.chars().collect();
-    while i < chars.len() {
-        if chars[i] == '{' {
-            let mut q_value: Option<i32> = None;
-            let mut r_value: Option<i32> = None;
-            
-            i += 1;
-            while i < chars.len() && chars[i] != '}' {
-                if i + 3 < chars.len() && chars[i] == '"' && chars[i + 1] == 'q' && chars[i + 2] == '"' {
-                    i += 3;
-                    while i < chars.len() && (chars[i] == ':' || chars[i] == ' ' || chars[i] == '\t') {
-                        i += 1;
-                    }
-                    if i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '-') {
-                        let start = i;
-                        i += 1;
-                        while i < chars.len() && chars[i].is_ascii_digit() {
-                            i += 1;
-                        }
-                        let num_str: String = chars[start..i].iter().collect();
-                        if let Ok(num) = num_str.parse::<i32>() {
-                            q_value = Some(num);
-                        }
-                    }
-                } else if i + 3 < chars.len() && chars[i] == '"' && chars[i + 1] == 'r' && chars[i + 2] == '"' {
-                    i += 3;
-                    while i < chars.len() && (chars[i] == ':' || chars[i] == ' ' || chars[i] == '\t') {
-                        i += 1;
-                    }
-                    if i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '-') {
-                        let start = i;
-                        i += 1;
-                        while i < chars.len() && chars[i].is_ascii_digit() {
-                            i += 1;
-                        }
-                        let num_str: String = chars[start..i].iter().collect();
-                        if let Ok(num) = num_str.parse::<i32>() {
-                            r_value = Some(num);
-                        }
-                    }
-                } else {
-                    i += 1;
-                }
-            }
-            
-            if let (Some(q), Some(r)) = (q_value, r_value) {
-                path.push((q, r));
+    // Simple JSON parsing: split on anything that cannot be part of an integer
+    for part in trimmed.split(|c: char| !(c.is_ascii_digit() || c == '-')) {
+        if part.is_empty() {
+            continue;
+        }
+        if let Some(tile_type) = part.parse::<i32>().ok().and_then(TileType::from_i32) {
+            if !tile_types.contains(&tile_type) {
+                tile_types.push(tile_type);
             }
         }
-        i += 1;
     }
     
-    path
+    tile_types
 }
-
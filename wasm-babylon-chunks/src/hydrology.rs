@@ -0,0 +1,208 @@
+/// Hydrology module
+/// 
+/// **Learning Point**: Rivers are traced downhill over the world-space elevation field
+/// (noise::elevation_at). Sources are picked by hashing world coordinates, and each river
+/// follows the steepest descent until it reaches low water, pools into a lake at a local
+/// minimum, or runs out of length. Because sources and elevation depend only on the
+/// world seed and the coordinates, a chunk can trace every river that might reach it
+/// (sources within `max_length` of its border) and the river hexes on both sides of a
+/// chunk border always agree.
+
+use wasm_bindgen::prelude::*;
+use std::collections::{HashMap, HashSet};
+use crate::state::WFC_STATE;
+use crate::hex_utils::{generate_hex_grid, hex_distance, CUBE_DIRECTIONS};
+use crate::noise::{elevation_at, NoiseConfig};
+use crate::seed::{hash_coords, mix64};
+use crate::json::{json_int_field, json_number_field, coords_to_json};
+
+/// Salt separating river source hashing from the other per-hex hashes
+const RIVER_SOURCE_SALT: u64 = 0x41BE_5011;
+
+/// River tracing settings
+/// 
+/// Parsed from JSON: {"riverSourceChance":0.004,"riverSourceElevation":0.6,
+///                    "riverMaxLength":48,"lakeDepth":0.02}
+pub struct HydrologyConfig {
+    /// Probability that a high enough hex starts a river
+    pub source_chance: f64,
+    /// Minimum elevation of a river source
+    pub source_elevation: f64,
+    /// Maximum river length in hexes (also how far outside a chunk sources are searched)
+    pub max_length: i32,
+    /// Neighbors of a river's final pit within this elevation margin join its lake
+    pub lake_depth: f64,
+}
+
+impl Default for HydrologyConfig {
+    fn default() -> Self {
+        HydrologyConfig {
+            source_chance: 0.004,
+            source_elevation: 0.6,
+            max_length: 48,
+            lake_depth: 0.02,
+        }
+    }
+}
+
+impl HydrologyConfig {
+    pub fn from_json(config_json: &str) -> Self {
+        let defaults = HydrologyConfig::default();
+        HydrologyConfig {
+            source_chance: json_number_field(config_json, "riverSourceChance")
+                .unwrap_or(defaults.source_chance)
+                .clamp(0.0, 1.0),
+            source_elevation: json_number_field(config_json, "riverSourceElevation")
+                .unwrap_or(defaults.source_elevation),
+            max_length: json_int_field(config_json, "riverMaxLength")
+                .map(|length| length.clamp(1, 256))
+                .unwrap_or(defaults.max_length),
+            lake_depth: json_number_field(config_json, "lakeDepth")
+                .unwrap_or(defaults.lake_depth)
+                .max(0.0),
+        }
+    }
+}
+
+/// River and lake hexes inside an area
+pub struct RiverSystem {
+    pub rivers: HashSet<(i32, i32)>,
+    pub lakes: HashSet<(i32, i32)>,
+}
+
+/// Elevation lookups are repeated between neighboring river steps, so cache them
+struct ElevationField<'a> {
+    world_seed: u64,
+    noise: &'a NoiseConfig,
+    cache: HashMap<(i32, i32), f64>,
+}
+
+impl ElevationField<'_> {
+    fn at(&mut self, hex: (i32, i32)) -> f64 {
+        let (world_seed, noise) = (self.world_seed, self.noise);
+        *self.cache
+            .entry(hex)
+            .or_insert_with(|| elevation_at(world_seed, hex.0, hex.1, noise))
+    }
+}
+
+/// Check whether a hex starts a river (hash test first, elevation only if it passes)
+fn is_river_source(field: &mut ElevationField, hex: (i32, i32), config: &HydrologyConfig) -> bool {
+    let roll = mix64(hash_coords(field.world_seed ^ RIVER_SOURCE_SALT, hex.0, hex.1)) as f64 / u64::MAX as f64;
+    roll < config.source_chance && field.at(hex) >= config.source_elevation
+}
+
+/// One traced river
+struct RiverTrace {
+    /// River hexes, source first
+    path: Vec<(i32, i32)>,
+    /// Lake hexes the river ends in (empty if it reaches low water or its length limit)
+    lake: Vec<(i32, i32)>,
+}
+
+/// Trace one river downhill from its source
+fn trace_river(field: &mut ElevationField, source: (i32, i32), config: &HydrologyConfig) -> RiverTrace {
+    let water_level = field.noise.water_level;
+    let mut path = vec![source];
+    let mut current = source;
+    
+    for _ in 0..config.max_length {
+        let current_elevation = field.at(current);
+        if current_elevation < water_level {
+            // Reached standing water from the terrain pass
+            break;
+        }
+        
+        // Steepest descent; ties resolve in direction order
+        let mut lowest: Option<((i32, i32), f64)> = None;
+        for offset in CUBE_DIRECTIONS.iter() {
+            let neighbor = (current.0 + offset.q, current.1 + offset.r);
+            let elevation = field.at(neighbor);
+            if lowest.is_none_or(|(_, best)| elevation < best) {
+                lowest = Some((neighbor, elevation));
+            }
+        }
+        
+        match lowest {
+            Some((next, elevation)) if elevation < current_elevation => {
+                path.push(next);
+                current = next;
+            },
+            _ => {
+                // Local minimum: pool into a lake around the pit
+                let mut lake = vec![current];
+                for offset in CUBE_DIRECTIONS.iter() {
+                    let neighbor = (current.0 + offset.q, current.1 + offset.r);
+                    if field.at(neighbor) <= current_elevation + config.lake_depth {
+                        lake.push(neighbor);
+                    }
+                }
+                return RiverTrace { path, lake };
+            },
+        }
+    }
+    
+    RiverTrace { path, lake: Vec::new() }
+}
+
+/// Find all river and lake hexes within `radius` of a center
+/// 
+/// Every source within `radius + max_length` is traced, so rivers entering the area
+/// from outside are included.
+pub fn rivers_in_area(
+    world_seed: u64,
+    center_q: i32,
+    center_r: i32,
+    radius: i32,
+    noise: &NoiseConfig,
+    config: &HydrologyConfig,
+) -> RiverSystem {
+    let mut field = ElevationField { world_seed, noise, cache: HashMap::new() };
+    let mut system = RiverSystem { rivers: HashSet::new(), lakes: HashSet::new() };
+    let radius = radius.max(0);
+    let inside = |hex: &(i32, i32)| hex_distance(center_q, center_r, hex.0, hex.1) <= radius;
+    
+    for hex in generate_hex_grid(radius + config.max_length, center_q, center_r) {
+        let source = (hex.q, hex.r);
+        if !is_river_source(&mut field, source, config) {
+            continue;
+        }
+        
+        let trace = trace_river(&mut field, source, config);
+        system.rivers.extend(trace.path.into_iter().filter(inside));
+        system.lakes.extend(trace.lake.into_iter().filter(inside));
+    }
+    
+    // A hex in a lake is lake, not river
+    system.rivers.retain(|hex| !system.lakes.contains(hex));
+    system
+}
+
+/// Trace the rivers and lakes crossing a chunk
+/// 
+/// Rivers follow the raw noise elevation (without Voronoi blending), so they match the
+/// noise terrain and continue seamlessly into neighboring chunks.
+/// 
+/// @param center_q - Chunk center q coordinate
+/// @param center_r - Chunk center r coordinate
+/// @param rings - Number of rings per chunk
+/// @param config_json - JSON object with NoiseConfig and HydrologyConfig fields, "{}" for defaults
+/// @returns JSON string: {"rivers":[{"q":0,"r":0},...],"lakes":[{"q":0,"r":0},...]}
+#[wasm_bindgen]
+pub fn get_rivers(center_q: i32, center_r: i32, rings: i32, config_json: String) -> String {
+    let world_seed = WFC_STATE.lock().unwrap().world_seed();
+    let noise = NoiseConfig::from_json(&config_json);
+    let config = HydrologyConfig::from_json(&config_json);
+    let system = rivers_in_area(world_seed, center_q, center_r, rings, &noise, &config);
+    
+    let mut rivers: Vec<(i32, i32)> = system.rivers.into_iter().collect();
+    let mut lakes: Vec<(i32, i32)> = system.lakes.into_iter().collect();
+    rivers.sort();
+    lakes.sort();
+    
+    format!(
+        r#"{{"rivers":{},"lakes":{}}}"#,
+        coords_to_json(&rivers),
+        coords_to_json(&lakes)
+    )
+}
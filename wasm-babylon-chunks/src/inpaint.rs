@@ -0,0 +1,343 @@
+/// Region inpainting module
+/// 
+/// **Learning Point**: Regenerating part of a layout is inpainting: the tiles outside the
+/// region are boundary conditions that must not change, and the new tiles must blend
+/// into them. Terrain blends by using every boundary hex as a fixed Voronoi seed next to
+/// the fresh seeds inside; roads stay continuous because every boundary road becomes a
+/// required endpoint that the new network has to reach.
+
+use wasm_bindgen::prelude::*;
+use std::collections::{HashMap, HashSet};
+use crate::state::{WfcState, WFC_STATE};
+use crate::types::{HexCoord, TileType, VoronoiSeed};
+use crate::hex_utils::{generate_hex_grid, get_hex_neighbors};
+use crate::voronoi::{pick_voronoi_seeds, assign_voronoi_regions};
+use crate::roads::{connect_endpoints, grow_road_tree, RiverCrossing, RoadNetworkParams};
+use crate::buildings::{place_buildings, BuildingRecord};
+use crate::chunks::chunk_tile_count;
+use crate::constraints::{enforce_grass_ratio, LayoutConstraints, ROAD_SEED_RATIO};
+use crate::edit::{changes_to_json, run_edit, EditOptions, MAX_REGENERATE_RADIUS};
+use crate::flow_field::invalidate_flow_fields_for_hexes;
+use crate::seed::{chunk_seed, SeededRng};
+use crate::json::{json_int_field, coords_to_json};
+
+/// What the region regeneration produced
+pub struct InpaintSummary {
+    /// Region hexes regenerated
+    pub hexes: usize,
+    /// Region hexes the roads had to reach (next to boundary roads and road-less boundary buildings)
+    pub entries: usize,
+    pub roads: usize,
+    /// Entries no route could reach (the region is split, walled off, or only reachable
+    /// across water too wide or winding for a straight bridge)
+    pub unconnected: Vec<(i32, i32)>,
+    /// Road hexes that cross water on a bridge, sorted
+    pub bridges: Vec<(i32, i32)>,
+    pub buildings: usize,
+}
+
+/// New tiles for a region
+pub struct InpaintResult {
+    /// Region tiles, sorted
+    pub tiles: Vec<((i32, i32), TileType)>,
+    /// Buildings placed in the region, in placement order
+    pub buildings: Vec<BuildingRecord>,
+    pub summary: InpaintSummary,
+}
+
+/// Regenerate the tiles of `region` with everything else held fixed
+/// 
+/// Steps:
+/// 1. Voronoi terrain: boundary hexes (outside, adjacent to the region) are seeds of
+///    their own type, listed before the new seeds so ties keep the surroundings; new seed
+///    counts are scaled from a chunk of constraints.rings to the region size
+/// 2. Forest converted to grass until the grass ratio is met
+/// 3. Roads: every entry is connected by connect_endpoints; entries cut off by water are
+///    routed again with water crossable on straight bridges (the river crossing rule of
+///    chunk generation), then the network grows to the road density
+/// 4. Buildings on the remaining land fronting any road (inside or on the boundary)
+/// 
+/// Pre-constraints inside the region win over generated tiles and are never paved over.
+pub fn inpaint_region(
+    state: &WfcState,
+    region: &HashSet<(i32, i32)>,
+    constraints: &LayoutConstraints,
+    rng: &mut SeededRng,
+) -> InpaintResult {
+    let mut hexes: Vec<(i32, i32)> = region.iter().copied().collect();
+    hexes.sort();
+    let pinned: HashMap<(i32, i32), TileType> = hexes
+        .iter()
+        .filter_map(|&(q, r)| state.get_pre_constraint(q, r).map(|tile_type| ((q, r), tile_type)))
+        .collect();
+    let mut boundary: Vec<((i32, i32), TileType)> = hexes
+        .iter()
+        .flat_map(|&(q, r)| get_hex_neighbors(q, r))
+        .filter(|hex| !region.contains(hex))
+        .filter_map(|(q, r)| state.get_tile(q, r).map(|tile_type| ((q, r), tile_type)))
+        .collect();
+    boundary.sort_by_key(|(hex, _)| *hex);
+    boundary.dedup_by_key(|(hex, _)| *hex);
+    
+    // Step 1: Voronoi terrain continued from the boundary
+    let mut seeds: Vec<VoronoiSeed> = boundary
+        .iter()
+        .map(|&((q, r), tile_type)| {
+            let natural = match tile_type {
+                TileType::Road | TileType::Building => TileType::Grass,
+                other => other,
+            };
+            VoronoiSeed { q, r, tile_type: natural }
+        })
+        .filter(|seed| !constraints.exclude_tile_types.contains(&seed.tile_type))
+        .collect();
+    let counts = constraints
+        .voronoi_seeds
+        .adjusted(&constraints.exclude_tile_types, constraints.primary_tile_type);
+    let scale = hexes.len() as f64 / chunk_tile_count(constraints.rings) as f64;
+    let scaled = |count: i32| (count as f64 * scale).round() as i32;
+    seeds.extend(pick_voronoi_seeds(&hexes, scaled(counts.forest), scaled(counts.water), scaled(counts.grass), rng));
+    if seeds.is_empty() {
+        seeds = pick_voronoi_seeds(&hexes, 0, 0, 1, rng);
+    }
+    let grid: Vec<HexCoord> = hexes.iter().map(|&(q, r)| HexCoord { q, r }).collect();
+    let mut terrain: HashMap<(i32, i32), TileType> = assign_voronoi_regions(&grid, &seeds).into_iter().collect();
+    
+    // Step 2: Grass ratio, then pre-constraints
+    if !constraints.exclude_tile_types.contains(&TileType::Grass) {
+        enforce_grass_ratio(&hexes, &mut terrain, constraints.grass_ratio, rng);
+    }
+    terrain.extend(pinned.iter().map(|(hex, tile_type)| (*hex, *tile_type)));
+    
+    // Step 3: Roads reaching every boundary road (and road-less boundary buildings)
+    let boundary_tiles: HashMap<(i32, i32), TileType> = boundary.iter().copied().collect();
+    let has_outside_road = |(q, r): (i32, i32)| {
+        get_hex_neighbors(q, r)
+            .iter()
+            .any(|neighbor| !region.contains(neighbor) && state.get_tile(neighbor.0, neighbor.1) == Some(TileType::Road))
+    };
+    let mut entries: Vec<(i32, i32)> = boundary
+        .iter()
+        .filter(|&&(hex, tile_type)| {
+            tile_type == TileType::Road || (tile_type == TileType::Building && !has_outside_road(hex))
+        })
+        .filter_map(|&((q, r), _)| {
+            // Smallest region neighbor that can carry a road
+            get_hex_neighbors(q, r)
+                .into_iter()
+                .filter(|hex| region.contains(hex))
+                .filter(|hex| pinned.get(hex).is_none_or(|&tile_type| tile_type == TileType::Road))
+                .min()
+        })
+        .collect();
+    entries.sort();
+    entries.dedup();
+    
+    let occupied: HashSet<(i32, i32)> = pinned
+        .iter()
+        .filter(|(_, &tile_type)| tile_type != TileType::Road)
+        .map(|(hex, _)| *hex)
+        .collect();
+    let params = RoadNetworkParams::default();
+    let first_pass = connect_endpoints(&entries, &terrain, &occupied, &HashSet::new(), &params);
+    let mut roads: HashSet<(i32, i32)> = first_pass.roads.iter().copied().collect();
+    let mut unconnected = first_pass.unconnected;
+    let mut bridges = Vec::new();
+    if !unconnected.is_empty() {
+        // Bridge the water rather than leave a boundary road dangling
+        let water: HashSet<(i32, i32)> = terrain
+            .iter()
+            .filter(|(_, &tile_type)| tile_type == TileType::Water)
+            .map(|(hex, _)| *hex)
+            .collect();
+        let bridge_params = RoadNetworkParams { river_crossing: RiverCrossing::Bridge, ..RoadNetworkParams::default() };
+        let mut endpoints = unconnected.clone();
+        endpoints.extend(first_pass.roads.iter().min());
+        let second_pass = connect_endpoints(&endpoints, &terrain, &occupied, &water, &bridge_params);
+        roads.extend(second_pass.roads);
+        unconnected = second_pass.unconnected;
+        bridges = second_pass.bridges;
+    }
+    
+    let land: Vec<(i32, i32)> = hexes
+        .iter()
+        .copied()
+        .filter(|hex| !occupied.contains(hex))
+        .filter(|hex| matches!(terrain.get(hex), Some(TileType::Grass) | Some(TileType::Forest) | Some(TileType::Road)))
+        .collect();
+    let target_road_count = (land.len() as f64 * constraints.road_density).floor() as usize;
+    if roads.len() < target_road_count {
+        let mut road_seeds: Vec<(i32, i32)> = roads.iter().copied().collect();
+        road_seeds.sort();
+        if road_seeds.is_empty() {
+            let seed_count = ((target_road_count as f64 * ROAD_SEED_RATIO).floor() as usize).max(1);
+            road_seeds = land.clone();
+            rng.shuffle(&mut road_seeds);
+            road_seeds.truncate(seed_count);
+        }
+        let mut valid: HashSet<(i32, i32)> = land.iter().copied().collect();
+        valid.extend(roads.iter().copied());
+        roads.extend(grow_road_tree(&road_seeds, &valid, target_road_count));
+    }
+    for hex in &roads {
+        terrain.insert(*hex, TileType::Road);
+    }
+    
+    // Step 4: Buildings fronting inside or boundary roads
+    let mut all_roads = roads.clone();
+    all_roads.extend(
+        boundary_tiles
+            .iter()
+            .filter(|(_, &tile_type)| tile_type == TileType::Road)
+            .map(|(hex, _)| *hex),
+    );
+    let buildable: HashSet<(i32, i32)> = land
+        .iter()
+        .copied()
+        .filter(|hex| !roads.contains(hex) && !pinned.contains_key(hex))
+        .collect();
+    let target_building_count = constraints.building_count.unwrap_or_else(|| {
+        let lots = buildable
+            .iter()
+            .filter(|&&(q, r)| {
+                let adjacent_roads = get_hex_neighbors(q, r).iter().filter(|neighbor| all_roads.contains(neighbor)).count();
+                adjacent_roads > 0 && adjacent_roads >= constraints.building_rules.min_adjacent_roads
+            })
+            .count();
+        (lots as f64 * constraints.building_density.ratio()).floor() as usize
+    });
+    let buildings = place_buildings(&buildable, &all_roads, &constraints.building_rules, target_building_count, rng);
+    for building in &buildings {
+        for hex in &building.hexes {
+            terrain.insert(*hex, TileType::Building);
+        }
+    }
+    
+    let building_count = buildings.len();
+    let mut tiles: Vec<((i32, i32), TileType)> = terrain.into_iter().collect();
+    tiles.sort_by_key(|(hex, _)| *hex);
+    InpaintResult {
+        tiles,
+        buildings,
+        summary: InpaintSummary {
+            hexes: hexes.len(),
+            entries: entries.len(),
+            roads: roads.len(),
+            unconnected,
+            bridges,
+            buildings: building_count,
+        },
+    }
+}
+
+/// Regenerate a hex region while keeping everything around it
+/// 
+/// **Learning Point**: The result is recorded as one edit, so undo_edit restores the
+/// previous tiles. Only loaded tiles are regenerated and the rest of the layout is
+/// untouched. The chunks owning the region follow the new tiles: their water and
+/// building records are updated with the edit (and restored by undo). The same seed
+/// always gives the same result, so pass a new "seed" to roll a different variant of
+/// the region.
+/// 
+/// @param center_q - Region center q coordinate
+/// @param center_r - Region center r coordinate
+/// @param radius - Region radius in hexes (capped at 8)
+/// @param constraints_json - JSON object (see LayoutConstraints::from_json; "rings" sets the
+///                           chunk size the seed counts refer to) plus optional "seed":0
+/// @returns JSON string: {"changed":[{"q":0,"r":0,"tileType":2},...],"summary":{"hexes":0,
+///          "entries":0,"roads":0,"unconnected":[{"q":0,"r":0},...],"bridges":[...],"buildings":0}}
+#[wasm_bindgen]
+pub fn regenerate_region(center_q: i32, center_r: i32, radius: i32, constraints_json: String) -> String {
+    let constraints = LayoutConstraints::from_json(&constraints_json);
+    let variant = json_int_field(&constraints_json, "seed").unwrap_or(0) as u32;
+    let mut state = WFC_STATE.lock().unwrap();
+    
+    let region: HashSet<(i32, i32)> = generate_hex_grid(radius.clamp(0, MAX_REGENERATE_RADIUS), center_q, center_r)
+        .iter()
+        .map(|hex| (hex.q, hex.r))
+        .filter(|&(q, r)| state.get_tile(q, r).is_some())
+        .collect();
+    let mut rng = SeededRng::new(chunk_seed(state.world_seed() ^ variant as u64, center_q, center_r));
+    let InpaintResult { tiles, buildings, summary } = inpaint_region(&state, &region, &constraints, &mut rng);
+    
+    let targets: Vec<((i32, i32), Option<TileType>)> = tiles.into_iter().map(|(hex, tile_type)| (hex, Some(tile_type))).collect();
+    let hexes = run_edit(&mut state, "regenerate_region", &targets, buildings, &EditOptions::from_json("{}"))
+        .map(|op| op.hexes())
+        .unwrap_or_default();
+    state.mark_water(&summary.bridges);
+    let changed_json = changes_to_json(&state, &hexes);
+    drop(state);
+    
+    invalidate_flow_fields_for_hexes(&hexes);
+    format!(
+        r#"{{"changed":{},"summary":{{"hexes":{},"entries":{},"roads":{},"unconnected":{},"bridges":{},"buildings":{}}}}}"#,
+        changed_json,
+        summary.hexes,
+        summary.entries,
+        summary.roads,
+        coords_to_json(&summary.unconnected),
+        coords_to_json(&summary.bridges),
+        summary.buildings
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hex_utils::connected_components;
+    
+    /// Chunk of `fill` with a straight road along r = 0, regenerated within 2 of the center
+    fn regenerate_across_road(fill: TileType, seed: u64) -> (WfcState, InpaintSummary) {
+        let mut state = WfcState::new();
+        let tiles: Vec<((i32, i32), TileType)> = generate_hex_grid(6, 0, 0)
+            .iter()
+            .map(|hex| ((hex.q, hex.r), if hex.r == 0 { TileType::Road } else { fill }))
+            .collect();
+        state.insert_chunk((0, 0), 6, tiles, Vec::new(), HashSet::new());
+        let region: HashSet<(i32, i32)> = generate_hex_grid(2, 0, 0).iter().map(|hex| (hex.q, hex.r)).collect();
+        
+        let mut rng = SeededRng::new(seed);
+        let result = inpaint_region(&state, &region, &LayoutConstraints::from_json("{}"), &mut rng);
+        for ((q, r), tile_type) in result.tiles {
+            state.insert_tile(q, r, tile_type);
+        }
+        (state, result.summary)
+    }
+    
+    fn road_components(state: &WfcState) -> usize {
+        let roads: HashSet<(i32, i32)> = state
+            .tiles()
+            .filter(|&(_, tile_type)| tile_type == TileType::Road)
+            .map(|(hex, _)| hex)
+            .collect();
+        connected_components(&roads).len()
+    }
+    
+    #[test]
+    fn boundary_roads_stay_connected() {
+        for seed in 0..10 {
+            let (state, summary) = regenerate_across_road(TileType::Grass, seed);
+            assert!(summary.unconnected.is_empty(), "seed {}", seed);
+            assert_eq!(road_components(&state), 1, "seed {}", seed);
+        }
+    }
+    
+    #[test]
+    fn roads_cross_water_on_bridges() {
+        let mut bridged = 0;
+        for seed in 0..10 {
+            let (state, summary) = regenerate_across_road(TileType::Water, seed);
+            // Either the ends are joined (over bridges where needed) or the gap is reported
+            if summary.unconnected.is_empty() {
+                assert_eq!(road_components(&state), 1, "seed {}", seed);
+            }
+            for &(q, r) in &summary.bridges {
+                assert_eq!(state.get_tile(q, r), Some(TileType::Road), "seed {}", seed);
+            }
+            if summary.unconnected.is_empty() && !summary.bridges.is_empty() {
+                bridged += 1;
+            }
+        }
+        assert!(bridged > 0);
+    }
+}
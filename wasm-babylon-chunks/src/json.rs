@@ -0,0 +1,178 @@
+/// JSON helper module
+/// 
+/// **Learning Point**: Minimal field extraction and serialization helpers so new
+/// exports can accept small config objects without pulling serde into the WASM build.
+/// Keys are matched literally, so these helpers are meant for flat config objects.
+
+/// Find the raw value text following `"key":` in a JSON object string
+/// Returns the slice starting at the first non-whitespace character of the value
+fn find_field_value<'a>(json: &'a str, key: &str) -> Option<&'a str> {
+    let pattern = format!(r#""{}""#, key);
+    let mut search_from = 0;
+    
+    while let Some(offset) = json[search_from..].find(&pattern) {
+        let after_key = search_from + offset + pattern.len();
+        let rest = json[after_key..].trim_start();
+        // Only accept the match if it is followed by a colon (i.e. it is a key, not a value)
+        if let Some(value) = rest.strip_prefix(':') {
+            return Some(value.trim_start());
+        }
+        search_from = after_key;
+    }
+    
+    None
+}
+
+/// Parse a numeric field from a JSON object string
+/// Example: json_number_field(r#"{"straightness":0.5}"#, "straightness") == Some(0.5)
+pub fn json_number_field(json: &str, key: &str) -> Option<f64> {
+    let value = find_field_value(json, key)?;
+    let end = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '-' || c == '+' || c == '.' || c == 'e' || c == 'E'))
+        .unwrap_or(value.len());
+    value[..end].parse::<f64>().ok()
+}
+
+/// Parse an integer field from a JSON object string
+/// Fractional values are truncated toward zero
+pub fn json_int_field(json: &str, key: &str) -> Option<i32> {
+    json_number_field(json, key).map(|value| value as i32)
+}
+
+/// Parse a boolean field from a JSON object string
+pub fn json_bool_field(json: &str, key: &str) -> Option<bool> {
+    let value = find_field_value(json, key)?;
+    if value.starts_with("true") {
+        Some(true)
+    } else if value.starts_with("false") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+/// Parse a string field from a JSON object string
+/// Escape sequences are not decoded (config values are plain identifiers)
+pub fn json_string_field(json: &str, key: &str) -> Option<String> {
+    let value = find_field_value(json, key)?;
+    let value = value.strip_prefix('"')?;
+    let end = value.find('"')?;
+    Some(value[..end].to_string())
+}
+
+/// Get a nested object field as its raw `{...}` text
+/// Example: json_object_field(r#"{"voronoiSeeds":{"forest":4}}"#, "voronoiSeeds") == Some(r#"{"forest":4}"#)
+pub fn json_object_field<'a>(json: &'a str, key: &str) -> Option<&'a str> {
+    let value = find_field_value(json, key)?;
+    if !value.starts_with('{') {
+        return None;
+    }
+    
+    let mut depth = 0;
+    for (index, c) in value.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(&value[..=index]);
+                }
+            },
+            _ => {},
+        }
+    }
+    
+    None
+}
+
+/// Get an array field as its raw `[...]` text (nested arrays are not supported)
+/// Example: json_array_field(r#"{"passable":[0,2]}"#, "passable") == Some("[0,2]")
+pub fn json_array_field<'a>(json: &'a str, key: &str) -> Option<&'a str> {
+    let value = find_field_value(json, key)?;
+    if !value.starts_with('[') {
+        return None;
+    }
+    let end = value.find(']')?;
+    Some(&value[..=end])
+}
+
+/// Parse an array of strings field from a JSON object string
+/// Example: json_string_array_field(r#"{"exclude":["water","forest"]}"#, "exclude") == ["water", "forest"]
+pub fn json_string_array_field(json: &str, key: &str) -> Vec<String> {
+    let Some(value) = find_field_value(json, key).and_then(|value| value.strip_prefix('[')) else {
+        return Vec::new();
+    };
+    let end = value.find(']').unwrap_or(value.len());
+    
+    value[..end]
+        .split(',')
+        .filter_map(|item| {
+            let item = item.trim();
+            item.strip_prefix('"')?.strip_suffix('"').map(|name| name.to_string())
+        })
+        .collect()
+}
+
+/// Iterate over the innermost `{...}` objects in a JSON string
+/// 
+/// **Learning Point**: Arrays of flat objects like [{"q":0,"r":0,"tileType":2},...]
+/// are the common input shape, so each yielded slice can be handed to the field helpers.
+pub fn json_objects(json: &str) -> impl Iterator<Item = &str> {
+    let mut objects = Vec::new();
+    let mut object_start: Option<usize> = None;
+    
+    for (index, c) in json.char_indices() {
+        match c {
+            '{' => object_start = Some(index),
+            '}' => {
+                if let Some(start) = object_start.take() {
+                    objects.push(&json[start..=index]);
+                }
+            },
+            _ => {},
+        }
+    }
+    
+    objects.into_iter()
+}
+
+/// Iterate over the top-level `{...}` objects of a JSON array string
+/// 
+/// Unlike json_objects, objects nested inside an element (e.g. the "hexes" of a
+/// building record) stay part of their parent's slice.
+pub fn json_array_items(json: &str) -> impl Iterator<Item = &str> {
+    let mut items = Vec::new();
+    let mut item_start = 0;
+    let mut depth = 0;
+    
+    for (index, c) in json.char_indices() {
+        match c {
+            '{' => {
+                if depth == 0 {
+                    item_start = index;
+                }
+                depth += 1;
+            },
+            '}' if depth > 0 => {
+                depth -= 1;
+                if depth == 0 {
+                    items.push(&json[item_start..=index]);
+                }
+            },
+            _ => {},
+        }
+    }
+    
+    items.into_iter()
+}
+
+/// Convert a list of hex coordinates to a JSON array string
+/// Format: [{"q":0,"r":0},...]
+pub fn coords_to_json(coords: &[(i32, i32)]) -> String {
+    let mut json_parts = Vec::new();
+    for (q, r) in coords {
+        json_parts.push(format!(r#"{{"q":{},"r":{}}}"#, q, r));
+    }
+    
+    format!("[{}]", json_parts.join(","))
+}
@@ -3,6 +3,9 @@
 use wasm_bindgen::prelude::*;
 use crate::state::WFC_STATE;
 use crate::types::TileType;
+use crate::seed::chunk_seed;
+use crate::flow_field::invalidate_all_flow_fields;
+use crate::prefab::forget_prefab_placements;
 
 /// Initialize the WASM module
 #[wasm_bindgen(start)]
@@ -24,6 +27,10 @@ pub fn get_wasm_version() -> String {
 /// **Learning Point**: This implements a simple algorithm:
 /// 1. Apply pre-constraints to grid (all tile types set by TypeScript)
 /// 2. Fill any remaining empty cells with grass (shouldn't happen if pre-constraints are complete)
+/// 
+/// The grid shape is chosen where the pre-constraints are produced (generate_voronoi_regions_in_shape,
+/// generate_chunk_from_constraints or generate_chunk with a "shape"); this only copies them,
+/// so it takes no shape of its own.
 #[wasm_bindgen]
 pub fn generate_layout() {
     let mut state = WFC_STATE.lock().unwrap();
@@ -39,6 +46,10 @@ pub fn generate_layout() {
     
     // Step 2: Fill any remaining empty cells with grass (shouldn't be needed if pre-constraints are complete)
     // This is a safety fallback
+    drop(state);
+    
+    // The whole grid was replaced, so every cached flow field is stale
+    invalidate_all_flow_fields();
 }
 
 /// Get tile type at a specific hex grid position
@@ -67,6 +78,8 @@ pub fn get_tile_at(q: i32, r: i32) -> i32 {
 pub fn clear_layout() {
     let mut state = WFC_STATE.lock().unwrap();
     state.clear();
+    drop(state);
+    invalidate_all_flow_fields();
 }
 
 /// Set a pre-constraint at a specific hex position
@@ -83,18 +96,25 @@ pub fn set_pre_constraint(q: i32, r: i32, tile_type: i32) -> bool {
     let mut state = WFC_STATE.lock().unwrap();
     
     // Convert i32 to TileType
-    let tile = match tile_type {
-        0 => TileType::Grass,
-        1 => TileType::Building,
-        2 => TileType::Road,
-        3 => TileType::Forest,
-        4 => TileType::Water,
-        _ => return false, // Invalid tile type
+    let tile = match TileType::from_i32(tile_type) {
+        Some(tile) => tile,
+        None => return false, // Invalid tile type
     };
     
     state.set_pre_constraint(q, r, tile)
 }
 
+/// Remove the pre-constraint at a specific hex position
+/// 
+/// @param q - Hex column coordinate (axial q)
+/// @param r - Hex row coordinate (axial r)
+/// @returns true if the hex had a pre-constraint
+#[wasm_bindgen]
+pub fn remove_pre_constraint(q: i32, r: i32) -> bool {
+    let mut state = WFC_STATE.lock().unwrap();
+    state.remove_pre_constraint(q, r)
+}
+
 /// Clear all pre-constraints
 /// 
 /// **Learning Point**: This clears all pre-constraints, allowing WFC to generate
@@ -103,6 +123,42 @@ pub fn set_pre_constraint(q: i32, r: i32, tile_type: i32) -> bool {
 pub fn clear_pre_constraints() {
     let mut state = WFC_STATE.lock().unwrap();
     state.clear_pre_constraints();
+    drop(state);
+    forget_prefab_placements();
+}
+
+/// Set the world seed used for all seeded generation
+/// 
+/// **Learning Point**: Every chunk derives its own seed from this value and its center,
+/// so the same world seed always regenerates the same world.
+/// The seed persists across clear_layout calls.
+/// 
+/// @param seed - World seed (32-bit so it round-trips through a JS number)
+#[wasm_bindgen]
+pub fn set_world_seed(seed: u32) {
+    let mut state = WFC_STATE.lock().unwrap();
+    state.set_world_seed(seed as u64);
+}
+
+/// Get the current world seed
+/// 
+/// @returns World seed (32-bit)
+#[wasm_bindgen]
+pub fn get_world_seed() -> u32 {
+    let state = WFC_STATE.lock().unwrap();
+    state.world_seed() as u32
+}
+
+/// Get the seed derived for a chunk from the world seed and the chunk center
+/// Useful for seeding TypeScript-side decoration consistently with Rust generation
+/// 
+/// @param center_q - Chunk center q coordinate
+/// @param center_r - Chunk center r coordinate
+/// @returns Chunk seed (low 32 bits)
+#[wasm_bindgen]
+pub fn get_chunk_seed(center_q: i32, center_r: i32) -> u32 {
+    let state = WFC_STATE.lock().unwrap();
+    chunk_seed(state.world_seed(), center_q, center_r) as u32
 }
 
 /// Get statistics about the current grid
@@ -2,7 +2,7 @@
 /// 
 /// This module organizes the WASM crate into logical sub-modules:
 /// - types: Core type definitions
-/// - state: WFC state management
+/// - state: WFC state management (flat tile grid + per-chunk records)
 /// - hex_utils: Hex coordinate utilities
 /// - astar: A* pathfinding algorithms
 /// - voronoi: Voronoi region generation
@@ -10,6 +10,28 @@
 /// - roads: Road network generation
 /// - chunks: Chunk management
 /// - utils: Utility functions
+/// - json: JSON parsing and serialization helpers
+/// - seed: Deterministic world/chunk seeding
+/// - noise: Seamless elevation/moisture noise terrain
+/// - generation: Seeded end-to-end chunk generation
+/// - persist: Binary world save/load
+/// - visibility: Viewer-driven chunk load/enable/disable/unload tracking
+/// - buildings: Multi-hex building footprints and lot placement
+/// - zoning: District zoning layer (residential, market, farmland, park)
+/// - hydrology: Downhill river and lake tracing
+/// - validation: Layout rule checks with per-hex violation reports
+/// - constraints: Parsed LayoutConstraints to a full layout in one call
+/// - analytics: Connected region and road network metrics
+/// - hex_layout: Hex <-> world space layout and picking
+/// - mesh: Merged per-chunk render geometry
+/// - sight: Hex lines, line of sight and field of view
+/// - flow_field: Multi-goal flow fields for crowd steering
+/// - edit: Reversible layout edits with undo/redo
+/// - inpaint: Regenerating a region against fixed surroundings
+/// - grid_shape: Rectangle/parallelogram/triangle grids and offset coordinates
+/// - fingerprint: Symmetry-invariant layout hashes and similarity scores
+/// - transform: Rotating, reflecting, translating and stamping layouts
+/// - prefab: Named multi-hex templates placed as fixed constraints
 
 // Module declarations
 mod types;
@@ -21,24 +43,106 @@ mod layout;
 mod roads;
 mod chunks;
 mod utils;
+mod json;
+mod seed;
+mod noise;
+mod generation;
+mod persist;
+mod visibility;
+mod buildings;
+mod zoning;
+mod hydrology;
+mod validation;
+mod constraints;
+mod analytics;
+mod hex_layout;
+mod mesh;
+mod sight;
+mod flow_field;
+mod edit;
+mod inpaint;
+mod grid_shape;
+mod fingerprint;
+mod transform;
+mod prefab;
 
 // Re-export all public functions from sub-modules
 // This maintains the same public API as before the refactoring
 
 // From layout module
-pub use layout::{init, get_wasm_version, generate_layout, get_tile_at, clear_layout, set_pre_constraint, clear_pre_constraints, get_stats};
+pub use layout::{init, get_wasm_version, generate_layout, get_tile_at, clear_layout, set_pre_constraint, remove_pre_constraint, clear_pre_constraints, set_world_seed, get_world_seed, get_chunk_seed, get_stats};
 
 // From astar module
-pub use astar::{hex_astar, build_path_between_roads, validate_road_connectivity};
+pub use astar::{hex_astar, find_path_on_layout, build_path_between_roads, validate_road_connectivity};
 
 // From voronoi module
-pub use voronoi::generate_voronoi_regions;
+pub use voronoi::{generate_voronoi_regions, generate_voronoi_regions_in_shape};
 
 // From roads module
-pub use roads::generate_road_network_growing_tree;
+pub use roads::{generate_road_network_growing_tree, generate_road_network_mst};
 
 // From chunks module
-pub use chunks::{calculate_chunk_radius, calculate_chunk_neighbors, find_nearest_neighbor_chunk, disable_distant_chunks, calculate_chunk_for_tile};
+pub use chunks::{calculate_chunk_radius, calculate_chunk_neighbors, find_nearest_neighbor_chunk, disable_distant_chunks, calculate_chunk_for_tile, get_chunk_for_tile, get_chunk_hexes, is_tile_in_chunk, get_chunk_border_hexes, calculate_border_exits};
+
+// From noise module
+pub use noise::{generate_noise_terrain, get_noise_fields};
+
+// From generation module
+pub use generation::{generate_chunk_layout, generate_chunk, evict_chunk, get_chunk_tiles, get_chunk_buildings, get_loaded_chunks, get_memory_stats};
+
+// From persist module
+pub use persist::{save_world, load_world};
+
+// From visibility module
+pub use visibility::{configure_chunk_visibility, update_viewer, get_live_chunks, clear_chunk_visibility};
+
+// From buildings module
+pub use buildings::generate_buildings;
+
+// From zoning module
+pub use zoning::{get_zone_at, batch_get_tile_zones, get_zone_stats};
+
+// From hydrology module
+pub use hydrology::get_rivers;
+
+// From validation module
+pub use validation::validate_layout;
+
+// From constraints module
+pub use constraints::generate_chunk_from_constraints;
+
+// From analytics module
+pub use analytics::get_region_analytics;
+
+// From hex_layout module
+pub use hex_layout::{hex_to_world, world_to_hex, hex_corners, batch_hex_to_world_typed, batch_world_to_hex, batch_hex_to_world};
+
+// From mesh module
+pub use mesh::{build_chunk_mesh, ChunkMesh};
+
+// From sight module
+pub use sight::{hex_line, has_line_of_sight, field_of_view};
+
+// From flow_field module
+pub use flow_field::{create_flow_field, flow_direction, flow_cost, get_flow_field_chunk, invalidate_flow_fields, remove_flow_field, clear_flow_fields};
+
+// From edit module
+pub use edit::{edit_set_tiles, edit_erase_tiles, edit_fill_ring, edit_fill_area, edit_flood_fill, undo_edit, redo_edit, get_edit_history, clear_edit_history};
+
+// From inpaint module
+pub use inpaint::regenerate_region;
+
+// From grid_shape module
+pub use grid_shape::{axial_to_offset, offset_to_axial, get_grid_shape_hexes};
+
+// From fingerprint module
+pub use fingerprint::{fingerprint_layout, get_layout_fingerprint, layout_similarity};
+
+// From transform module
+pub use transform::{transform_hex, transform_layout, transform_buildings, stamp_layout};
+
+// From prefab module
+pub use prefab::{register_prefab, unregister_prefab, get_prefabs, place_prefab, remove_prefab_placement, get_prefab_placements, clear_prefab_placements};
 
 // From utils module
-pub use utils::{batch_get_tile_types, shuffle_array, count_adjacent_roads, get_adjacent_valid_terrain, generate_building_placement, batch_hex_to_world};
+pub use utils::{batch_get_tile_types, shuffle_array, count_adjacent_roads, get_adjacent_valid_terrain, generate_building_placement};
@@ -0,0 +1,267 @@
+/// Chunk mesh module
+/// 
+/// **Learning Point**: One merged mesh per chunk replaces one mesh instance per hex, so
+/// a chunk costs a single draw call. Every hex becomes a prism: a fan-triangulated top
+/// face plus side walls. Walls hidden by an equally high neighbor in the same chunk are
+/// skipped, and walls on the chunk border can be extended downward as a skirt so
+/// elevation differences between neighboring chunks never show a crack.
+/// 
+/// Buffers use Babylon's layout (VertexData positions/normals/uvs/colors/indices) with
+/// counter-clockwise front faces as seen from outside the prism.
+
+use wasm_bindgen::prelude::*;
+use std::collections::HashMap;
+use crate::state::WFC_STATE;
+use crate::types::TileType;
+use crate::hex_utils::{generate_hex_grid, CUBE_DIRECTIONS};
+use crate::hex_layout::HexLayout;
+use crate::json::json_number_field;
+
+/// Vertex colors per tile type (RGBA, matches TILE_CONFIGS in tiles.ts)
+const TILE_COLORS: [[f32; 4]; 5] = [
+    [0.2, 0.8, 0.2, 1.0],       // Grass
+    [0.96, 0.96, 0.96, 1.0],    // Building
+    [0.326, 0.336, 0.326, 1.0], // Road
+    [0.05, 0.3, 0.05, 1.0],     // Forest
+    [0.0, 0.149, 1.0, 1.0],     // Water
+];
+
+/// Side walls are darkened so prism edges read without lighting
+const SIDE_SHADE: f32 = 0.8;
+
+/// Mesh settings
+/// 
+/// Parsed from JSON: HexLayout fields plus {"tileHeight":0.3,"tileRadius":1.0,
+///                   "elevationScale":1.0,"skirtDepth":0.0}
+pub struct MeshConfig {
+    pub layout: HexLayout,
+    /// Prism height above the hex's elevation
+    pub tile_height: f32,
+    /// Corner radius of the top face; defaults to the layout cell (size * spacing) so tiles meet
+    pub tile_radius: f32,
+    /// Multiplier applied to the per-hex elevations
+    pub elevation_scale: f32,
+    /// Extra depth of border walls below the prism base (0 disables skirts)
+    pub skirt_depth: f32,
+}
+
+impl MeshConfig {
+    pub fn from_json(config_json: &str) -> Self {
+        let layout = HexLayout::from_json(config_json);
+        let cell_radius = layout.size * layout.spacing;
+        MeshConfig {
+            tile_height: json_number_field(config_json, "tileHeight").unwrap_or(0.3).max(0.0) as f32,
+            tile_radius: json_number_field(config_json, "tileRadius")
+                .filter(|radius| *radius > 0.0)
+                .unwrap_or(cell_radius) as f32,
+            elevation_scale: json_number_field(config_json, "elevationScale").unwrap_or(1.0) as f32,
+            skirt_depth: json_number_field(config_json, "skirtDepth").unwrap_or(0.0).max(0.0) as f32,
+            layout,
+        }
+    }
+}
+
+/// Merged chunk geometry, ready for BABYLON.VertexData
+#[wasm_bindgen]
+pub struct ChunkMesh {
+    positions: Vec<f32>,
+    normals: Vec<f32>,
+    uvs: Vec<f32>,
+    colors: Vec<f32>,
+    indices: Vec<u32>,
+}
+
+#[wasm_bindgen]
+impl ChunkMesh {
+    /// Float32Array [x, y, z, ...]
+    #[wasm_bindgen(getter)]
+    pub fn positions(&self) -> Vec<f32> {
+        self.positions.clone()
+    }
+    
+    /// Float32Array [nx, ny, nz, ...]
+    #[wasm_bindgen(getter)]
+    pub fn normals(&self) -> Vec<f32> {
+        self.normals.clone()
+    }
+    
+    /// Float32Array [u, v, ...]
+    #[wasm_bindgen(getter)]
+    pub fn uvs(&self) -> Vec<f32> {
+        self.uvs.clone()
+    }
+    
+    /// Float32Array [r, g, b, a, ...]
+    #[wasm_bindgen(getter)]
+    pub fn colors(&self) -> Vec<f32> {
+        self.colors.clone()
+    }
+    
+    /// Uint32Array of triangle vertex indices
+    #[wasm_bindgen(getter)]
+    pub fn indices(&self) -> Vec<u32> {
+        self.indices.clone()
+    }
+    
+    #[wasm_bindgen(getter)]
+    pub fn vertex_count(&self) -> u32 {
+        (self.positions.len() / 3) as u32
+    }
+}
+
+impl ChunkMesh {
+    fn new() -> Self {
+        ChunkMesh {
+            positions: Vec::new(),
+            normals: Vec::new(),
+            uvs: Vec::new(),
+            colors: Vec::new(),
+            indices: Vec::new(),
+        }
+    }
+    
+    /// Append a vertex and return its index
+    fn vertex(&mut self, position: [f32; 3], normal: [f32; 3], uv: [f32; 2], color: [f32; 4]) -> u32 {
+        let index = (self.positions.len() / 3) as u32;
+        self.positions.extend_from_slice(&position);
+        self.normals.extend_from_slice(&normal);
+        self.uvs.extend_from_slice(&uv);
+        self.colors.extend_from_slice(&color);
+        index
+    }
+}
+
+/// One hex of the chunk: tile type and the height of its prism base
+struct MeshHex {
+    tile_type: TileType,
+    base: f32,
+}
+
+/// Corner offsets (dx, dz) of a hex top, counter-clockwise
+fn corner_offsets(config: &MeshConfig) -> [(f32, f32); 6] {
+    // Corner angles come from the layout; rescale them from the layout size to the tile radius
+    let corners = config.layout.hex_corners(0, 0);
+    let (origin_x, origin_z) = config.layout.hex_to_world(0, 0);
+    let scale = config.tile_radius as f64 / config.layout.size;
+    let mut offsets = [(0.0, 0.0); 6];
+    for (offset, (x, z)) in offsets.iter_mut().zip(corners) {
+        *offset = (((x - origin_x) * scale) as f32, ((z - origin_z) * scale) as f32);
+    }
+    offsets
+}
+
+/// Direction index (into CUBE_DIRECTIONS) of the neighbor across the edge between
+/// corner `edge` and corner `edge + 1`, found by matching the edge midpoint direction
+fn edge_neighbor_directions(config: &MeshConfig, corners: &[(f32, f32); 6]) -> [usize; 6] {
+    let (origin_x, origin_z) = config.layout.hex_to_world(0, 0);
+    let mut directions = [0; 6];
+    for (edge, direction) in directions.iter_mut().enumerate() {
+        let (ax, az) = corners[edge];
+        let (bx, bz) = corners[(edge + 1) % 6];
+        let (mid_x, mid_z) = ((ax + bx) as f64 / 2.0, (az + bz) as f64 / 2.0);
+        *direction = (0..6)
+            .max_by(|&a, &b| {
+                let alignment = |index: usize| {
+                    let (x, z) = config.layout.hex_to_world(CUBE_DIRECTIONS[index].q, CUBE_DIRECTIONS[index].r);
+                    (x - origin_x) * mid_x + (z - origin_z) * mid_z
+                };
+                alignment(a).total_cmp(&alignment(b))
+            })
+            .unwrap_or(0);
+    }
+    directions
+}
+
+/// Build the merged prism mesh for a set of hexes
+/// 
+/// `hexes` maps every hex in the chunk to its tile and base height; a wall is emitted
+/// where the neighbor is lower or outside the chunk, and walls facing outside the chunk
+/// reach `skirt_depth` further down.
+fn build_mesh(order: &[(i32, i32)], hexes: &HashMap<(i32, i32), MeshHex>, config: &MeshConfig) -> ChunkMesh {
+    let corners = corner_offsets(config);
+    let edge_directions = edge_neighbor_directions(config, &corners);
+    let up = [0.0, 1.0, 0.0];
+    let mut mesh = ChunkMesh::new();
+    
+    for &(q, r) in order {
+        let Some(hex) = hexes.get(&(q, r)) else {
+            continue;
+        };
+        let (center_x, center_z) = config.layout.hex_to_world(q, r);
+        let (center_x, center_z) = (center_x as f32, center_z as f32);
+        let top = hex.base + config.tile_height;
+        let color = TILE_COLORS[hex.tile_type as usize];
+        
+        // Top face: center plus six corners, fan triangulated
+        let center = mesh.vertex([center_x, top, center_z], up, [0.5, 0.5], color);
+        let radius = config.tile_radius.max(f32::EPSILON);
+        let mut rim = [0u32; 6];
+        for (corner, &(dx, dz)) in rim.iter_mut().zip(corners.iter()) {
+            let uv = [0.5 + 0.5 * dx / radius, 0.5 + 0.5 * dz / radius];
+            *corner = mesh.vertex([center_x + dx, top, center_z + dz], up, uv, color);
+        }
+        for edge in 0..6 {
+            mesh.indices.extend_from_slice(&[center, rim[edge], rim[(edge + 1) % 6]]);
+        }
+        
+        // Side walls and skirts
+        let side_color = [color[0] * SIDE_SHADE, color[1] * SIDE_SHADE, color[2] * SIDE_SHADE, color[3]];
+        for edge in 0..6 {
+            let offset = CUBE_DIRECTIONS[edge_directions[edge]];
+            let bottom = match hexes.get(&(q + offset.q, r + offset.r)) {
+                Some(neighbor) if neighbor.base + config.tile_height >= top => continue,
+                Some(neighbor) => (neighbor.base + config.tile_height).max(hex.base),
+                None => hex.base - config.skirt_depth,
+            };
+            
+            let (ax, az) = corners[edge];
+            let (bx, bz) = corners[(edge + 1) % 6];
+            // Outward normal: the edge midpoint direction, flattened
+            let (mid_x, mid_z) = ((ax + bx) / 2.0, (az + bz) / 2.0);
+            let length = mid_x.hypot(mid_z).max(f32::EPSILON);
+            let normal = [mid_x / length, 0.0, mid_z / length];
+            let height = (top - bottom).max(f32::EPSILON);
+            
+            let top_a = mesh.vertex([center_x + ax, top, center_z + az], normal, [0.0, 0.0], side_color);
+            let top_b = mesh.vertex([center_x + bx, top, center_z + bz], normal, [1.0, 0.0], side_color);
+            let bottom_a = mesh.vertex([center_x + ax, bottom, center_z + az], normal, [0.0, height / radius], side_color);
+            let bottom_b = mesh.vertex([center_x + bx, bottom, center_z + bz], normal, [1.0, height / radius], side_color);
+            mesh.indices.extend_from_slice(&[top_a, bottom_a, top_b, top_b, bottom_a, bottom_b]);
+        }
+    }
+    
+    mesh
+}
+
+/// Build merged render geometry for a loaded chunk
+/// 
+/// **Learning Point**: Upload the buffers directly with BABYLON.VertexData
+/// (positions, normals, uvs, colors, indices) and use a material with vertex colors;
+/// tiles outside the layout state are skipped.
+/// 
+/// @param center_q - Chunk center q coordinate
+/// @param center_r - Chunk center r coordinate
+/// @param rings - Number of rings per chunk
+/// @param config_json - JSON object (see MeshConfig::from_json), "{}" for defaults
+/// @param elevations - Float32Array of base heights in ring order (center first), empty for flat
+/// @returns ChunkMesh with positions, normals, uvs, colors (Float32Array) and indices (Uint32Array)
+#[wasm_bindgen]
+pub fn build_chunk_mesh(center_q: i32, center_r: i32, rings: i32, config_json: String, elevations: &[f32]) -> ChunkMesh {
+    let config = MeshConfig::from_json(&config_json);
+    let order: Vec<(i32, i32)> = generate_hex_grid(rings.max(0), center_q, center_r)
+        .iter()
+        .map(|hex| (hex.q, hex.r))
+        .collect();
+    
+    let state = WFC_STATE.lock().unwrap();
+    let mut hexes = HashMap::new();
+    for (index, &(q, r)) in order.iter().enumerate() {
+        if let Some(tile_type) = state.get_tile(q, r) {
+            let elevation = elevations.get(index).copied().unwrap_or(0.0);
+            hexes.insert((q, r), MeshHex { tile_type, base: elevation * config.elevation_scale });
+        }
+    }
+    drop(state);
+    
+    build_mesh(&order, &hexes, &config)
+}
@@ -0,0 +1,287 @@
+/// Coherent noise terrain module
+
+use wasm_bindgen::prelude::*;
+use std::f64::consts::FRAC_1_SQRT_2;
+use crate::state::WFC_STATE;
+use crate::types::{TileType, VoronoiSeed};
+use crate::hex_utils::{generate_hex_grid, hex_distance, axial_to_plane};
+use crate::voronoi::pick_voronoi_seeds;
+use crate::seed::{chunk_seed, hash_coords, mix64, SeededRng};
+use crate::json::{json_int_field, json_number_field};
+
+/// Salts separating the independent noise fields derived from one world seed
+const ELEVATION_SALT: u64 = 0xE1E7;
+const MOISTURE_SALT: u64 = 0x3015;
+
+/// Skew/unskew factors for 2D simplex noise
+const F2: f64 = 0.366_025_403_784_438_6; // (sqrt(3) - 1) / 2
+const G2: f64 = 0.211_324_865_405_187_1; // (3 - sqrt(3)) / 6
+
+/// Gradient directions for 2D simplex noise
+const GRADIENTS: [(f64, f64); 8] = [
+    (1.0, 0.0), (-1.0, 0.0), (0.0, 1.0), (0.0, -1.0),
+    (FRAC_1_SQRT_2, FRAC_1_SQRT_2), (-FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+    (FRAC_1_SQRT_2, -FRAC_1_SQRT_2), (-FRAC_1_SQRT_2, -FRAC_1_SQRT_2),
+];
+
+/// Configuration for noise terrain generation
+/// Parsed from JSON: {"scale":0.05,"octaves":4,"persistence":0.5,"waterLevel":0.35,
+///                    "forestMoisture":0.6,"voronoiWeight":0.0}
+pub struct NoiseConfig {
+    /// Noise frequency in cycles per hex (smaller = larger features)
+    pub scale: f64,
+    /// Number of fractal octaves
+    pub octaves: u32,
+    /// Amplitude falloff per octave
+    pub persistence: f64,
+    /// Elevation below this becomes water
+    pub water_level: f64,
+    /// Moisture above this (on land) becomes forest
+    pub forest_moisture: f64,
+    /// 0.0 = pure noise, 1.0 = pure Voronoi regions
+    /// Anything above 0.0 gives up seamless chunk borders (see generate_noise_tiles)
+    pub voronoi_weight: f64,
+}
+
+impl Default for NoiseConfig {
+    fn default() -> Self {
+        NoiseConfig {
+            scale: 0.05,
+            octaves: 4,
+            persistence: 0.5,
+            water_level: 0.35,
+            forest_moisture: 0.6,
+            voronoi_weight: 0.0,
+        }
+    }
+}
+
+impl NoiseConfig {
+    pub fn from_json(config_json: &str) -> Self {
+        let defaults = NoiseConfig::default();
+        NoiseConfig {
+            scale: json_number_field(config_json, "scale")
+                .filter(|scale| *scale > 0.0)
+                .unwrap_or(defaults.scale),
+            octaves: json_int_field(config_json, "octaves")
+                .map(|octaves| octaves.clamp(1, 8) as u32)
+                .unwrap_or(defaults.octaves),
+            persistence: json_number_field(config_json, "persistence")
+                .unwrap_or(defaults.persistence)
+                .clamp(0.0, 1.0),
+            water_level: json_number_field(config_json, "waterLevel").unwrap_or(defaults.water_level),
+            forest_moisture: json_number_field(config_json, "forestMoisture").unwrap_or(defaults.forest_moisture),
+            voronoi_weight: json_number_field(config_json, "voronoiWeight")
+                .unwrap_or(defaults.voronoi_weight)
+                .clamp(0.0, 1.0),
+        }
+    }
+}
+
+/// Pick the gradient for a simplex lattice corner
+fn gradient(seed: u64, i: i64, j: i64) -> (f64, f64) {
+    GRADIENTS[(hash_coords(seed, i as i32, j as i32) % GRADIENTS.len() as u64) as usize]
+}
+
+/// 2D simplex noise in roughly [-1, 1]
+pub fn simplex2(seed: u64, x: f64, y: f64) -> f64 {
+    // Skew input space to find the containing simplex cell
+    let skew = (x + y) * F2;
+    let i = (x + skew).floor();
+    let j = (y + skew).floor();
+    let unskew = (i + j) * G2;
+    let x0 = x - (i - unskew);
+    let y0 = y - (j - unskew);
+    
+    // Lower or upper triangle of the cell
+    let (i1, j1) = if x0 > y0 { (1.0, 0.0) } else { (0.0, 1.0) };
+    
+    let corners = [
+        (x0, y0, 0.0, 0.0),
+        (x0 - i1 + G2, y0 - j1 + G2, i1, j1),
+        (x0 - 1.0 + 2.0 * G2, y0 - 1.0 + 2.0 * G2, 1.0, 1.0),
+    ];
+    
+    let mut total = 0.0;
+    for (dx, dy, offset_i, offset_j) in corners {
+        let falloff = 0.5 - dx * dx - dy * dy;
+        if falloff > 0.0 {
+            let (gx, gy) = gradient(seed, (i + offset_i) as i64, (j + offset_j) as i64);
+            let falloff_sq = falloff * falloff;
+            total += falloff_sq * falloff_sq * (gx * dx + gy * dy);
+        }
+    }
+    
+    // Scale to roughly [-1, 1]
+    70.0 * total
+}
+
+/// Fractal (fBm) simplex noise at a hex, normalized to [0, 1]
+fn fractal_noise(seed: u64, q: i32, r: i32, config: &NoiseConfig) -> f64 {
+    // Sampling in world space (not per chunk) is what makes the fields seamless across chunks
+    let (x, y) = axial_to_plane(q as f64, r as f64);
+    let mut frequency = config.scale;
+    let mut amplitude = 1.0;
+    let mut total = 0.0;
+    let mut amplitude_sum = 0.0;
+    
+    for octave in 0..config.octaves {
+        let octave_seed = mix64(seed ^ octave as u64);
+        // Per-octave offset keeps lattice points (where simplex noise is 0) from lining up
+        let offset_x = (octave_seed % 1024) as f64 / 7.0;
+        let offset_y = ((octave_seed >> 10) % 1024) as f64 / 7.0;
+        total += amplitude * simplex2(octave_seed, x * frequency + offset_x, y * frequency + offset_y);
+        amplitude_sum += amplitude;
+        amplitude *= config.persistence;
+        frequency *= 2.0;
+    }
+    
+    ((total / amplitude_sum) * 0.5 + 0.5).clamp(0.0, 1.0)
+}
+
+/// Elevation in [0, 1] at a hex for the given world seed
+pub fn elevation_at(world_seed: u64, q: i32, r: i32, config: &NoiseConfig) -> f64 {
+    fractal_noise(mix64(world_seed ^ ELEVATION_SALT), q, r, config)
+}
+
+/// Moisture in [0, 1] at a hex for the given world seed
+pub fn moisture_at(world_seed: u64, q: i32, r: i32, config: &NoiseConfig) -> f64 {
+    fractal_noise(mix64(world_seed ^ MOISTURE_SALT), q, r, config)
+}
+
+/// Map elevation and moisture to a tile type using the configured thresholds
+pub fn biome_for(elevation: f64, moisture: f64, config: &NoiseConfig) -> TileType {
+    if elevation < config.water_level {
+        TileType::Water
+    } else if moisture > config.forest_moisture {
+        TileType::Forest
+    } else {
+        TileType::Grass
+    }
+}
+
+/// Target (elevation, moisture) a Voronoi region pulls its hexes toward when blending
+fn voronoi_field_target(tile_type: TileType, config: &NoiseConfig) -> (f64, f64) {
+    match tile_type {
+        TileType::Water => (0.0, 0.5),
+        TileType::Forest => (1.0, 1.0),
+        _ => (1.0, config.forest_moisture * 0.5),
+    }
+}
+
+/// Generate noise terrain for one chunk
+/// 
+/// **Learning Point**: Voronoi blending happens on the continuous fields rather than on
+/// tile types: each Voronoi region pulls elevation/moisture toward its own biome, then
+/// the thresholds are applied once.
+/// 
+/// Only the noise part is seamless. The Voronoi seeds are drawn per chunk, so the two
+/// sides of a chunk border are pulled toward different seeds and any `voronoi_weight`
+/// above 0 leaves a step in the fields (and often in the tiles) along the border. The
+/// step grows with the weight; keep it at 0 where chunks must join without a seam.
+/// 
+/// Returns tiles in ring order (center first)
+pub fn generate_noise_tiles(
+    world_seed: u64,
+    center_q: i32,
+    center_r: i32,
+    rings: i32,
+    config: &NoiseConfig,
+    voronoi_seeds: &[VoronoiSeed],
+) -> Vec<((i32, i32), TileType)> {
+    let hex_grid = generate_hex_grid(rings.max(0), center_q, center_r);
+    let mut tiles = Vec::with_capacity(hex_grid.len());
+    
+    for hex in &hex_grid {
+        let mut elevation = elevation_at(world_seed, hex.q, hex.r, config);
+        let mut moisture = moisture_at(world_seed, hex.q, hex.r, config);
+        
+        if config.voronoi_weight > 0.0 {
+            let nearest_seed = voronoi_seeds.iter()
+                .min_by_key(|seed| hex_distance(hex.q, hex.r, seed.q, seed.r));
+            if let Some(seed) = nearest_seed {
+                let (target_elevation, target_moisture) = voronoi_field_target(seed.tile_type, config);
+                let weight = config.voronoi_weight;
+                elevation = elevation * (1.0 - weight) + target_elevation * weight;
+                moisture = moisture * (1.0 - weight) + target_moisture * weight;
+            }
+        }
+        
+        tiles.push(((hex.q, hex.r), biome_for(elevation, moisture, config)));
+    }
+    
+    tiles
+}
+
+/// Generate seamless noise-based terrain for a chunk
+/// 
+/// **Learning Point**: Elevation and moisture are sampled in world space from the world
+/// seed, so adjacent chunks line up without any knowledge of each other (unless
+/// voronoiWeight blends in per-chunk Voronoi regions, see generate_noise_tiles). Output has
+/// the same format as generate_voronoi_regions and can replace it directly.
+/// 
+/// @param center_q - Chunk center q coordinate
+/// @param center_r - Chunk center r coordinate
+/// @param rings - Number of rings per chunk
+/// @param config_json - JSON object (see NoiseConfig), "{}" for defaults
+/// @param forest_seeds - Voronoi forest seeds (only used when voronoiWeight > 0)
+/// @param water_seeds - Voronoi water seeds (only used when voronoiWeight > 0)
+/// @param grass_seeds - Voronoi grass seeds (only used when voronoiWeight > 0)
+/// @returns JSON array in ring order: [{"q":0,"r":0,"tileType":0},...]
+#[wasm_bindgen]
+pub fn generate_noise_terrain(
+    center_q: i32,
+    center_r: i32,
+    rings: i32,
+    config_json: String,
+    forest_seeds: i32,
+    water_seeds: i32,
+    grass_seeds: i32,
+) -> String {
+    let world_seed = WFC_STATE.lock().unwrap().world_seed();
+    let config = NoiseConfig::from_json(&config_json);
+    
+    // Voronoi seeds come from the same chunk RNG as generate_voronoi_regions
+    let hexes: Vec<(i32, i32)> = generate_hex_grid(rings.max(0), center_q, center_r)
+        .iter()
+        .map(|hex| (hex.q, hex.r))
+        .collect();
+    let mut rng = SeededRng::new(chunk_seed(world_seed, center_q, center_r));
+    let voronoi_seeds = pick_voronoi_seeds(&hexes, forest_seeds, water_seeds, grass_seeds, &mut rng);
+    
+    let mut json_parts = Vec::new();
+    for ((q, r), tile_type) in generate_noise_tiles(world_seed, center_q, center_r, rings, &config, &voronoi_seeds) {
+        json_parts.push(format!(
+            r#"{{"q":{},"r":{},"tileType":{}}}"#,
+            q, r, tile_type as i32
+        ));
+    }
+    
+    format!("[{}]", json_parts.join(","))
+}
+
+/// Sample the raw elevation and moisture fields for a chunk
+/// 
+/// @param center_q - Chunk center q coordinate
+/// @param center_r - Chunk center r coordinate
+/// @param rings - Number of rings per chunk
+/// @param config_json - JSON object (see NoiseConfig), "{}" for defaults
+/// @returns JSON array in ring order: [{"q":0,"r":0,"elevation":0.5,"moisture":0.5},...]
+#[wasm_bindgen]
+pub fn get_noise_fields(center_q: i32, center_r: i32, rings: i32, config_json: String) -> String {
+    let world_seed = WFC_STATE.lock().unwrap().world_seed();
+    let config = NoiseConfig::from_json(&config_json);
+    
+    let mut json_parts = Vec::new();
+    for hex in generate_hex_grid(rings.max(0), center_q, center_r) {
+        json_parts.push(format!(
+            r#"{{"q":{},"r":{},"elevation":{:.4},"moisture":{:.4}}}"#,
+            hex.q,
+            hex.r,
+            elevation_at(world_seed, hex.q, hex.r, &config),
+            moisture_at(world_seed, hex.q, hex.r, &config)
+        ));
+    }
+    
+    format!("[{}]", json_parts.join(","))
+}
@@ -0,0 +1,534 @@
+/// World persistence module
+/// 
+/// **Learning Point**: The whole layout state (world seed, pre-constraints, loaded chunks
+/// and any tiles outside chunks) is written as a compact binary blob. Tiles are stored as
+/// run-length encoded tile types in ring order, so coordinates never need to be written:
+/// a chunk is fully described by its center, ring count and the runs. Roads and buildings
+/// are tile types, so they round-trip with the terrain; zones get their own runs, the
+/// building records (footprint, facing, size) follow them, and a last set of runs flags the
+/// hexes that were water before roads and buildings covered them.
+/// 
+/// Layout (all integers little endian / LEB128 varints):
+/// - magic "HXWD", format version (u8)
+/// - world seed (u64)
+/// - pre-constraints: count, then sorted (q, r, tile type)
+/// - chunks: count, then sorted (center q, center r, rings, tile runs, zone runs, buildings, water runs)
+/// - buildings: count, then (size, facing, hex count, hexes as (q, r)) in placement order
+/// - water runs: (run length, 0 or 1) in ring order, 1 where the terrain under the tile is water
+/// - loose tiles: span count, then sorted rows of consecutive hexes (r, first q, length, tile runs)
+/// - FNV-1a checksum (u32) of everything before it
+
+use wasm_bindgen::prelude::*;
+use std::collections::HashSet;
+use crate::state::{WfcState, WFC_STATE};
+use crate::types::{TileType, Zone};
+use crate::buildings::{BuildingRecord, BuildingSize};
+use crate::hex_utils::generate_hex_grid;
+use crate::flow_field::invalidate_all_flow_fields;
+use crate::prefab::forget_prefab_placements;
+
+/// Magic bytes identifying a saved world
+const MAGIC: &[u8; 4] = b"HXWD";
+
+/// Current save format version; bump when the layout changes
+pub const FORMAT_VERSION: u8 = 1;
+
+/// Tile byte used for hexes without a tile
+const EMPTY_TILE: u8 = 0xFF;
+
+/// Largest chunk ring count accepted on load (a corrupt count would allocate a huge grid)
+const MAX_LOAD_RINGS: u64 = 256;
+
+/// Longest loose tile span accepted on load
+const MAX_LOAD_SPAN: u64 = 1 << 16;
+
+/// Building sizes by their saved byte
+const BUILDING_SIZES: [BuildingSize; 3] = [BuildingSize::Small, BuildingSize::Medium, BuildingSize::Large];
+
+/// Reasons a saved world can be rejected
+#[derive(Debug, PartialEq)]
+pub enum LoadError {
+    BadMagic,
+    UnsupportedVersion(u8),
+    ChecksumMismatch,
+    Truncated,
+    InvalidTile(u8),
+    InvalidZone(u8),
+    InvalidBuilding(u8),
+    TooLarge(u64),
+}
+
+impl LoadError {
+    /// Human readable message for TypeScript
+    pub fn message(&self) -> String {
+        match self {
+            LoadError::BadMagic => "not a saved world".to_string(),
+            LoadError::UnsupportedVersion(version) => format!(
+                "unsupported save version {} (expected {})",
+                version, FORMAT_VERSION
+            ),
+            LoadError::ChecksumMismatch => "checksum mismatch".to_string(),
+            LoadError::Truncated => "save data is truncated".to_string(),
+            LoadError::InvalidTile(value) => format!("invalid tile type {}", value),
+            LoadError::InvalidZone(value) => format!("invalid zone {}", value),
+            LoadError::InvalidBuilding(value) => format!("invalid building size {}", value),
+            LoadError::TooLarge(value) => format!("size {} is out of range", value),
+        }
+    }
+}
+
+/// FNV-1a 32-bit hash used as the save checksum
+fn checksum(bytes: &[u8]) -> u32 {
+    let mut hash: u32 = 0x811C_9DC5;
+    for &byte in bytes {
+        hash ^= byte as u32;
+        hash = hash.wrapping_mul(0x0100_0193);
+    }
+    hash
+}
+
+/// Append-only byte writer with varint helpers
+struct Writer {
+    bytes: Vec<u8>,
+}
+
+impl Writer {
+    fn write_u8(&mut self, value: u8) {
+        self.bytes.push(value);
+    }
+    
+    fn write_varint(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.bytes.push((value as u8 & 0x7F) | 0x80);
+            value >>= 7;
+        }
+        self.bytes.push(value as u8);
+    }
+    
+    /// Zigzag-encode so small negative coordinates stay one byte
+    fn write_signed(&mut self, value: i32) {
+        let value = value as i64;
+        self.write_varint(((value << 1) ^ (value >> 63)) as u64);
+    }
+    
+    /// Run-length encode tile bytes as (run length, tile byte) pairs
+    fn write_runs(&mut self, tiles: &[u8]) {
+        let mut index = 0;
+        while index < tiles.len() {
+            let tile = tiles[index];
+            let run = tiles[index..].iter().take_while(|&&other| other == tile).count();
+            self.write_varint(run as u64);
+            self.write_u8(tile);
+            index += run;
+        }
+    }
+    
+    fn write_building(&mut self, building: &BuildingRecord) {
+        let size = BUILDING_SIZES.iter().position(|&size| size == building.size).unwrap_or(0);
+        self.write_u8(size as u8);
+        self.write_u8(building.facing as u8);
+        self.write_varint(building.hexes.len() as u64);
+        for &(q, r) in &building.hexes {
+            self.write_signed(q);
+            self.write_signed(r);
+        }
+    }
+}
+
+/// Cursor over saved bytes; every read fails with Truncated past the end
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn read_u8(&mut self) -> Result<u8, LoadError> {
+        let value = *self.bytes.get(self.position).ok_or(LoadError::Truncated)?;
+        self.position += 1;
+        Ok(value)
+    }
+    
+    fn read_u64(&mut self) -> Result<u64, LoadError> {
+        let end = self.position + 8;
+        let slice = self.bytes.get(self.position..end).ok_or(LoadError::Truncated)?;
+        self.position = end;
+        Ok(u64::from_le_bytes(slice.try_into().unwrap()))
+    }
+    
+    fn read_varint(&mut self) -> Result<u64, LoadError> {
+        let mut value = 0u64;
+        let mut shift = 0;
+        loop {
+            let byte = self.read_u8()?;
+            if shift >= 64 {
+                return Err(LoadError::Truncated);
+            }
+            value |= ((byte & 0x7F) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+            shift += 7;
+        }
+    }
+    
+    fn read_signed(&mut self) -> Result<i32, LoadError> {
+        let value = self.read_varint()?;
+        Ok(((value >> 1) as i64 ^ -((value & 1) as i64)) as i32)
+    }
+    
+    fn read_tile(&mut self) -> Result<Option<TileType>, LoadError> {
+        let value = self.read_u8()?;
+        if value == EMPTY_TILE {
+            return Ok(None);
+        }
+        TileType::from_i32(value as i32).map(Some).ok_or(LoadError::InvalidTile(value))
+    }
+    
+    /// Decode runs until `count` tiles have been read
+    fn read_runs(&mut self, count: usize) -> Result<Vec<Option<TileType>>, LoadError> {
+        let mut tiles = Vec::with_capacity(count);
+        while tiles.len() < count {
+            let run = self.read_varint()? as usize;
+            let tile = self.read_tile()?;
+            if run == 0 || tiles.len() + run > count {
+                return Err(LoadError::Truncated);
+            }
+            tiles.extend(std::iter::repeat_n(tile, run));
+        }
+        Ok(tiles)
+    }
+    
+    /// Decode zone runs until `count` zones have been read
+    fn read_zone_runs(&mut self, count: usize) -> Result<Vec<Zone>, LoadError> {
+        let mut zones = Vec::with_capacity(count);
+        while zones.len() < count {
+            let run = self.read_varint()? as usize;
+            let value = self.read_u8()?;
+            let zone = Zone::from_i32(value as i32).ok_or(LoadError::InvalidZone(value))?;
+            if run == 0 || zones.len() + run > count {
+                return Err(LoadError::Truncated);
+            }
+            zones.extend(std::iter::repeat_n(zone, run));
+        }
+        Ok(zones)
+    }
+    
+    /// Decode 0/1 flag runs until `count` flags have been read
+    fn read_flag_runs(&mut self, count: usize) -> Result<Vec<bool>, LoadError> {
+        let mut flags = Vec::with_capacity(count);
+        while flags.len() < count {
+            let run = self.read_varint()? as usize;
+            let value = self.read_u8()?;
+            if value > 1 || run == 0 || flags.len() + run > count {
+                return Err(LoadError::Truncated);
+            }
+            flags.extend(std::iter::repeat_n(value == 1, run));
+        }
+        Ok(flags)
+    }
+    
+    fn read_building(&mut self) -> Result<BuildingRecord, LoadError> {
+        let value = self.read_u8()?;
+        let size = BUILDING_SIZES.get(value as usize).copied().ok_or(LoadError::InvalidBuilding(value))?;
+        let facing = self.read_u8()? as usize % 6;
+        let hex_count = self.read_varint()?;
+        if hex_count == 0 || hex_count > BuildingSize::Large.hex_count() as u64 {
+            return Err(LoadError::TooLarge(hex_count));
+        }
+        let mut hexes = Vec::with_capacity(hex_count as usize);
+        for _ in 0..hex_count {
+            hexes.push((self.read_signed()?, self.read_signed()?));
+        }
+        Ok(BuildingRecord { anchor: hexes[0], hexes, facing, size })
+    }
+}
+
+/// Tile bytes for every hex of the ring-ordered grid around a center
+fn ring_tile_bytes(state: &WfcState, rings: i32, center_q: i32, center_r: i32) -> Vec<u8> {
+    generate_hex_grid(rings, center_q, center_r)
+        .iter()
+        .map(|hex| state.get_tile(hex.q, hex.r).map_or(EMPTY_TILE, |tile_type| tile_type as u8))
+        .collect()
+}
+
+/// Zone bytes for every hex of the ring-ordered grid around a center (0 = unzoned)
+fn ring_zone_bytes(state: &WfcState, rings: i32, center_q: i32, center_r: i32) -> Vec<u8> {
+    generate_hex_grid(rings, center_q, center_r)
+        .iter()
+        .map(|hex| state.get_zone(hex.q, hex.r).unwrap_or(Zone::Unzoned) as u8)
+        .collect()
+}
+
+/// Serialize the layout state into a versioned, checksummed binary blob
+pub fn save_state(state: &WfcState) -> Vec<u8> {
+    let mut writer = Writer { bytes: Vec::new() };
+    writer.bytes.extend_from_slice(MAGIC);
+    writer.write_u8(FORMAT_VERSION);
+    writer.bytes.extend_from_slice(&state.world_seed().to_le_bytes());
+    
+    // Pre-constraints (sorted so identical states produce identical bytes)
+    let mut pre_constraints: Vec<((i32, i32), TileType)> = state.pre_constraints().collect();
+    pre_constraints.sort_by_key(|(hex, _)| *hex);
+    writer.write_varint(pre_constraints.len() as u64);
+    for ((q, r), tile_type) in pre_constraints {
+        writer.write_signed(q);
+        writer.write_signed(r);
+        writer.write_u8(tile_type as u8);
+    }
+    
+    // Chunks: center + rings, tiles as runs in ring order
+    let mut centers: Vec<(i32, i32)> = state.chunk_centers().collect();
+    centers.sort();
+    writer.write_varint(centers.len() as u64);
+    for &(q, r) in &centers {
+        let rings = state.chunk((q, r)).map_or(0, |record| record.rings.max(0));
+        writer.write_signed(q);
+        writer.write_signed(r);
+        writer.write_varint(rings as u64);
+        writer.write_runs(&ring_tile_bytes(state, rings, q, r));
+        writer.write_runs(&ring_zone_bytes(state, rings, q, r));
+        let buildings = state.chunk((q, r)).map_or(&[][..], |record| &record.buildings[..]);
+        writer.write_varint(buildings.len() as u64);
+        for building in buildings {
+            writer.write_building(building);
+        }
+        let water_flags: Vec<u8> = generate_hex_grid(rings, q, r)
+            .iter()
+            .map(|hex| state.chunk((q, r)).is_some_and(|record| record.water.contains(&(hex.q, hex.r))) as u8)
+            .collect();
+        writer.write_runs(&water_flags);
+    }
+    
+    // Loose tiles (e.g. from generate_layout) that no chunk owns, as rows of consecutive hexes
+    // so a single far tile costs a few bytes instead of a disc reaching out to it
+    let mut loose: Vec<((i32, i32), TileType)> = state
+        .tiles()
+        .filter(|&((q, r), _)| {
+            !centers.iter().any(|&center| {
+                state.chunk(center).is_some_and(|record| record.contains(center, q, r))
+            })
+        })
+        .collect();
+    loose.sort_by_key(|&((q, r), _)| (r, q));
+    let mut spans: Vec<(i32, i32, Vec<u8>)> = Vec::new();
+    for ((q, r), tile_type) in loose {
+        match spans.last_mut() {
+            Some((span_r, first_q, tiles)) if *span_r == r && *first_q + tiles.len() as i32 == q => {
+                tiles.push(tile_type as u8);
+            },
+            _ => spans.push((r, q, vec![tile_type as u8])),
+        }
+    }
+    writer.write_varint(spans.len() as u64);
+    for (r, first_q, tiles) in &spans {
+        writer.write_signed(*r);
+        writer.write_signed(*first_q);
+        writer.write_varint(tiles.len() as u64);
+        writer.write_runs(tiles);
+    }
+    
+    let sum = checksum(&writer.bytes);
+    writer.bytes.extend_from_slice(&sum.to_le_bytes());
+    writer.bytes
+}
+
+/// Deserialize a saved blob into a fresh layout state
+/// The blob is fully validated before anything is returned
+pub fn load_state(bytes: &[u8]) -> Result<WfcState, LoadError> {
+    if bytes.len() < MAGIC.len() + 1 || &bytes[..MAGIC.len()] != MAGIC {
+        return Err(LoadError::BadMagic);
+    }
+    let version = bytes[MAGIC.len()];
+    if version != FORMAT_VERSION {
+        return Err(LoadError::UnsupportedVersion(version));
+    }
+    if bytes.len() < MAGIC.len() + 1 + 4 {
+        return Err(LoadError::Truncated);
+    }
+    let (body, stored_sum) = bytes.split_at(bytes.len() - 4);
+    if checksum(body) != u32::from_le_bytes(stored_sum.try_into().unwrap()) {
+        return Err(LoadError::ChecksumMismatch);
+    }
+    
+    let mut reader = Reader { bytes: body, position: MAGIC.len() + 1 };
+    let mut state = WfcState::new();
+    state.set_world_seed(reader.read_u64()?);
+    
+    let pre_constraint_count = reader.read_varint()?;
+    for _ in 0..pre_constraint_count {
+        let q = reader.read_signed()?;
+        let r = reader.read_signed()?;
+        let tile_type = reader.read_tile()?.ok_or(LoadError::InvalidTile(EMPTY_TILE))?;
+        state.set_pre_constraint(q, r, tile_type);
+    }
+    
+    let chunk_count = reader.read_varint()?;
+    for _ in 0..chunk_count {
+        let q = reader.read_signed()?;
+        let r = reader.read_signed()?;
+        let rings = reader.read_varint()?;
+        if rings > MAX_LOAD_RINGS {
+            return Err(LoadError::TooLarge(rings));
+        }
+        let rings = rings as i32;
+        let hexes = generate_hex_grid(rings, q, r);
+        let tiles = reader.read_runs(hexes.len())?;
+        let chunk_tiles: Vec<((i32, i32), TileType)> = hexes
+            .iter()
+            .zip(tiles)
+            .filter_map(|(hex, tile)| tile.map(|tile_type| ((hex.q, hex.r), tile_type)))
+            .collect();
+        let zones = reader.read_zone_runs(hexes.len())?;
+        let building_count = reader.read_varint()?;
+        let mut buildings = Vec::new();
+        for _ in 0..building_count {
+            buildings.push(reader.read_building()?);
+        }
+        let water: HashSet<(i32, i32)> = hexes
+            .iter()
+            .zip(reader.read_flag_runs(hexes.len())?)
+            .filter(|&(_, is_water)| is_water)
+            .map(|(hex, _)| (hex.q, hex.r))
+            .collect();
+        
+        state.insert_chunk((q, r), rings, chunk_tiles, buildings, water);
+        for (hex, zone) in hexes.iter().zip(zones) {
+            state.set_zone(hex.q, hex.r, zone);
+        }
+    }
+    
+    let span_count = reader.read_varint()?;
+    for _ in 0..span_count {
+        let r = reader.read_signed()?;
+        let first_q = reader.read_signed()?;
+        let length = reader.read_varint()?;
+        if length > MAX_LOAD_SPAN {
+            return Err(LoadError::TooLarge(length));
+        }
+        let tiles = reader.read_runs(length as usize)?;
+        for (offset, tile) in tiles.into_iter().enumerate() {
+            let tile_type = tile.ok_or(LoadError::InvalidTile(EMPTY_TILE))?;
+            state.insert_tile(first_q.wrapping_add(offset as i32), r, tile_type);
+        }
+    }
+    
+    if reader.position != body.len() {
+        return Err(LoadError::Truncated);
+    }
+    Ok(state)
+}
+
+/// Save the whole layout state as a binary blob
+/// 
+/// **Learning Point**: Returned to TypeScript as a Uint8Array, ready to store in
+/// IndexedDB next to the pattern cache.
+/// 
+/// @returns Versioned, checksummed save data
+#[wasm_bindgen]
+pub fn save_world() -> Vec<u8> {
+    let state = WFC_STATE.lock().unwrap();
+    save_state(&state)
+}
+
+/// Load a layout state saved by save_world, replacing the current state
+/// 
+/// The current state is left untouched if the data is rejected (wrong magic,
+/// different format version, bad checksum or truncated data).
+/// 
+/// @param data - Bytes returned by save_world
+/// @returns JSON string: {"ok":true,"chunks":0,"tiles":0} or {"ok":false,"error":"..."}
+#[wasm_bindgen]
+pub fn load_world(data: &[u8]) -> String {
+    match load_state(data) {
+        Ok(loaded) => {
+            let mut state = WFC_STATE.lock().unwrap();
+            *state = loaded;
+            let stats = state.memory_stats();
+            drop(state);
+            invalidate_all_flow_fields();
+            // Loaded pre-constraints replace the ones placements were written to
+            forget_prefab_placements();
+            format!(r#"{{"ok":true,"chunks":{},"tiles":{}}}"#, stats.chunks, stats.tiles)
+        },
+        Err(error) => format!(r#"{{"ok":false,"error":"{}"}}"#, error.message()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn sample_state() -> WfcState {
+        let mut state = WfcState::new();
+        state.set_world_seed(42);
+        state.set_pre_constraint(1, -1, TileType::Water);
+        let chunk_tiles = generate_hex_grid(2, 0, 0)
+            .iter()
+            .enumerate()
+            .map(|(index, hex)| ((hex.q, hex.r), if index % 3 == 0 { TileType::Road } else { TileType::Grass }))
+            .collect();
+        let building = BuildingRecord { anchor: (1, 0), hexes: vec![(1, 0), (2, 0)], facing: 3, size: BuildingSize::Medium };
+        state.insert_chunk((0, 0), 2, chunk_tiles, vec![building], HashSet::from([(1, -1), (2, -1)]));
+        state.set_zone(0, 1, Zone::Market);
+        state.insert_tile(10, 0, TileType::Forest);
+        state.insert_tile(11, 0, TileType::Forest);
+        state.insert_tile(5000, -3000, TileType::Building);
+        state
+    }
+    
+    /// Replace the trailing checksum after editing the body
+    fn reseal(bytes: &mut Vec<u8>) {
+        bytes.truncate(bytes.len() - 4);
+        let sum = checksum(bytes);
+        bytes.extend_from_slice(&sum.to_le_bytes());
+    }
+    
+    #[test]
+    fn save_load_round_trip() {
+        let state = sample_state();
+        let saved = save_state(&state);
+        let loaded = load_state(&saved).unwrap();
+        
+        assert_eq!(save_state(&loaded), saved);
+        assert_eq!(loaded.world_seed(), 42);
+        assert_eq!(loaded.get_tile(5000, -3000), Some(TileType::Building));
+        assert_eq!(loaded.get_tile(11, 0), Some(TileType::Forest));
+        assert_eq!(loaded.get_zone(0, 1), Some(Zone::Market));
+        assert_eq!(loaded.chunk((0, 0)).unwrap().buildings, state.chunk((0, 0)).unwrap().buildings);
+        assert_eq!(loaded.chunk((0, 0)).unwrap().water, state.chunk((0, 0)).unwrap().water);
+        // A far loose tile costs a few bytes, not a disc reaching out to it
+        assert!(saved.len() < 128, "save is {} bytes", saved.len());
+    }
+    
+    #[test]
+    fn rejects_other_versions() {
+        let mut saved = save_state(&sample_state());
+        saved[MAGIC.len()] = FORMAT_VERSION + 1;
+        assert_eq!(load_state(&saved).err(), Some(LoadError::UnsupportedVersion(FORMAT_VERSION + 1)));
+    }
+    
+    #[test]
+    fn rejects_bad_checksum() {
+        let mut saved = save_state(&sample_state());
+        let last_body_byte = saved.len() - 5;
+        saved[last_body_byte] ^= 0x01;
+        assert_eq!(load_state(&saved).err(), Some(LoadError::ChecksumMismatch));
+    }
+    
+    #[test]
+    fn rejects_oversized_chunk() {
+        let mut writer = Writer { bytes: Vec::new() };
+        writer.bytes.extend_from_slice(MAGIC);
+        writer.write_u8(FORMAT_VERSION);
+        writer.bytes.extend_from_slice(&0u64.to_le_bytes());
+        writer.write_varint(0);
+        writer.write_varint(1);
+        writer.write_signed(0);
+        writer.write_signed(0);
+        writer.write_varint(1 << 40);
+        let mut bytes = writer.bytes;
+        bytes.extend_from_slice(&[0; 4]);
+        reseal(&mut bytes);
+        assert_eq!(load_state(&bytes).err(), Some(LoadError::TooLarge(1 << 40)));
+    }
+}
@@ -0,0 +1,412 @@
+/// Prefab module
+/// 
+/// **Learning Point**: A prefab is a named multi-hex template (a plaza, a harbour, a
+/// crossroads) registered once and placed many times. Placing one transforms its tiles
+/// to the target position and orientation, checks its placement rules against the
+/// current grid and rejects overlaps with earlier placements. Accepted placements are
+/// written as pre-constraints (remembering the ones they replace, which come back when
+/// the placement is removed), so every generator that already honors pre-constraints
+/// (generate_layout, generate_chunk, regenerate_region) treats them as fixed tiles and
+/// routes roads around and into them.
+
+use wasm_bindgen::prelude::*;
+use std::sync::{LazyLock, Mutex};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use crate::state::{WfcState, WFC_STATE};
+use crate::types::{HexAxis, TileType};
+use crate::hex_utils::get_hex_neighbors;
+use crate::transform::LayoutTransform;
+use crate::json::{json_objects, json_array_field, json_int_field, json_bool_field, json_string_field, json_string_array_field};
+
+/// Rules a placement must satisfy against the current grid
+/// 
+/// **Learning Point**: Rules read the current tile of each hex (its pre-constraint when it
+/// has one). Hexes without a tile fail both rules, so prefabs with rules are placed once
+/// the terrain they depend on has been generated; rule-free prefabs can go anywhere.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PlacementRules {
+    /// Every prefab hex must currently be one of these types (empty: anything)
+    pub must_be_on: Vec<TileType>,
+    /// At least one hex next to the prefab must be one of these types (empty: no rule)
+    pub must_touch: Vec<TileType>,
+}
+
+impl PlacementRules {
+    /// Format: {"mustBeOn":["grass"],"mustTouch":["water"]}
+    pub fn from_json(rules_json: &str) -> Self {
+        let tile_types = |key: &str| -> Vec<TileType> {
+            json_string_array_field(rules_json, key)
+                .iter()
+                .filter_map(|name| TileType::from_name(name))
+                .collect()
+        };
+        PlacementRules {
+            must_be_on: tile_types("mustBeOn"),
+            must_touch: tile_types("mustTouch"),
+        }
+    }
+}
+
+/// A registered prefab: tiles relative to its anchor (0, 0) plus placement rules
+pub struct PrefabTemplate {
+    pub tiles: Vec<((i32, i32), TileType)>,
+    pub rules: PlacementRules,
+}
+
+impl PrefabTemplate {
+    /// Format: {"tiles":[{"q":0,"r":0,"tileType":2},...],"mustBeOn":["grass"],"mustTouch":["water"]}
+    /// None when no tile is valid
+    pub fn from_json(template_json: &str) -> Option<Self> {
+        let tiles_json = json_array_field(template_json, "tiles")?;
+        let mut latest: BTreeMap<(i32, i32), TileType> = BTreeMap::new();
+        for object in json_objects(tiles_json) {
+            let (Some(q), Some(r)) = (json_int_field(object, "q"), json_int_field(object, "r")) else {
+                continue;
+            };
+            if let Some(tile_type) = json_int_field(object, "tileType").and_then(TileType::from_i32) {
+                latest.insert((q, r), tile_type);
+            }
+        }
+        if latest.is_empty() {
+            return None;
+        }
+        
+        Some(PrefabTemplate {
+            tiles: latest.into_iter().collect(),
+            rules: PlacementRules::from_json(template_json),
+        })
+    }
+}
+
+/// An accepted placement
+pub struct PrefabPlacement {
+    pub name: String,
+    pub transform: LayoutTransform,
+    /// Placed tiles in template order
+    pub tiles: Vec<((i32, i32), TileType)>,
+    /// Pre-constraint of each placed hex before the placement, in the same order
+    pub previous: Vec<Option<TileType>>,
+}
+
+/// A rule a candidate placement breaks
+pub struct RuleViolation {
+    /// "mustBeOn" or "mustTouch"
+    pub rule: &'static str,
+    /// Offending hex (for "mustTouch", the prefab anchor)
+    pub hex: (i32, i32),
+}
+
+/// Outcome of checking (and possibly making) a placement
+pub struct PlacementReport {
+    pub tiles: Vec<((i32, i32), TileType)>,
+    /// Ids of placed prefabs overlapping the candidate
+    pub conflicts: Vec<u32>,
+    pub violations: Vec<RuleViolation>,
+    /// Existing pre-constraints (not from prefabs) the placement replaces; they are
+    /// restored when the placement is removed
+    pub overridden: Vec<((i32, i32), TileType)>,
+    /// Id of the new placement, None if rejected or a dry run
+    pub id: Option<u32>,
+}
+
+impl PlacementReport {
+    pub fn to_json(&self) -> String {
+        let tile_parts: Vec<String> = self
+            .tiles
+            .iter()
+            .map(|((q, r), tile_type)| format!(r#"{{"q":{},"r":{},"tileType":{}}}"#, q, r, *tile_type as i32))
+            .collect();
+        let conflict_parts: Vec<String> = self.conflicts.iter().map(|id| id.to_string()).collect();
+        let violation_parts: Vec<String> = self
+            .violations
+            .iter()
+            .map(|violation| format!(r#"{{"rule":"{}","q":{},"r":{}}}"#, violation.rule, violation.hex.0, violation.hex.1))
+            .collect();
+        let overridden_parts: Vec<String> = self
+            .overridden
+            .iter()
+            .map(|((q, r), tile_type)| format!(r#"{{"q":{},"r":{},"tileType":{}}}"#, q, r, *tile_type as i32))
+            .collect();
+        format!(
+            r#"{{"placed":{},"id":{},"tiles":[{}],"conflicts":[{}],"violations":[{}],"overridden":[{}]}}"#,
+            self.id.is_some(),
+            self.id.map_or("null".to_string(), |id| id.to_string()),
+            tile_parts.join(","),
+            conflict_parts.join(","),
+            violation_parts.join(","),
+            overridden_parts.join(",")
+        )
+    }
+}
+
+/// Registered templates and placements
+pub struct PrefabRegistry {
+    next_id: u32,
+    templates: HashMap<String, PrefabTemplate>,
+    /// Placements by id; they never overlap
+    placements: BTreeMap<u32, PrefabPlacement>,
+}
+
+impl PrefabRegistry {
+    pub fn new() -> Self {
+        PrefabRegistry {
+            next_id: 1,
+            templates: HashMap::new(),
+            placements: BTreeMap::new(),
+        }
+    }
+    
+    /// Check a placement of a registered template; None if the name is unknown
+    pub fn check(&self, state: &WfcState, name: &str, transform: &LayoutTransform) -> Option<PlacementReport> {
+        let template = self.templates.get(name)?;
+        let tiles: Vec<((i32, i32), TileType)> = template
+            .tiles
+            .iter()
+            .map(|&(hex, tile_type)| (transform.apply(hex), tile_type))
+            .collect();
+        let footprint: HashSet<(i32, i32)> = tiles.iter().map(|(hex, _)| *hex).collect();
+        
+        let conflicts: Vec<u32> = self
+            .placements
+            .iter()
+            .filter(|(_, placement)| placement.tiles.iter().any(|(hex, _)| footprint.contains(hex)))
+            .map(|(id, _)| *id)
+            .collect();
+        
+        let current = |(q, r): (i32, i32)| state.get_pre_constraint(q, r).or_else(|| state.get_tile(q, r));
+        let mut violations = Vec::new();
+        if !template.rules.must_be_on.is_empty() {
+            for &(hex, _) in &tiles {
+                if !current(hex).is_some_and(|tile_type| template.rules.must_be_on.contains(&tile_type)) {
+                    violations.push(RuleViolation { rule: "mustBeOn", hex });
+                }
+            }
+        }
+        if !template.rules.must_touch.is_empty() {
+            let touches = footprint
+                .iter()
+                .flat_map(|&(q, r)| get_hex_neighbors(q, r))
+                .filter(|hex| !footprint.contains(hex))
+                .any(|hex| current(hex).is_some_and(|tile_type| template.rules.must_touch.contains(&tile_type)));
+            if !touches {
+                violations.push(RuleViolation { rule: "mustTouch", hex: transform.apply((0, 0)) });
+            }
+        }
+        
+        // Hand-set pre-constraints that differ from the prefab (conflicting prefabs are reported above)
+        let overridden: Vec<((i32, i32), TileType)> = tiles
+            .iter()
+            .filter_map(|&((q, r), tile_type)| {
+                state
+                    .get_pre_constraint(q, r)
+                    .filter(|&existing| existing != tile_type)
+                    .map(|existing| ((q, r), existing))
+            })
+            .filter(|(hex, _)| !self.placements.values().any(|placement| placement.tiles.iter().any(|(placed, _)| placed == hex)))
+            .collect();
+        
+        Some(PlacementReport { tiles, conflicts, violations, overridden, id: None })
+    }
+    
+    /// Remove a placement, restoring the pre-constraints it replaced
+    /// Hexes whose pre-constraint was changed since (e.g. by a pinned edit) keep it
+    fn remove_placement(&mut self, state: &mut WfcState, id: u32) -> bool {
+        let Some(removed) = self.placements.remove(&id) else {
+            return false;
+        };
+        for (((q, r), tile_type), previous) in removed.tiles.into_iter().zip(removed.previous) {
+            if state.get_pre_constraint(q, r) != Some(tile_type) {
+                continue;
+            }
+            match previous {
+                Some(previous) => {
+                    state.set_pre_constraint(q, r, previous);
+                },
+                None => {
+                    state.remove_pre_constraint(q, r);
+                },
+            }
+        }
+        true
+    }
+}
+
+/// Prefab templates and placements
+/// Lock order: PREFABS before WFC_STATE
+pub static PREFABS: LazyLock<Mutex<PrefabRegistry>> = LazyLock::new(|| Mutex::new(PrefabRegistry::new()));
+
+/// Drop all placement records without touching the pre-constraints
+/// (call after the pre-constraints were cleared or replaced); callers must not hold WFC_STATE
+pub fn forget_prefab_placements() {
+    PREFABS.lock().unwrap().placements.clear();
+}
+
+/// Parse the placement transform: anchor at (q, r), `rotation` 60 degree steps,
+/// optional "reflect":"q"|"r"|"s" from the options
+fn placement_transform(q: i32, r: i32, rotation: i32, options_json: &str) -> LayoutTransform {
+    LayoutTransform {
+        rotation: rotation.rem_euclid(6),
+        reflect: json_string_field(options_json, "reflect").and_then(|value| HexAxis::from_str(&value)),
+        center: (0, 0),
+        offset: (q, r),
+    }
+}
+
+/// Register (or replace) a named prefab template
+/// 
+/// @param name - Prefab name, e.g. "plaza"
+/// @param template_json - JSON object (see PrefabTemplate::from_json); coordinates are
+///                        relative to the anchor hex (0, 0)
+/// @returns Number of template hexes, 0 if the template has no valid tiles (not registered)
+#[wasm_bindgen]
+pub fn register_prefab(name: String, template_json: String) -> i32 {
+    let Some(template) = PrefabTemplate::from_json(&template_json) else {
+        return 0;
+    };
+    let count = template.tiles.len() as i32;
+    PREFABS.lock().unwrap().templates.insert(name, template);
+    count
+}
+
+/// Unregister a prefab template (its placements stay)
+/// 
+/// @param name - Prefab name
+/// @returns true if the template was registered
+#[wasm_bindgen]
+pub fn unregister_prefab(name: String) -> bool {
+    PREFABS.lock().unwrap().templates.remove(&name).is_some()
+}
+
+/// Get the registered prefab templates
+/// 
+/// @returns JSON array sorted by name: [{"name":"plaza","hexes":7,"mustBeOn":["grass"],"mustTouch":[]},...]
+#[wasm_bindgen]
+pub fn get_prefabs() -> String {
+    let prefabs = PREFABS.lock().unwrap();
+    let mut names: Vec<&String> = prefabs.templates.keys().collect();
+    names.sort();
+    
+    let names_json = |tile_types: &[TileType]| {
+        let parts: Vec<String> = tile_types.iter().map(|tile_type| format!(r#""{}""#, tile_type.as_str())).collect();
+        format!("[{}]", parts.join(","))
+    };
+    let json_parts: Vec<String> = names
+        .iter()
+        .map(|name| {
+            let template = &prefabs.templates[*name];
+            format!(
+                r#"{{"name":"{}","hexes":{},"mustBeOn":{},"mustTouch":{}}}"#,
+                name,
+                template.tiles.len(),
+                names_json(&template.rules.must_be_on),
+                names_json(&template.rules.must_touch)
+            )
+        })
+        .collect();
+    
+    format!("[{}]", json_parts.join(","))
+}
+
+/// Place a prefab as fixed pre-constraints
+/// 
+/// **Learning Point**: A placement is all or nothing: overlapping an earlier placement
+/// or breaking a rule rejects it and reports why, so callers can try the next position
+/// or rotation. Loaded chunks keep their tiles until regenerated (generate_chunk or
+/// regenerate_region), which then builds roads into the prefab.
+/// 
+/// @param name - Prefab name
+/// @param q - Anchor q coordinate (axial)
+/// @param r - Anchor r coordinate (axial)
+/// @param rotation - Rotation around the anchor in 60 degree steps
+/// @param options_json - JSON object: {"reflect":"q"|"r"|"s","dryRun":false}, "{}" for defaults
+///                       ("reflect" mirrors the template before rotating; "dryRun" only checks)
+/// @returns JSON string: {"placed":true,"id":1,"tiles":[{"q":0,"r":0,"tileType":2},...],
+///          "conflicts":[2,...],"violations":[{"rule":"mustBeOn","q":0,"r":0},...],
+///          "overridden":[{"q":0,"r":0,"tileType":1},...]} or "null" if the prefab is not registered
+///          ("overridden" lists hand-set pre-constraints the placement replaces until removed)
+#[wasm_bindgen]
+pub fn place_prefab(name: String, q: i32, r: i32, rotation: i32, options_json: String) -> String {
+    let transform = placement_transform(q, r, rotation, &options_json);
+    let dry_run = json_bool_field(&options_json, "dryRun").unwrap_or(false);
+    
+    let mut prefabs = PREFABS.lock().unwrap();
+    let mut state = WFC_STATE.lock().unwrap();
+    let Some(mut report) = prefabs.check(&state, &name, &transform) else {
+        return "null".to_string();
+    };
+    if dry_run || !report.conflicts.is_empty() || !report.violations.is_empty() {
+        return report.to_json();
+    }
+    
+    let mut previous = Vec::with_capacity(report.tiles.len());
+    for &((q, r), tile_type) in &report.tiles {
+        previous.push(state.get_pre_constraint(q, r));
+        state.set_pre_constraint(q, r, tile_type);
+    }
+    let id = prefabs.next_id;
+    prefabs.next_id += 1;
+    prefabs.placements.insert(id, PrefabPlacement { name, transform, tiles: report.tiles.clone(), previous });
+    report.id = Some(id);
+    report.to_json()
+}
+
+/// Remove a placed prefab and its pre-constraints, restoring the ones it replaced
+/// 
+/// @param id - Placement id returned by place_prefab
+/// @returns true if the placement existed
+#[wasm_bindgen]
+pub fn remove_prefab_placement(id: u32) -> bool {
+    let mut prefabs = PREFABS.lock().unwrap();
+    let mut state = WFC_STATE.lock().unwrap();
+    prefabs.remove_placement(&mut state, id)
+}
+
+/// Get all placed prefabs
+/// 
+/// @returns JSON array by id: [{"id":1,"name":"plaza","q":0,"r":0,"rotation":0,"reflect":"q"|null,
+///          "tiles":[{"q":0,"r":0,"tileType":2},...]},...]
+#[wasm_bindgen]
+pub fn get_prefab_placements() -> String {
+    let prefabs = PREFABS.lock().unwrap();
+    let json_parts: Vec<String> = prefabs
+        .placements
+        .iter()
+        .map(|(id, placement)| {
+            let reflect = match placement.transform.reflect {
+                Some(HexAxis::Q) => r#""q""#,
+                Some(HexAxis::R) => r#""r""#,
+                Some(HexAxis::S) => r#""s""#,
+                None => "null",
+            };
+            let tile_parts: Vec<String> = placement
+                .tiles
+                .iter()
+                .map(|((q, r), tile_type)| format!(r#"{{"q":{},"r":{},"tileType":{}}}"#, q, r, *tile_type as i32))
+                .collect();
+            format!(
+                r#"{{"id":{},"name":"{}","q":{},"r":{},"rotation":{},"reflect":{},"tiles":[{}]}}"#,
+                id,
+                placement.name,
+                placement.transform.offset.0,
+                placement.transform.offset.1,
+                placement.transform.rotation,
+                reflect,
+                tile_parts.join(",")
+            )
+        })
+        .collect();
+    
+    format!("[{}]", json_parts.join(","))
+}
+
+/// Remove all placed prefabs and their pre-constraints, restoring the ones they replaced
+/// (templates stay registered)
+#[wasm_bindgen]
+pub fn clear_prefab_placements() {
+    let mut prefabs = PREFABS.lock().unwrap();
+    let mut state = WFC_STATE.lock().unwrap();
+    let ids: Vec<u32> = prefabs.placements.keys().copied().collect();
+    for id in ids {
+        prefabs.remove_placement(&mut state, id);
+    }
+}
@@ -1,9 +1,12 @@
 /// Road network generation module
 
 use wasm_bindgen::prelude::*;
-use std::collections::HashSet;
-use crate::astar::hex_astar;
-use crate::hex_utils::{parse_valid_terrain_json, parse_path_json, hex_distance};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use crate::astar::find_path_in_set;
+use crate::hex_utils::{parse_valid_terrain_json, hex_distance, CUBE_DIRECTIONS};
+use crate::json::{json_objects, json_int_field, json_number_field, json_string_field, json_bool_field, coords_to_json};
+use crate::types::TileType;
 
 /// Find nearest point in connected set to a given point
 /// Returns the nearest point and its distance
@@ -20,7 +23,8 @@ fn find_nearest_in_set(
     
     for &connected_point in connected_set {
         let dist = hex_distance(point.0, point.1, connected_point.0, connected_point.1);
-        if dist < min_distance {
+        // Break distance ties by coordinate so the result never depends on set iteration order
+        if dist < min_distance || (dist == min_distance && nearest.is_some_and(|n| connected_point < n)) {
             min_distance = dist;
             nearest = Some(connected_point);
         }
@@ -29,51 +33,16 @@ fn find_nearest_in_set(
     nearest.map(|n| (n, min_distance))
 }
 
-/// Generate road network using true growing tree algorithm
-/// 
-/// Algorithm:
-/// 1. Start with first seed point
-/// 2. For each remaining seed: find nearest connected road, build A* path, add path
-/// 3. For expansion: repeatedly find nearest unconnected valid terrain to any connected road,
-///    build A* path, add path. Continue until target count reached.
+/// Grow a connected road tree over valid terrain
 /// 
-/// This creates a true tree structure where every road is connected via a path,
-/// not just adjacent (which would be flood fill).
-/// 
-/// @param seeds_json - JSON array of seed points: [{"q":0,"r":0},...]
-/// @param valid_terrain_json - JSON array of valid terrain: [{"q":0,"r":0},...]
-/// @param occupied_json - JSON array of occupied hexes: [{"q":0,"r":0},...]
-/// @param target_count - Target number of roads to generate
-/// @returns JSON array of road coordinates: [{"q":0,"r":0},...]
-#[wasm_bindgen]
-pub fn generate_road_network_growing_tree(
-    seeds_json: String,
-    valid_terrain_json: String,
-    occupied_json: String,
-    target_count: i32,
-) -> String {
-    // Parse inputs
-    let seeds = parse_valid_terrain_json(&seeds_json);
-    let valid_terrain = parse_valid_terrain_json(&valid_terrain_json);
-    let occupied = parse_valid_terrain_json(&occupied_json);
-    
-    // Build valid terrain set (valid terrain minus occupied)
-    let mut valid_terrain_set = HashSet::new();
-    for &hex in &valid_terrain {
-        if !occupied.contains(&hex) {
-            valid_terrain_set.insert(hex);
-        }
-    }
-    
-    // Convert valid terrain to JSON for hex_astar calls
-    let mut valid_terrain_vec: Vec<(i32, i32)> = valid_terrain_set.iter().cloned().collect();
-    valid_terrain_vec.sort();
-    let mut valid_terrain_json_parts = Vec::new();
-    for (q, r) in &valid_terrain_vec {
-        valid_terrain_json_parts.push(format!(r#"{{"q":{},"r":{}}}"#, q, r));
-    }
-    let valid_terrain_json_for_astar = format!("[{}]", valid_terrain_json_parts.join(","));
-    
+/// Connects the seeds in order (the first seed is the root), then keeps attaching the
+/// nearest unconnected hex with an A* path until `target_count` roads exist or no
+/// reachable hex is left. Seeds outside `valid_terrain_set` are skipped.
+pub fn grow_road_tree(
+    seeds: &[(i32, i32)],
+    valid_terrain_set: &HashSet<(i32, i32)>,
+    target_count: usize,
+) -> HashSet<(i32, i32)> {
     // Connected set: roads in the network
     let mut connected: HashSet<(i32, i32)> = HashSet::new();
     
@@ -82,7 +51,7 @@ pub fn generate_road_network_growing_tree(
     
     // Phase 1: Connect seed points
     if !seeds.is_empty() {
-        let first_seed = seeds.iter().next().copied();
+        let first_seed = seeds.first().copied();
         if let Some(seed) = first_seed {
             if valid_terrain_set.contains(&seed) {
                 connected.insert(seed);
@@ -106,16 +75,7 @@ pub fn generate_road_network_growing_tree(
             // Find nearest connected road
             if let Some((nearest_road, _)) = find_nearest_in_set(*seed, &connected) {
                 // Build path from nearest road to seed
-                let path_json = hex_astar(
-                    nearest_road.0,
-                    nearest_road.1,
-                    seed.0,
-                    seed.1,
-                    valid_terrain_json_for_astar.clone(),
-                );
-                
-                if path_json != "null" && !path_json.is_empty() {
-                    let path = parse_path_json(&path_json);
+                if let Some(path) = find_path_in_set(nearest_road, *seed, valid_terrain_set) {
                     // Add all path hexes to connected
                     for path_hex in path {
                         connected.insert(path_hex);
@@ -127,7 +87,7 @@ pub fn generate_road_network_growing_tree(
     }
     
     // Phase 2: Expand to target density using growing tree
-    while (connected.len() as i32) < target_count && !unconnected.is_empty() {
+    while connected.len() < target_count && !unconnected.is_empty() {
         let mut best_unconnected: Option<(i32, i32)> = None;
         let mut best_connected: Option<(i32, i32)> = None;
         let mut min_distance = i32::MAX;
@@ -135,7 +95,9 @@ pub fn generate_road_network_growing_tree(
         // Find nearest unconnected point to any connected road
         for &unconnected_point in &unconnected {
             if let Some((nearest_road, distance)) = find_nearest_in_set(unconnected_point, &connected) {
-                if distance < min_distance {
+                let is_tie_winner = distance == min_distance
+                    && best_unconnected.is_some_and(|best| unconnected_point < best);
+                if distance < min_distance || is_tie_winner {
                     min_distance = distance;
                     best_unconnected = Some(unconnected_point);
                     best_connected = Some(nearest_road);
@@ -145,16 +107,7 @@ pub fn generate_road_network_growing_tree(
         
         // Build path and add to network
         if let (Some(unconnected_point), Some(connected_road)) = (best_unconnected, best_connected) {
-            let path_json = hex_astar(
-                connected_road.0,
-                connected_road.1,
-                unconnected_point.0,
-                unconnected_point.1,
-                valid_terrain_json_for_astar.clone(),
-            );
-            
-            if path_json != "null" && !path_json.is_empty() {
-                let path = parse_path_json(&path_json);
+            if let Some(path) = find_path_in_set(connected_road, unconnected_point, valid_terrain_set) {
                 // Add all path hexes to connected
                 for path_hex in path {
                     connected.insert(path_hex);
@@ -170,6 +123,49 @@ pub fn generate_road_network_growing_tree(
         }
     }
     
+    connected
+}
+
+/// Generate road network using true growing tree algorithm
+/// 
+/// Algorithm:
+/// 1. Start with first seed point
+/// 2. For each remaining seed: find nearest connected road, build A* path, add path
+/// 3. For expansion: repeatedly find nearest unconnected valid terrain to any connected road,
+///    build A* path, add path. Continue until target count reached.
+/// 
+/// This creates a true tree structure where every road is connected via a path,
+/// not just adjacent (which would be flood fill).
+/// 
+/// @param seeds_json - JSON array of seed points: [{"q":0,"r":0},...]
+/// @param valid_terrain_json - JSON array of valid terrain: [{"q":0,"r":0},...]
+/// @param occupied_json - JSON array of occupied hexes: [{"q":0,"r":0},...]
+/// @param target_count - Target number of roads to generate
+/// @returns JSON array of road coordinates: [{"q":0,"r":0},...]
+#[wasm_bindgen]
+pub fn generate_road_network_growing_tree(
+    seeds_json: String,
+    valid_terrain_json: String,
+    occupied_json: String,
+    target_count: i32,
+) -> String {
+    // Parse inputs
+    // Seeds are sorted so the first seed (the tree root) is deterministic
+    let mut seeds: Vec<(i32, i32)> = parse_valid_terrain_json(&seeds_json).into_iter().collect();
+    seeds.sort();
+    let valid_terrain = parse_valid_terrain_json(&valid_terrain_json);
+    let occupied = parse_valid_terrain_json(&occupied_json);
+    
+    // Build valid terrain set (valid terrain minus occupied)
+    let mut valid_terrain_set = HashSet::new();
+    for &hex in &valid_terrain {
+        if !occupied.contains(&hex) {
+            valid_terrain_set.insert(hex);
+        }
+    }
+    
+    let connected = grow_road_tree(&seeds, &valid_terrain_set, target_count.max(0) as usize);
+    
     // Convert connected set to JSON array
     let mut road_vec: Vec<(i32, i32)> = connected.iter().cloned().collect();
     road_vec.sort();
@@ -181,3 +177,520 @@ pub fn generate_road_network_growing_tree(
     format!("[{}]", json_parts.join(","))
 }
 
+
+/// Fixed-point scale for path costs (keeps the priority queue on integers)
+const COST_SCALE: f64 = 100.0;
+
+/// Direction marker for search nodes that have not moved yet
+const NO_DIRECTION: u8 = 6;
+
+/// Step cost for a bridge hex (bridges are expensive, so roads share them)
+const BRIDGE_COST: f64 = 4.0 * COST_SCALE;
+
+/// How roads treat river hexes
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RiverCrossing {
+    /// Cross rivers on straight bridges (one river hex entered and left in the same direction)
+    Bridge,
+    /// Never cross rivers
+    Avoid,
+}
+
+/// Parameters for MST road generation
+/// Parsed from JSON: {"straightness":0.5,"maxBranches":4,"riverCrossing":"bridge"|"avoid"}
+pub struct RoadNetworkParams {
+    /// 0.0 ignores turns, 1.0 strongly prefers long straight runs
+    pub straightness: f64,
+    /// Maximum number of roads leaving any hex of the network (counted along the routed
+    /// paths, not by adjacency); below 2 a road can only run one hex
+    pub max_branches: usize,
+    pub river_crossing: RiverCrossing,
+}
+
+impl Default for RoadNetworkParams {
+    fn default() -> Self {
+        RoadNetworkParams {
+            straightness: 0.0,
+            max_branches: 6,
+            river_crossing: RiverCrossing::Bridge,
+        }
+    }
+}
+
+impl RoadNetworkParams {
+    pub fn from_json(params_json: &str) -> Self {
+        let defaults = RoadNetworkParams::default();
+        RoadNetworkParams {
+            straightness: json_number_field(params_json, "straightness")
+                .unwrap_or(defaults.straightness)
+                .clamp(0.0, 1.0),
+            max_branches: json_int_field(params_json, "maxBranches")
+                .map(|value| value.clamp(1, 6) as usize)
+                .unwrap_or(defaults.max_branches),
+            river_crossing: match json_string_field(params_json, "riverCrossing").as_deref() {
+                Some("avoid") => RiverCrossing::Avoid,
+                Some("bridge") => RiverCrossing::Bridge,
+                _ => defaults.river_crossing,
+            },
+        }
+    }
+    
+    /// Extra cost for changing direction by `turn` sixths of a full rotation (1-3)
+    fn turn_penalty(&self, turn: u8) -> i64 {
+        (self.straightness * 1.5 * COST_SCALE) as i64 * turn as i64
+    }
+}
+
+/// Result of MST road generation
+pub struct RoadNetwork {
+    /// Paved road hexes, sorted
+    pub roads: Vec<(i32, i32)>,
+    /// Endpoints that could not be reached, sorted
+    pub unconnected: Vec<(i32, i32)>,
+    /// Road hexes that cross a river, sorted
+    pub bridges: Vec<(i32, i32)>,
+    /// Routed road edges at each network hex with any, sorted (the count maxBranches limits)
+    pub branches: Vec<((i32, i32), usize)>,
+}
+
+/// Step cost for entering a tile of the given type
+/// Existing roads are cheap so new routes merge into them; buildings and water are impassable
+fn terrain_step_cost(tile_type: TileType) -> Option<i64> {
+    match tile_type {
+        TileType::Road => Some((0.5 * COST_SCALE) as i64),
+        TileType::Grass => Some(COST_SCALE as i64),
+        TileType::Forest => Some((3.0 * COST_SCALE) as i64),
+        TileType::Building | TileType::Water => None,
+    }
+}
+
+/// Number of sixth-turns between two direction indices (0-3)
+fn turn_size(from: u8, to: u8) -> u8 {
+    let diff = (from as i32 - to as i32).unsigned_abs() as u8;
+    diff.min(6 - diff)
+}
+
+/// Plain Dijkstra from a terminal over terrain costs
+/// Returns the cost to reach every terminal reachable from `source`
+/// Terminals may be impassable (e.g. buildings): they can be reached but not walked through
+fn terminal_costs(
+    source: (i32, i32),
+    terminals: &HashSet<(i32, i32)>,
+    costs: &HashMap<(i32, i32), i64>,
+) -> HashMap<(i32, i32), i64> {
+    let mut dist: HashMap<(i32, i32), i64> = HashMap::new();
+    let mut reached: HashMap<(i32, i32), i64> = HashMap::new();
+    let mut open_set = BinaryHeap::new();
+    
+    dist.insert(source, 0);
+    open_set.push(Reverse((0_i64, source.0, source.1)));
+    
+    while let Some(Reverse((cost, q, r))) = open_set.pop() {
+        if dist.get(&(q, r)).is_some_and(|&best| cost > best) {
+            continue;
+        }
+        
+        if terminals.contains(&(q, r)) {
+            reached.insert((q, r), cost);
+        }
+        
+        // Impassable terminals end the walk (the source is always expanded)
+        if (q, r) != source && !costs.contains_key(&(q, r)) {
+            continue;
+        }
+        
+        for direction in CUBE_DIRECTIONS.iter() {
+            let neighbor = (q + direction.q, r + direction.r);
+            let step = match costs.get(&neighbor) {
+                Some(&step) => step,
+                None if terminals.contains(&neighbor) => COST_SCALE as i64,
+                None => continue,
+            };
+            
+            let next_cost = cost + step;
+            if dist.get(&neighbor).is_none_or(|&best| next_cost < best) {
+                dist.insert(neighbor, next_cost);
+                open_set.push(Reverse((next_cost, neighbor.0, neighbor.1)));
+            }
+        }
+    }
+    
+    reached
+}
+
+/// Order terminals with Prim's algorithm over pairwise path costs
+/// Returns one insertion order per tree of the spanning forest: when no remaining
+/// terminal is reachable, a new tree starts at the lowest unreached terminal
+fn prim_order(
+    terminals: &[(i32, i32)],
+    costs: &HashMap<(i32, i32), i64>,
+) -> Vec<Vec<(i32, i32)>> {
+    let terminal_set: HashSet<(i32, i32)> = terminals.iter().copied().collect();
+    let pairwise: Vec<HashMap<(i32, i32), i64>> = terminals
+        .iter()
+        .map(|&terminal| terminal_costs(terminal, &terminal_set, costs))
+        .collect();
+    
+    let mut in_tree = vec![false; terminals.len()];
+    let mut best_cost = vec![i64::MAX; terminals.len()];
+    let mut trees: Vec<Vec<(i32, i32)>> = Vec::new();
+    
+    loop {
+        // Pick the cheapest terminal not yet in the tree
+        let next = (0..terminals.len())
+            .filter(|&i| !in_tree[i] && best_cost[i] < i64::MAX)
+            .min_by_key(|&i| (best_cost[i], terminals[i]));
+        
+        let next = match next {
+            Some(next) => next,
+            None => match in_tree.iter().position(|&added| !added) {
+                // Nothing left is reachable from the current tree: start the next one
+                Some(root) => {
+                    trees.push(Vec::new());
+                    root
+                }
+                None => break,
+            },
+        };
+        if trees.is_empty() {
+            trees.push(Vec::new());
+        }
+        
+        in_tree[next] = true;
+        trees.last_mut().expect("a tree was started").push(terminals[next]);
+        
+        for (i, terminal) in terminals.iter().enumerate() {
+            if let Some(&cost) = pairwise[next].get(terminal) {
+                if !in_tree[i] && cost < best_cost[i] {
+                    best_cost[i] = cost;
+                }
+            }
+        }
+    }
+    
+    trees
+}
+
+/// Add a routed path to the road network, counting one road edge per step
+/// 
+/// Only consecutive path hexes are linked: a road that runs past another one without
+/// joining it adds no branch to either.
+fn add_to_network(
+    path: &[(i32, i32)],
+    network: &mut HashSet<(i32, i32)>,
+    degrees: &mut HashMap<(i32, i32), usize>,
+) {
+    network.extend(path.iter().copied());
+    for pair in path.windows(2) {
+        *degrees.entry(pair[0]).or_insert(0) += 1;
+        *degrees.entry(pair[1]).or_insert(0) += 1;
+    }
+}
+
+/// Route from a terminal to the nearest attachable hex of the existing network
+/// 
+/// **Learning Point**: Stopping at the first network hex (instead of the MST partner
+/// terminal) is the shortest-path Steiner heuristic: new roads merge into existing
+/// ones mid-way, creating Steiner points rather than parallel roads.
+/// The search state includes the travel direction so turns can be penalised and so
+/// bridges can be kept straight: a river hex must be left in the direction it was entered.
+/// The branch limit holds on every hex the path touches: the joined hex gains one road
+/// edge, and each new hex gets two (one when the path is a single step).
+fn route_to_network(
+    start: (i32, i32),
+    network: &HashSet<(i32, i32)>,
+    degrees: &HashMap<(i32, i32), usize>,
+    costs: &HashMap<(i32, i32), i64>,
+    rivers: &HashSet<(i32, i32)>,
+    params: &RoadNetworkParams,
+) -> Option<Vec<(i32, i32)>> {
+    type SearchNode = (i32, i32, u8);
+    
+    let start_node: SearchNode = (start.0, start.1, NO_DIRECTION);
+    let mut dist: HashMap<SearchNode, i64> = HashMap::new();
+    let mut parents: HashMap<SearchNode, SearchNode> = HashMap::new();
+    let mut open_set = BinaryHeap::new();
+    
+    dist.insert(start_node, 0);
+    open_set.push(Reverse((0_i64, start_node)));
+    
+    while let Some(Reverse((cost, node))) = open_set.pop() {
+        if dist.get(&node).is_some_and(|&best| cost > best) {
+            continue;
+        }
+        
+        let (q, r, direction) = node;
+        
+        if node != start_node && network.contains(&(q, r)) {
+            // Attach here unless the junction is already at its branch limit (or a bridge)
+            if degrees.get(&(q, r)).copied().unwrap_or(0) < params.max_branches && !rivers.contains(&(q, r)) {
+                let mut path = vec![(q, r)];
+                let mut current = node;
+                while let Some(&parent) = parents.get(&current) {
+                    path.push((parent.0, parent.1));
+                    current = parent;
+                }
+                path.reverse();
+                return Some(path);
+            }
+            continue;
+        }
+        
+        // Impassable start hexes (e.g. buildings) may only be left, never re-entered, and
+        // passing through a hex gives it two road edges
+        if node != start_node && (!costs.contains_key(&(q, r)) || params.max_branches < 2) {
+            continue;
+        }
+        
+        let on_bridge = direction != NO_DIRECTION && rivers.contains(&(q, r));
+        for (next_direction, offset) in CUBE_DIRECTIONS.iter().enumerate() {
+            let next_direction = next_direction as u8;
+            if on_bridge && next_direction != direction {
+                continue;
+            }
+            let neighbor = (q + offset.q, r + offset.r);
+            let step = match costs.get(&neighbor) {
+                Some(&step) => step,
+                None if network.contains(&neighbor) => COST_SCALE as i64,
+                None => continue,
+            };
+            
+            let turn = if direction == NO_DIRECTION {
+                0
+            } else {
+                params.turn_penalty(turn_size(direction, next_direction))
+            };
+            
+            let next_node = (neighbor.0, neighbor.1, next_direction);
+            let next_cost = cost + step + turn;
+            if dist.get(&next_node).is_none_or(|&best| next_cost < best) {
+                dist.insert(next_node, next_cost);
+                parents.insert(next_node, node);
+                open_set.push(Reverse((next_cost, next_node)));
+            }
+        }
+    }
+    
+    None
+}
+
+/// Connect required endpoints with an MST + Steiner merging road network
+/// 
+/// Algorithm:
+/// 1. Compute terrain-aware path costs between all endpoints (Dijkstra per endpoint)
+/// 2. Order endpoints with Prim's algorithm over those costs (minimum spanning tree)
+/// 3. In MST order, route each endpoint to the nearest point of the network built so far,
+///    so roads merge at Steiner points instead of running in parallel
+/// 
+/// Terrain costs: road 0.5, grass 1, forest 3; buildings and water are impassable.
+/// River hexes cost 4 and must be crossed straight when `river_crossing` is Bridge,
+/// and are impassable when it is Avoid.
+/// Endpoints are required: each one is paved even on impassable terrain, except
+/// occupied endpoints (e.g. building hexes), which are connected to but never paved,
+/// and river endpoints under Avoid, which are reported as unconnected.
+/// Groups of endpoints cut off from each other (e.g. by a lake) are each connected
+/// on their own; an endpoint that can reach no other one is reported as unconnected.
+pub fn connect_endpoints(
+    endpoints: &[(i32, i32)],
+    terrain: &HashMap<(i32, i32), TileType>,
+    occupied: &HashSet<(i32, i32)>,
+    rivers: &HashSet<(i32, i32)>,
+    params: &RoadNetworkParams,
+) -> RoadNetwork {
+    // Build step cost map from terrain (occupied hexes are impassable)
+    let mut costs: HashMap<(i32, i32), i64> = HashMap::new();
+    for (&hex, &tile_type) in terrain {
+        if occupied.contains(&hex) {
+            continue;
+        }
+        if rivers.contains(&hex) {
+            if params.river_crossing == RiverCrossing::Bridge {
+                costs.insert(hex, BRIDGE_COST as i64);
+            }
+            continue;
+        }
+        if let Some(cost) = terrain_step_cost(tile_type) {
+            costs.insert(hex, cost);
+        }
+    }
+    
+    let mut terminals: Vec<(i32, i32)> = endpoints.to_vec();
+    terminals.sort();
+    terminals.dedup();
+    
+    if terminals.is_empty() {
+        return RoadNetwork { roads: Vec::new(), unconnected: Vec::new(), bridges: Vec::new(), branches: Vec::new() };
+    }
+    
+    // Required endpoints are always paved unless occupied (e.g. border exits on forest or water)
+    // or on a river that roads must avoid
+    let avoid_rivers = params.river_crossing == RiverCrossing::Avoid;
+    for terminal in &terminals {
+        if !occupied.contains(terminal) && (!avoid_rivers || !rivers.contains(terminal)) {
+            costs.entry(*terminal).or_insert(COST_SCALE as i64);
+        }
+    }
+    
+    // Terminals cut off by water or buildings form separate trees, each with its own roads;
+    // a terminal that can reach no other one is reported as unconnected
+    let trees = prim_order(&terminals, &costs);
+    let isolated = trees.len() > 1;
+    let mut unconnected: Vec<(i32, i32)> = Vec::new();
+    
+    // Grow the network terminal by terminal in MST order
+    let mut network: HashSet<(i32, i32)> = HashSet::new();
+    let mut degrees: HashMap<(i32, i32), usize> = HashMap::new();
+    for tree in &trees {
+        if isolated && tree.len() == 1 {
+            unconnected.push(tree[0]);
+            continue;
+        }
+        for (index, &terminal) in tree.iter().enumerate() {
+            if index == 0 {
+                network.insert(terminal);
+                continue;
+            }
+            if network.contains(&terminal) {
+                continue;
+            }
+            
+            match route_to_network(terminal, &network, &degrees, &costs, rivers, params) {
+                Some(path) => add_to_network(&path, &mut network, &mut degrees),
+                None => unconnected.push(terminal),
+            }
+        }
+    }
+    
+    // Occupied endpoints (buildings) are connected to, not paved
+    let mut roads: Vec<(i32, i32)> = network
+        .into_iter()
+        .filter(|hex| costs.contains_key(hex))
+        .collect();
+    roads.sort();
+    unconnected.sort();
+    let bridges: Vec<(i32, i32)> = roads.iter().copied().filter(|hex| rivers.contains(hex)).collect();
+    
+    let mut branches: Vec<((i32, i32), usize)> = degrees.into_iter().collect();
+    branches.sort();
+    
+    RoadNetwork { roads, unconnected, bridges, branches }
+}
+
+/// Generate a road network connecting required endpoints (MST + Steiner merging)
+/// 
+/// Unlike the growing tree generator this connects places rather than filling area:
+/// roads only exist where they are needed to link endpoints. See connect_endpoints
+/// for the algorithm and terrain costs.
+/// 
+/// Border exits from calculate_border_exits can be passed directly as endpoints.
+/// 
+/// @param endpoints_json - JSON array of required endpoints: [{"q":0,"r":0},...]
+/// @param terrain_json - JSON array of terrain: [{"q":0,"r":0,"tileType":0,"river":true},...]
+///                       (tileType defaults to grass, river to false)
+/// @param occupied_json - JSON array of occupied hexes: [{"q":0,"r":0},...]
+/// @param params_json - JSON object: {"straightness":0.0-1.0,"maxBranches":1-6,"riverCrossing":"bridge"|"avoid"}
+/// @returns JSON string: {"roads":[{"q":0,"r":0},...],"unconnected":[...],"bridges":[...],"junctions":[...]}
+///          (junctions are network hexes where three or more routed roads meet)
+#[wasm_bindgen]
+pub fn generate_road_network_mst(
+    endpoints_json: String,
+    terrain_json: String,
+    occupied_json: String,
+    params_json: String,
+) -> String {
+    let params = RoadNetworkParams::from_json(&params_json);
+    let occupied = parse_valid_terrain_json(&occupied_json);
+    
+    let mut terrain: HashMap<(i32, i32), TileType> = HashMap::new();
+    let mut rivers: HashSet<(i32, i32)> = HashSet::new();
+    for object in json_objects(&terrain_json) {
+        let (Some(q), Some(r)) = (json_int_field(object, "q"), json_int_field(object, "r")) else {
+            continue;
+        };
+        let tile_type = json_int_field(object, "tileType")
+            .and_then(TileType::from_i32)
+            .unwrap_or(TileType::Grass);
+        terrain.insert((q, r), tile_type);
+        if json_bool_field(object, "river") == Some(true) {
+            rivers.insert((q, r));
+        }
+    }
+    
+    let endpoints: Vec<(i32, i32)> = parse_valid_terrain_json(&endpoints_json).into_iter().collect();
+    let network = connect_endpoints(&endpoints, &terrain, &occupied, &rivers, &params);
+    let junctions: Vec<(i32, i32)> = network
+        .branches
+        .iter()
+        .filter(|&&(_, branches)| branches >= 3)
+        .map(|&(hex, _)| hex)
+        .collect();
+    
+    format!(
+        r#"{{"roads":{},"unconnected":{},"bridges":{},"junctions":{}}}"#,
+        coords_to_json(&network.roads),
+        coords_to_json(&network.unconnected),
+        coords_to_json(&network.bridges),
+        coords_to_json(&junctions)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hex_utils::{connected_components, generate_hex_grid, get_hex_neighbors};
+    
+    const RADIUS: i32 = 8;
+    
+    fn grass_field() -> HashMap<(i32, i32), TileType> {
+        generate_hex_grid(RADIUS, 0, 0).iter().map(|hex| ((hex.q, hex.r), TileType::Grass)).collect()
+    }
+    
+    /// Every seventh hex of the field, spread over all rings
+    fn scattered_endpoints() -> Vec<(i32, i32)> {
+        generate_hex_grid(RADIUS, 0, 0).iter().step_by(7).map(|hex| (hex.q, hex.r)).collect()
+    }
+    
+    fn params(max_branches: usize) -> RoadNetworkParams {
+        RoadNetworkParams { max_branches, ..RoadNetworkParams::default() }
+    }
+    
+    #[test]
+    fn every_endpoint_is_connected() {
+        let endpoints = scattered_endpoints();
+        let network = connect_endpoints(&endpoints, &grass_field(), &HashSet::new(), &HashSet::new(), &params(6));
+        
+        assert!(network.unconnected.is_empty());
+        let roads: HashSet<(i32, i32)> = network.roads.iter().copied().collect();
+        assert!(endpoints.iter().all(|endpoint| roads.contains(endpoint)));
+        assert_eq!(connected_components(&roads).len(), 1);
+    }
+    
+    #[test]
+    fn no_hex_exceeds_max_branches() {
+        let endpoints = scattered_endpoints();
+        for max_branches in 1..=6 {
+            let network = connect_endpoints(&endpoints, &grass_field(), &HashSet::new(), &HashSet::new(), &params(max_branches));
+            for &(hex, branches) in &network.branches {
+                assert!(branches <= max_branches, "{:?} has {} branches, limit {}", hex, branches, max_branches);
+            }
+            if max_branches >= 3 {
+                assert!(network.unconnected.is_empty(), "limit {}", max_branches);
+            }
+        }
+    }
+    
+    #[test]
+    fn occupied_hexes_are_never_paved() {
+        // A wall of occupied hexes across the field with one gap, plus an occupied endpoint
+        let mut occupied: HashSet<(i32, i32)> = (-RADIUS..=RADIUS).filter(|&q| q != 3).map(|q| (q, 0)).collect();
+        occupied.insert((-4, 4));
+        let endpoints = vec![(-4, 4), (0, -5), (2, 4), (-3, -2)];
+        let network = connect_endpoints(&endpoints, &grass_field(), &occupied, &HashSet::new(), &params(6));
+        
+        assert!(network.unconnected.is_empty());
+        assert!(network.roads.iter().all(|hex| !occupied.contains(hex)));
+        // The occupied endpoint is reached from a neighbor
+        assert!(get_hex_neighbors(-4, 4).iter().any(|hex| network.roads.contains(hex)));
+        assert!(network.roads.contains(&(3, 0)));
+    }
+}
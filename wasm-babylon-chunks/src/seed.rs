@@ -0,0 +1,65 @@
+/// Deterministic seeding module
+/// 
+/// **Learning Point**: All randomness in chunk generation flows from a single world seed.
+/// Each chunk derives its own seed by hashing the world seed with the chunk center, so a
+/// chunk regenerated after being unloaded comes out identical, independent of the order
+/// in which chunks were visited.
+
+/// Default world seed used until TypeScript sets one
+pub const DEFAULT_WORLD_SEED: u64 = 0x5EED_C0DE;
+
+/// SplitMix64 finalizer: scrambles a 64-bit value into a well distributed hash
+pub fn mix64(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Hash a seed together with a hex coordinate
+pub fn hash_coords(seed: u64, q: i32, r: i32) -> u64 {
+    let hash = mix64(seed ^ mix64(q as i64 as u64));
+    mix64(hash ^ (r as i64 as u64).rotate_left(32))
+}
+
+/// Derive the seed for a chunk from the world seed and the chunk center (q, r)
+pub fn chunk_seed(world_seed: u64, center_q: i32, center_r: i32) -> u64 {
+    hash_coords(world_seed, center_q, center_r)
+}
+
+/// Small, fast deterministic PRNG (SplitMix64)
+/// 
+/// **Learning Point**: Replaces the ad hoc LCGs seeded from array contents. The same seed
+/// always yields the same sequence on every platform, which is what makes chunk output
+/// byte-identical across regenerations.
+pub struct SeededRng {
+    state: u64,
+}
+
+impl SeededRng {
+    pub fn new(seed: u64) -> Self {
+        SeededRng { state: seed }
+    }
+    
+    /// Next raw 64-bit value
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        mix64(self.state)
+    }
+    
+    /// Uniform index in [0, bound); returns 0 when bound is 0
+    pub fn next_index(&mut self, bound: usize) -> usize {
+        if bound == 0 {
+            return 0;
+        }
+        (self.next_u64() % bound as u64) as usize
+    }
+    
+    /// Fisher-Yates shuffle in place
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.next_index(i + 1);
+            items.swap(i, j);
+        }
+    }
+}
@@ -0,0 +1,234 @@
+/// Line of sight module
+/// 
+/// **Learning Point**: Lines between hexes are drawn by linear interpolation in cube
+/// space followed by cube rounding. Field of view uses shadowcasting over rings: every
+/// hex on ring k covers an equal arc of 1 / (6k) of a full turn, so a blocking hex
+/// casts a shadow arc that hides the hexes behind it on all further rings. One pass
+/// over the rings replaces a line-of-sight test per hex.
+
+use wasm_bindgen::prelude::*;
+use crate::state::{WfcState, WFC_STATE};
+use crate::types::TileType;
+use crate::hex_utils::{axial_to_cube, cube_ring, cube_round, hex_distance, parse_tile_types_json};
+use crate::json::coords_to_json;
+
+/// Offset added to both line endpoints so lines running exactly along hex edges
+/// always round to the same side
+const LINE_NUDGE: (f64, f64) = (1e-6, 2e-6);
+
+/// Tolerance for shadow edges: arcs this close count as touching
+const ANGLE_EPSILON: f64 = 1e-9;
+
+/// Largest field of view radius (keeps a runaway radius from freezing the frame)
+const MAX_FOV_RADIUS: i32 = 64;
+
+/// Hexes on the line from a to b, both endpoints included
+pub fn hex_line_coords(a: (i32, i32), b: (i32, i32)) -> Vec<(i32, i32)> {
+    let steps = hex_distance(a.0, a.1, b.0, b.1);
+    if steps == 0 {
+        return vec![a];
+    }
+    
+    let (start_q, start_r) = (a.0 as f64 + LINE_NUDGE.0, a.1 as f64 + LINE_NUDGE.1);
+    let (end_q, end_r) = (b.0 as f64 + LINE_NUDGE.0, b.1 as f64 + LINE_NUDGE.1);
+    (0..=steps)
+        .map(|step| {
+            let t = step as f64 / steps as f64;
+            cube_round(start_q + (end_q - start_q) * t, start_r + (end_r - start_r) * t)
+        })
+        .collect()
+}
+
+/// Check that no hex strictly between a and b blocks sight
+/// Hexes that are not in the layout never block
+pub fn line_of_sight(state: &WfcState, a: (i32, i32), b: (i32, i32), blocking: &[TileType]) -> bool {
+    let line = hex_line_coords(a, b);
+    line.iter()
+        .skip(1)
+        .take(line.len().saturating_sub(2))
+        .all(|&(q, r)| !state.get_tile(q, r).is_some_and(|tile| blocking.contains(&tile)))
+}
+
+/// Shadow arcs, in fractions of a full turn (start may be below 0, end above 1)
+/// 
+/// **Learning Point**: Two blocking hexes side by side cast arcs that only touch. Kept
+/// separate, the seam between them lets a hex whose arc straddles it count as seen; merged
+/// into one arc, the wall is closed.
+struct Shadows {
+    /// Merged arcs sorted by start, each also stored shifted by -1 and +1 turn
+    arcs: Vec<(f64, f64)>,
+}
+
+impl Shadows {
+    /// Add arcs and merge every arc that overlaps or touches another
+    fn extend(&mut self, arcs: &[(f64, f64)]) {
+        for &(start, end) in arcs {
+            for shift in [-1.0, 0.0, 1.0] {
+                self.arcs.push((start + shift, end + shift));
+            }
+        }
+        self.arcs.sort_by(|a, b| a.0.total_cmp(&b.0));
+        
+        let mut merged: Vec<(f64, f64)> = Vec::with_capacity(self.arcs.len());
+        for &(start, end) in &self.arcs {
+            match merged.last_mut() {
+                Some(last) if start <= last.1 + ANGLE_EPSILON => last.1 = last.1.max(end),
+                _ => merged.push((start, end)),
+            }
+        }
+        self.arcs = merged;
+    }
+    
+    /// Whether the whole arc [start, end] lies inside one shadow
+    fn covers(&self, start: f64, end: f64) -> bool {
+        self.arcs
+            .iter()
+            .any(|&(shadow_start, shadow_end)| shadow_start <= start + ANGLE_EPSILON && end - ANGLE_EPSILON <= shadow_end)
+    }
+}
+
+/// Hexes visible from a center within a radius (center first, then ring order)
+/// 
+/// A hex whose whole arc is shadowed is hidden without further checks; every other hex
+/// is decided by line_of_sight, so field of view and line of sight always agree (ring
+/// arcs only approximate the hex geometry, so an unshadowed arc alone is not enough).
+/// Visible blocking hexes are included (walls are seen) and cast a shadow over their
+/// whole arc.
+pub fn compute_field_of_view(state: &WfcState, center: (i32, i32), radius: i32, blocking: &[TileType]) -> Vec<(i32, i32)> {
+    let mut visible = vec![center];
+    let mut shadows = Shadows { arcs: Vec::new() };
+    let center_cube = axial_to_cube(center.0, center.1);
+    
+    for ring in 1..=radius.clamp(0, MAX_FOV_RADIUS) {
+        let hexes = cube_ring(center_cube, ring);
+        let arc = 1.0 / hexes.len() as f64;
+        let mut new_arcs = Vec::new();
+        
+        for (index, hex) in hexes.iter().enumerate() {
+            let (start, end) = (index as f64 * arc - arc / 2.0, index as f64 * arc + arc / 2.0);
+            let hidden = shadows.covers(start, end) || !line_of_sight(state, center, (hex.q, hex.r), blocking);
+            if hidden {
+                continue;
+            }
+            visible.push((hex.q, hex.r));
+            if state.get_tile(hex.q, hex.r).is_some_and(|tile| blocking.contains(&tile)) {
+                new_arcs.push((start, end));
+            }
+        }
+        
+        // Hexes on the same ring never shadow each other
+        shadows.extend(&new_arcs);
+    }
+    
+    visible
+}
+
+/// Get the hexes on the line between two hexes
+/// 
+/// @param start_q - Start q coordinate (axial)
+/// @param start_r - Start r coordinate (axial)
+/// @param end_q - End q coordinate (axial)
+/// @param end_r - End r coordinate (axial)
+/// @returns JSON array from start to end (both included): [{"q":0,"r":0},...]
+#[wasm_bindgen]
+pub fn hex_line(start_q: i32, start_r: i32, end_q: i32, end_r: i32) -> String {
+    coords_to_json(&hex_line_coords((start_q, start_r), (end_q, end_r)))
+}
+
+/// Check line of sight between two hexes on the current layout
+/// 
+/// @param start_q - Start q coordinate (axial)
+/// @param start_r - Start r coordinate (axial)
+/// @param end_q - End q coordinate (axial)
+/// @param end_r - End r coordinate (axial)
+/// @param blocking_types_json - JSON array of tile types that block sight: [1,3] (TileType values)
+/// @returns true if no hex between the endpoints blocks sight (the endpoints themselves may)
+#[wasm_bindgen]
+pub fn has_line_of_sight(start_q: i32, start_r: i32, end_q: i32, end_r: i32, blocking_types_json: String) -> bool {
+    let blocking = parse_tile_types_json(&blocking_types_json);
+    let state = WFC_STATE.lock().unwrap();
+    line_of_sight(&state, (start_q, start_r), (end_q, end_r), &blocking)
+}
+
+/// Get the hexes visible from a hex (fog of war, watchtower coverage)
+/// 
+/// @param center_q - Viewer q coordinate (axial)
+/// @param center_r - Viewer r coordinate (axial)
+/// @param radius - Sight radius in hexes (capped at 64)
+/// @param blocking_types_json - JSON array of tile types that block sight: [1,3] (TileType values)
+/// @returns JSON array of visible hexes, viewer first: [{"q":0,"r":0},...]
+#[wasm_bindgen]
+pub fn field_of_view(center_q: i32, center_r: i32, radius: i32, blocking_types_json: String) -> String {
+    let blocking = parse_tile_types_json(&blocking_types_json);
+    let state = WFC_STATE.lock().unwrap();
+    coords_to_json(&compute_field_of_view(&state, (center_q, center_r), radius, &blocking))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hex_utils::generate_hex_grid;
+    use crate::seed::SeededRng;
+    
+    const RADIUS: i32 = 6;
+    
+    fn state_with_forest(forest: &[(i32, i32)]) -> WfcState {
+        let mut state = WfcState::new();
+        for hex in generate_hex_grid(RADIUS + 1, 0, 0) {
+            state.insert_tile(hex.q, hex.r, TileType::Grass);
+        }
+        for &(q, r) in forest {
+            state.insert_tile(q, r, TileType::Forest);
+        }
+        state
+    }
+    
+    /// Hexes where field of view and line of sight disagree
+    fn disagreements(state: &WfcState) -> Vec<(i32, i32)> {
+        let blocking = [TileType::Forest];
+        let visible = compute_field_of_view(state, (0, 0), RADIUS, &blocking);
+        generate_hex_grid(RADIUS, 0, 0)
+            .iter()
+            .map(|hex| (hex.q, hex.r))
+            .filter(|&hex| visible.contains(&hex) != line_of_sight(state, (0, 0), hex, &blocking))
+            .collect()
+    }
+    
+    #[test]
+    fn enclosed_viewer_sees_only_its_ring() {
+        let ring: Vec<(i32, i32)> = cube_ring(axial_to_cube(0, 0), 1).iter().map(|hex| (hex.q, hex.r)).collect();
+        let state = state_with_forest(&ring);
+        
+        let mut visible = compute_field_of_view(&state, (0, 0), RADIUS, &[TileType::Forest]);
+        visible.sort();
+        let mut expected = ring.clone();
+        expected.push((0, 0));
+        expected.sort();
+        assert_eq!(visible, expected);
+        assert!(disagreements(&state).is_empty());
+    }
+    
+    #[test]
+    fn two_hex_wall_has_no_seam() {
+        let state = state_with_forest(&[(0, 2), (1, 1)]);
+        let visible = compute_field_of_view(&state, (0, 0), RADIUS, &[TileType::Forest]);
+        // Straight behind the seam between the two wall hexes
+        assert!(!visible.contains(&(1, 3)));
+        assert!(!visible.contains(&(2, 4)));
+        assert!(disagreements(&state).is_empty());
+    }
+    
+    #[test]
+    fn scattered_forest_agrees_with_line_of_sight() {
+        for seed in 0..20 {
+            let mut rng = SeededRng::new(seed);
+            let forest: Vec<(i32, i32)> = generate_hex_grid(RADIUS, 0, 0)
+                .iter()
+                .skip(1)
+                .filter(|_| rng.next_index(5) == 0)
+                .map(|hex| (hex.q, hex.r))
+                .collect();
+            assert!(disagreements(&state_with_forest(&forest)).is_empty(), "seed {}", seed);
+        }
+    }
+}
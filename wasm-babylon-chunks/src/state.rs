@@ -1,16 +1,55 @@
 /// WFC state management module
 
 use std::sync::{LazyLock, Mutex};
-use std::collections::HashMap;
-use crate::types::TileType;
+use std::collections::{HashMap, HashSet};
+use crate::types::{TileType, Zone};
+use crate::seed::DEFAULT_WORLD_SEED;
+use crate::hex_utils::hex_distance;
+use crate::edit::{EditHistory, EditOp, HexEdit};
+use crate::buildings::BuildingRecord;
+
+/// A generated chunk tracked by the state
+/// 
+/// **Learning Point**: Tiles themselves live in the flat grid (so tile queries stay O(1));
+/// the record only remembers which coordinates the chunk owns, in ring order, and the
+/// buildings placed on them (footprint, facing and size cannot be recovered from tiles).
+pub struct ChunkRecord {
+    pub rings: i32,
+    pub tiles: Vec<(i32, i32)>,
+    /// Buildings in placement order (the index is the building id)
+    pub buildings: Vec<BuildingRecord>,
+    /// Hexes that were water before roads and buildings were laid (bridges and buildings
+    /// hide it in the grid)
+    pub water: HashSet<(i32, i32)>,
+}
+
+impl ChunkRecord {
+    /// Check whether a hex lies inside this chunk's hexagon
+    pub fn contains(&self, center: (i32, i32), q: i32, r: i32) -> bool {
+        hex_distance(center.0, center.1, q, r) <= self.rings
+    }
+}
+
+/// Approximate memory usage of the state
+pub struct MemoryStats {
+    pub chunks: usize,
+    pub tiles: usize,
+    pub pre_constraints: usize,
+    pub estimated_bytes: usize,
+}
 
 /// State structure using hash map for efficient sparse grid storage
 /// 
 /// **Learning Point**: Uses HashMap<(i32, i32), TileType> for O(1) lookups and
 /// no size limitations. Keys are (q, r) hex coordinates.
+/// Generated chunks are tracked by center so they can be evicted independently.
 pub struct WfcState {
     grid: HashMap<(i32, i32), TileType>,
     pre_constraints: HashMap<(i32, i32), TileType>,
+    world_seed: u64,
+    chunks: HashMap<(i32, i32), ChunkRecord>,
+    zones: HashMap<(i32, i32), Zone>,
+    history: EditHistory,
 }
 
 impl WfcState {
@@ -18,12 +57,32 @@ impl WfcState {
         WfcState {
             grid: HashMap::new(),
             pre_constraints: HashMap::new(),
+            world_seed: DEFAULT_WORLD_SEED,
+            chunks: HashMap::new(),
+            zones: HashMap::new(),
+            history: EditHistory::new(),
         }
     }
     
     pub fn clear(&mut self) {
         self.grid.clear();
+        // Chunk records and zones only annotate the grid, so they go with it
+        self.chunks.clear();
+        self.zones.clear();
+        // Recorded edits refer to the cleared tiles
+        self.history.clear();
         // DO NOT clear pre_constraints - they must persist
+        // The world seed also persists so cleared chunks regenerate identically
+    }
+    
+    /// Get the world seed all chunk seeds are derived from
+    pub fn world_seed(&self) -> u64 {
+        self.world_seed
+    }
+    
+    /// Set the world seed all chunk seeds are derived from
+    pub fn set_world_seed(&mut self, world_seed: u64) {
+        self.world_seed = world_seed;
     }
     
     /// Set a pre-constraint at a specific hex position (q, r)
@@ -33,11 +92,22 @@ impl WfcState {
         true
     }
     
+    /// Remove the pre-constraint at a hex position
+    /// Returns true if there was one
+    pub fn remove_pre_constraint(&mut self, q: i32, r: i32) -> bool {
+        self.pre_constraints.remove(&(q, r)).is_some()
+    }
+    
     /// Clear all pre-constraints
     pub fn clear_pre_constraints(&mut self) {
         self.pre_constraints.clear();
     }
     
+    /// Get the pre-constraint at a hex position
+    pub fn get_pre_constraint(&self, q: i32, r: i32) -> Option<TileType> {
+        self.pre_constraints.get(&(q, r)).copied()
+    }
+    
     /// Get tile at hex coordinate (q, r)
     pub fn get_tile(&self, q: i32, r: i32) -> Option<TileType> {
         self.grid.get(&(q, r)).copied()
@@ -53,12 +123,316 @@ impl WfcState {
         self.grid.insert((q, r), tile_type);
     }
     
+    /// Remove a tile from the grid, returning the removed tile type
+    pub fn remove_tile(&mut self, q: i32, r: i32) -> Option<TileType> {
+        self.grid.remove(&(q, r))
+    }
+    
+    /// Get the zone of a hex (None if the hex was never zoned)
+    pub fn get_zone(&self, q: i32, r: i32) -> Option<Zone> {
+        self.zones.get(&(q, r)).copied()
+    }
+    
+    /// Set the zone of a hex; unzoned hexes are not stored
+    pub fn set_zone(&mut self, q: i32, r: i32, zone: Zone) {
+        if zone == Zone::Unzoned {
+            self.zones.remove(&(q, r));
+        } else {
+            self.zones.insert((q, r), zone);
+        }
+    }
+    
+    /// Get grid entries iterator
+    pub fn tiles(&self) -> impl Iterator<Item = ((i32, i32), TileType)> + '_ {
+        self.grid.iter().map(|((q, r), tile_type)| ((*q, *r), *tile_type))
+    }
+    
     /// Get grid values iterator
     pub fn grid_values(&self) -> impl Iterator<Item = TileType> + '_ {
         self.grid.values().copied()
     }
+    
+    /// Store a generated chunk: tiles go into the grid, coordinates into the chunk record
+    /// Replaces any chunk previously stored at the same center; recorded edits inside
+    /// the chunk are dropped, since undoing them would overwrite the new tiles
+    pub fn insert_chunk(
+        &mut self,
+        center: (i32, i32),
+        rings: i32,
+        tiles: Vec<((i32, i32), TileType)>,
+        buildings: Vec<BuildingRecord>,
+        water: HashSet<(i32, i32)>,
+    ) {
+        self.evict_chunk(center);
+        self.history.forget_touching(|(q, r)| hex_distance(center.0, center.1, q, r) <= rings);
+        let mut coords = Vec::with_capacity(tiles.len());
+        for (hex, tile_type) in tiles {
+            self.grid.insert(hex, tile_type);
+            coords.push(hex);
+        }
+        self.chunks.insert(center, ChunkRecord { rings, tiles: coords, buildings, water });
+    }
+    
+    /// Remove a chunk and its tiles (and their zones) from the grid
+    /// Tiles also covered by another loaded chunk are kept; recorded edits inside the
+    /// chunk are dropped
+    /// Returns true if the chunk was loaded
+    pub fn evict_chunk(&mut self, center: (i32, i32)) -> bool {
+        let Some(record) = self.chunks.remove(&center) else {
+            return false;
+        };
+        self.history.forget_touching(|(q, r)| record.contains(center, q, r));
+        
+        for (q, r) in record.tiles {
+            let shared = self.chunks.iter().any(|(&other_center, other)| other.contains(other_center, q, r));
+            if !shared {
+                self.grid.remove(&(q, r));
+                self.zones.remove(&(q, r));
+            }
+        }
+        true
+    }
+    
+    /// Get a loaded chunk record by center
+    pub fn chunk(&self, center: (i32, i32)) -> Option<&ChunkRecord> {
+        self.chunks.get(&center)
+    }
+    
+    /// Get the centers of all loaded chunks
+    pub fn chunk_centers(&self) -> impl Iterator<Item = (i32, i32)> + '_ {
+        self.chunks.keys().copied()
+    }
+    
+    /// Write a set of hex edits to the grid and pre-constraints without recording them
+    /// `forward` writes the edited values, otherwise the values before the edit
+    /// 
+    /// Written tiles become the terrain of the chunks that own them: water joins the
+    /// chunk's underlying water, a road keeps it (a bridge stays over its river) and any
+    /// other tile or an erased hex clears it. Building records whose footprint lost a
+    /// building tile are dropped and returned.
+    pub fn write_hex_edits(&mut self, edits: &[HexEdit], forward: bool) -> Vec<BuildingRecord> {
+        for edit in edits {
+            let (q, r) = edit.hex;
+            let (tile, constraint) = if forward {
+                (edit.tile_after, edit.constraint_after)
+            } else {
+                (edit.tile_before, edit.constraint_before)
+            };
+            for (&center, record) in self.chunks.iter_mut() {
+                if !record.contains(center, q, r) {
+                    continue;
+                }
+                match tile {
+                    Some(TileType::Water) => {
+                        record.water.insert((q, r));
+                    },
+                    Some(TileType::Road) => {},
+                    _ => {
+                        record.water.remove(&(q, r));
+                    },
+                }
+            }
+            match tile {
+                Some(tile_type) => self.insert_tile(q, r, tile_type),
+                None => {
+                    self.remove_tile(q, r);
+                },
+            }
+            match constraint {
+                Some(tile_type) => {
+                    self.set_pre_constraint(q, r, tile_type);
+                },
+                None => {
+                    self.remove_pre_constraint(q, r);
+                },
+            }
+        }
+        
+        let edited: HashSet<(i32, i32)> = edits.iter().map(|edit| edit.hex).collect();
+        let mut dropped = Vec::new();
+        for record in self.chunks.values_mut() {
+            let (stale, kept): (Vec<BuildingRecord>, Vec<BuildingRecord>) = record.buildings.drain(..).partition(|building| {
+                building
+                    .hexes
+                    .iter()
+                    .any(|hex| edited.contains(hex) && self.grid.get(hex) != Some(&TileType::Building))
+            });
+            record.buildings = kept;
+            dropped.extend(stale);
+        }
+        dropped
+    }
+    
+    /// Add hexes to the underlying water of the chunks that own them (bridges laid
+    /// over water that the grid now shows as road)
+    pub fn mark_water(&mut self, hexes: &[(i32, i32)]) {
+        for &(q, r) in hexes {
+            for (&center, record) in self.chunks.iter_mut() {
+                if record.contains(center, q, r) {
+                    record.water.insert((q, r));
+                }
+            }
+        }
+    }
+    
+    /// Record buildings with the chunks that own their anchors
+    /// Buildings outside every chunk and records already present are skipped
+    pub fn add_building_records(&mut self, buildings: &[BuildingRecord]) {
+        for building in buildings {
+            let (q, r) = building.anchor;
+            let owner = self
+                .chunks
+                .iter()
+                .filter(|(&center, record)| record.contains(center, q, r))
+                .map(|(&center, _)| center)
+                .min();
+            if let Some(record) = owner.and_then(|center| self.chunks.get_mut(&center)) {
+                if !record.buildings.contains(building) {
+                    record.buildings.push(building.clone());
+                }
+            }
+        }
+    }
+    
+    /// Forget building records (the tiles stay as they are)
+    pub fn remove_building_records(&mut self, buildings: &[BuildingRecord]) {
+        for record in self.chunks.values_mut() {
+            record.buildings.retain(|building| !buildings.contains(building));
+        }
+    }
+    
+    /// Record an already written edit operation; clears the redo stack
+    pub fn record_edit(&mut self, op: EditOp) {
+        self.history.push(op);
+    }
+    
+    /// Undo the most recent edit operation
+    /// Returns the hexes it touched, or None if there is nothing to undo
+    pub fn undo_edit(&mut self) -> Option<Vec<(i32, i32)>> {
+        let op = self.history.pop_undo()?;
+        let mut edits = op.edits.clone();
+        // Restore in reverse order so repeated hexes end at their oldest value
+        edits.reverse();
+        self.write_hex_edits(&edits, false);
+        self.remove_building_records(&op.placed);
+        self.add_building_records(&op.dropped);
+        let hexes = op.hexes();
+        self.history.push_redo(op);
+        Some(hexes)
+    }
+    
+    /// Redo the most recently undone edit operation
+    /// Returns the hexes it touched, or None if there is nothing to redo
+    pub fn redo_edit(&mut self) -> Option<Vec<(i32, i32)>> {
+        let op = self.history.pop_redo()?;
+        self.write_hex_edits(&op.edits, true);
+        self.remove_building_records(&op.dropped);
+        self.add_building_records(&op.placed);
+        let hexes = op.hexes();
+        self.history.push_undone(op);
+        Some(hexes)
+    }
+    
+    /// Get the edit history
+    pub fn edit_history(&self) -> &EditHistory {
+        &self.history
+    }
+    
+    /// Forget all recorded edits (the tiles stay as they are)
+    pub fn clear_edit_history(&mut self) {
+        self.history.clear();
+    }
+    
+    /// Estimate memory used by the grid, zones, pre-constraints and chunk records
+    pub fn memory_stats(&self) -> MemoryStats {
+        let entry_size = std::mem::size_of::<((i32, i32), TileType)>();
+        let coord_size = std::mem::size_of::<(i32, i32)>();
+        let record_size = std::mem::size_of::<((i32, i32), ChunkRecord)>();
+        
+        // Hash maps store one control byte per bucket in addition to the entry
+        let grid_bytes = self.grid.capacity() * (entry_size + 1);
+        let zone_bytes = self.zones.capacity() * (std::mem::size_of::<((i32, i32), Zone)>() + 1);
+        let pre_constraint_bytes = self.pre_constraints.capacity() * (entry_size + 1);
+        let chunk_bytes: usize = self.chunks.capacity() * (record_size + 1)
+            + self.chunks.values().map(|record| record.tiles.capacity() * coord_size).sum::<usize>();
+        
+        MemoryStats {
+            chunks: self.chunks.len(),
+            tiles: self.grid.len(),
+            pre_constraints: self.pre_constraints.len(),
+            estimated_bytes: grid_bytes + zone_bytes + pre_constraint_bytes + chunk_bytes,
+        }
+    }
 }
 
 /// Global WFC state (thread-safe)
 pub static WFC_STATE: LazyLock<Mutex<WfcState>> = LazyLock::new(|| Mutex::new(WfcState::new()));
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::edit::{run_edit, EditOptions};
+    use crate::generation::{generate_chunk_tiles, ChunkGenerationParams};
+    use crate::hex_utils::generate_hex_grid;
+    
+    fn grass_chunk(center: (i32, i32), rings: i32) -> Vec<((i32, i32), TileType)> {
+        generate_hex_grid(rings, center.0, center.1)
+            .iter()
+            .map(|hex| ((hex.q, hex.r), TileType::Grass))
+            .collect()
+    }
+    
+    #[test]
+    fn replacing_or_evicting_a_chunk_drops_its_edits() {
+        let mut state = WfcState::new();
+        state.insert_chunk((0, 0), 2, grass_chunk((0, 0), 2), Vec::new(), HashSet::new());
+        state.insert_tile(10, 0, TileType::Grass);
+        let options = EditOptions::from_json("{}");
+        run_edit(&mut state, "inside", &[((1, 0), Some(TileType::Road))], Vec::new(), &options);
+        run_edit(&mut state, "outside", &[((10, 0), Some(TileType::Forest))], Vec::new(), &options);
+        
+        state.insert_chunk((0, 0), 2, grass_chunk((0, 0), 2), Vec::new(), HashSet::new());
+        assert_eq!(state.edit_history().undo_labels().collect::<Vec<_>>(), vec!["outside"]);
+        
+        run_edit(&mut state, "inside", &[((0, 1), Some(TileType::Water))], Vec::new(), &options);
+        state.evict_chunk((0, 0));
+        assert_eq!(state.edit_history().undo_labels().collect::<Vec<_>>(), vec!["outside"]);
+        assert_eq!(state.undo_edit(), Some(vec![(10, 0)]));
+        assert_eq!(state.get_tile(10, 0), Some(TileType::Grass));
+    }
+    
+    #[test]
+    fn building_records_follow_edits() {
+        let params = ChunkGenerationParams::from_json("{}");
+        let chunk = generate_chunk_tiles(7, 0, 0, 5, &params, &HashMap::new());
+        let mut state = WfcState::new();
+        state.insert_chunk((0, 0), 5, chunk.tiles, chunk.buildings.clone(), HashSet::new());
+        let records_are_built = |state: &WfcState| {
+            state.chunk((0, 0)).unwrap().buildings.iter().all(|building| {
+                building.hexes.iter().all(|&(q, r)| state.get_tile(q, r) == Some(TileType::Building))
+            })
+        };
+        
+        // Erasing an anchor drops its record; undo and redo bring it back and drop it again
+        let erased = chunk.buildings[0].clone();
+        let options = EditOptions::from_json("{}");
+        run_edit(&mut state, "erase", &[(erased.anchor, None)], Vec::new(), &options);
+        assert!(!state.chunk((0, 0)).unwrap().buildings.contains(&erased));
+        state.undo_edit();
+        assert!(state.chunk((0, 0)).unwrap().buildings.contains(&erased));
+        state.redo_edit();
+        assert!(!state.chunk((0, 0)).unwrap().buildings.contains(&erased));
+        assert!(records_are_built(&state));
+        
+        // Buildings placed by a regenerated window are recorded with the chunk
+        let options = EditOptions::from_json(r#"{"regenerateRadius":4,"roadDensity":0.3,"buildingCount":4}"#);
+        let op = run_edit(&mut state, "regenerate", &[(erased.anchor, Some(TileType::Grass))], Vec::new(), &options).unwrap();
+        assert!(!op.placed.is_empty());
+        assert!(op.placed.iter().all(|building| state.chunk((0, 0)).unwrap().buildings.contains(building)));
+        assert!(records_are_built(&state));
+        state.undo_edit();
+        assert!(op.placed.iter().all(|building| !state.chunk((0, 0)).unwrap().buildings.contains(building)));
+        assert!(records_are_built(&state));
+    }
+}
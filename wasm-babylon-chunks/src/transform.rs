@@ -0,0 +1,259 @@
+/// Layout transform module
+/// 
+/// **Learning Point**: Rotations and reflections are linear maps in cube coordinates
+/// (a 60 degree turn is (q, r, s) -> (-s, -q, -r), a mirror swaps two components), so a
+/// layout is transformed hex by hex around a pivot and then shifted. Directions stored
+/// with the layout, like the road a building faces, go through the same map without
+/// the shift. One authored template can this way be stamped in all six orientations
+/// and both handednesses.
+
+use wasm_bindgen::prelude::*;
+use crate::types::{HexAxis, TileType};
+use crate::hex_utils::{hex_rotate, hex_reflect, CUBE_DIRECTIONS};
+use crate::buildings::{BuildingRecord, BuildingSize};
+use crate::edit::edit_and_report;
+use crate::json::{json_objects, json_array_items, json_array_field, json_int_field, json_string_field};
+
+/// Reflect, rotate around a pivot, then translate
+/// 
+/// Parsed from JSON: {"rotation":0,"reflect":"q"|"r"|"s","centerQ":0,"centerR":0,"dq":0,"dr":0}
+/// "rotation" counts 60 degree steps (negative turns back), "reflect" is optional and
+/// applied before the rotation, and (centerQ, centerR) is the pivot of both.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LayoutTransform {
+    pub rotation: i32,
+    pub reflect: Option<HexAxis>,
+    pub center: (i32, i32),
+    pub offset: (i32, i32),
+}
+
+impl LayoutTransform {
+    pub fn from_json(transform_json: &str) -> Self {
+        let field = |key: &str| json_int_field(transform_json, key).unwrap_or(0);
+        LayoutTransform {
+            rotation: field("rotation").rem_euclid(6),
+            reflect: json_string_field(transform_json, "reflect").and_then(|value| HexAxis::from_str(&value)),
+            center: (field("centerQ"), field("centerR")),
+            offset: (field("dq"), field("dr")),
+        }
+    }
+    
+    /// Reflection and rotation around the origin (no pivot, no shift)
+    fn turn(&self, q: i32, r: i32) -> (i32, i32) {
+        let (q, r) = match self.reflect {
+            Some(axis) => hex_reflect(q, r, axis),
+            None => (q, r),
+        };
+        hex_rotate(q, r, self.rotation)
+    }
+    
+    /// Transform a hex
+    pub fn apply(&self, (q, r): (i32, i32)) -> (i32, i32) {
+        let (center_q, center_r) = self.center;
+        let (turned_q, turned_r) = self.turn(q - center_q, r - center_r);
+        (center_q + turned_q + self.offset.0, center_r + turned_r + self.offset.1)
+    }
+    
+    /// Transform a direction index (into CUBE_DIRECTIONS)
+    pub fn apply_direction(&self, direction: usize) -> usize {
+        let step = CUBE_DIRECTIONS[direction % 6];
+        let turned = self.turn(step.q, step.r);
+        CUBE_DIRECTIONS
+            .iter()
+            .position(|candidate| (candidate.q, candidate.r) == turned)
+            .unwrap_or(direction)
+    }
+}
+
+/// Transform a tile list: [{"q":0,"r":0,"tileType":2},...]; invalid tile types are skipped
+fn transform_tiles(tiles_json: &str, transform: &LayoutTransform) -> Vec<((i32, i32), TileType)> {
+    json_objects(tiles_json)
+        .filter_map(|object| {
+            let q = json_int_field(object, "q")?;
+            let r = json_int_field(object, "r")?;
+            let tile_type = json_int_field(object, "tileType").and_then(TileType::from_i32)?;
+            Some((transform.apply((q, r)), tile_type))
+        })
+        .collect()
+}
+
+/// Transform a single hex
+/// 
+/// @param q - Hex q coordinate (axial)
+/// @param r - Hex r coordinate (axial)
+/// @param transform_json - JSON object (see LayoutTransform::from_json)
+/// @returns Int32Array [q, r]
+#[wasm_bindgen]
+pub fn transform_hex(q: i32, r: i32, transform_json: String) -> Vec<i32> {
+    let (q, r) = LayoutTransform::from_json(&transform_json).apply((q, r));
+    vec![q, r]
+}
+
+/// Rotate, reflect and translate a tile layout
+/// 
+/// Roads and buildings are tiles too, so this moves a whole chunk layout
+/// (e.g. from get_chunk_tiles) without touching the grid.
+/// 
+/// @param tiles_json - JSON array: [{"q":0,"r":0,"tileType":2},...] (invalid tile types are skipped)
+/// @param transform_json - JSON object (see LayoutTransform::from_json)
+/// @returns JSON array in input order: [{"q":0,"r":0,"tileType":2},...]
+#[wasm_bindgen]
+pub fn transform_layout(tiles_json: String, transform_json: String) -> String {
+    let transform = LayoutTransform::from_json(&transform_json);
+    let json_parts: Vec<String> = transform_tiles(&tiles_json, &transform)
+        .iter()
+        .map(|((q, r), tile_type)| format!(r#"{{"q":{},"r":{},"tileType":{}}}"#, q, r, *tile_type as i32))
+        .collect();
+    
+    format!("[{}]", json_parts.join(","))
+}
+
+/// Rotate, reflect and translate building records
+/// 
+/// **Learning Point**: A footprint is just a set of hexes, but "facing" is a direction;
+/// rotating it by the same number of steps (and mirroring it with the layout) keeps each
+/// building fronting the road it faced before the transform.
+/// 
+/// @param buildings_json - JSON array as returned by generate_buildings:
+///                         [{"id":0,"q":0,"r":0,"facing":0,"size":"medium","hexes":[{"q":0,"r":0},...]},...]
+/// @param transform_json - JSON object (see LayoutTransform::from_json)
+/// @returns JSON array of buildings in the same format
+#[wasm_bindgen]
+pub fn transform_buildings(buildings_json: String, transform_json: String) -> String {
+    let transform = LayoutTransform::from_json(&transform_json);
+    let mut json_parts = Vec::new();
+    for (index, item) in json_array_items(&buildings_json).enumerate() {
+        // Read scalar fields without the nested hexes, whose "q"/"r" keys would shadow them
+        let hexes_json = json_array_field(item, "hexes").unwrap_or("[]");
+        let fields = item.replacen(hexes_json, "", 1);
+        let (Some(q), Some(r)) = (json_int_field(&fields, "q"), json_int_field(&fields, "r")) else {
+            continue;
+        };
+        
+        // Footprint order is kept, so the anchor stays first
+        let hexes: Vec<(i32, i32)> = json_objects(hexes_json)
+            .filter_map(|hex| Some(transform.apply((json_int_field(hex, "q")?, json_int_field(hex, "r")?))))
+            .collect();
+        
+        let record = BuildingRecord {
+            anchor: transform.apply((q, r)),
+            hexes,
+            facing: transform.apply_direction(json_int_field(&fields, "facing").unwrap_or(0).rem_euclid(6) as usize),
+            size: json_string_field(&fields, "size")
+                .and_then(|value| BuildingSize::from_str(&value))
+                .unwrap_or(BuildingSize::Small),
+        };
+        let id = json_int_field(&fields, "id").map_or(index, |id| id.max(0) as usize);
+        json_parts.push(record.to_json(id));
+    }
+    
+    format!("[{}]", json_parts.join(","))
+}
+
+/// Stamp a transformed tile layout into the grid as one undoable edit
+/// 
+/// @param tiles_json - JSON array: [{"q":0,"r":0,"tileType":2},...] (the template)
+/// @param transform_json - JSON object (see LayoutTransform::from_json)
+/// @param options_json - JSON object (see EditOptions::from_json), "{}" for defaults
+/// @returns JSON string (see edit_set_tiles)
+#[wasm_bindgen]
+pub fn stamp_layout(tiles_json: String, transform_json: String, options_json: String) -> String {
+    let transform = LayoutTransform::from_json(&transform_json);
+    edit_and_report("stamp_layout", &options_json, |_| {
+        transform_tiles(&tiles_json, &transform)
+            .into_iter()
+            .map(|(hex, tile_type)| (hex, Some(tile_type)))
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use crate::hex_utils::generate_hex_grid;
+    use crate::generation::{generate_chunk_tiles, ChunkGenerationParams};
+    
+    const AXES: [HexAxis; 3] = [HexAxis::Q, HexAxis::R, HexAxis::S];
+    
+    fn transform(rotation: i32, reflect: Option<HexAxis>) -> LayoutTransform {
+        LayoutTransform { rotation, reflect, center: (2, -1), offset: (0, 0) }
+    }
+    
+    /// Anchor and facing of each building in a transform_buildings result
+    fn anchors_and_facings(buildings_json: &str) -> Vec<((i32, i32), usize)> {
+        json_array_items(buildings_json)
+            .map(|item| {
+                let q = json_int_field(item, "q").expect("anchor q");
+                let r = json_int_field(item, "r").expect("anchor r");
+                ((q, r), json_int_field(item, "facing").expect("facing") as usize)
+            })
+            .collect()
+    }
+    
+    #[test]
+    fn six_rotations_and_two_reflections_are_identities() {
+        let hexes: Vec<(i32, i32)> = generate_hex_grid(4, 0, 0).iter().map(|hex| (hex.q, hex.r)).collect();
+        let turn = transform(1, None);
+        for &hex in &hexes {
+            assert_eq!((0..6).fold(hex, |hex, _| turn.apply(hex)), hex);
+            for axis in AXES {
+                let mirror = transform(0, Some(axis));
+                assert_eq!(mirror.apply(mirror.apply(hex)), hex, "{:?} across {:?}", hex, axis);
+            }
+        }
+        for direction in 0..6 {
+            assert_eq!((0..6).fold(direction, |direction, _| turn.apply_direction(direction)), direction);
+            for axis in AXES {
+                let mirror = transform(0, Some(axis));
+                assert_eq!(mirror.apply_direction(mirror.apply_direction(direction)), direction);
+            }
+        }
+        
+        // Whole building records round-trip through the JSON export too
+        let buildings = r#"[{"id":3,"q":1,"r":0,"facing":2,"size":"medium","hexes":[{"q":1,"r":0},{"q":2,"r":0}]}]"#;
+        let turn_json = r#"{"rotation":1,"centerQ":2,"centerR":-1}"#;
+        let turned = (0..6).fold(buildings.to_string(), |json, _| transform_buildings(json, turn_json.to_string()));
+        assert_eq!(turned, buildings);
+        let mirror_json = r#"{"reflect":"s","centerQ":2,"centerR":-1}"#;
+        let mirrored = transform_buildings(transform_buildings(buildings.to_string(), mirror_json.to_string()), mirror_json.to_string());
+        assert_eq!(mirrored, buildings);
+    }
+    
+    #[test]
+    fn transformed_buildings_still_face_their_road() {
+        let chunk = generate_chunk_tiles(7, 0, 0, 4, &ChunkGenerationParams::from_json("{}"), &HashMap::new());
+        assert!(!chunk.buildings.is_empty(), "the chunk should hold buildings");
+        let buildings_json = format!(
+            "[{}]",
+            chunk.buildings.iter().enumerate().map(|(id, building)| building.to_json(id)).collect::<Vec<_>>().join(",")
+        );
+        let roads: Vec<(i32, i32)> = chunk
+            .buildings
+            .iter()
+            .map(|building| {
+                let step = CUBE_DIRECTIONS[building.facing];
+                let road = (building.anchor.0 + step.q, building.anchor.1 + step.r);
+                assert!(chunk.tiles.contains(&(road, TileType::Road)), "building at {:?} fronts {:?}", building.anchor, road);
+                road
+            })
+            .collect();
+        
+        for reflect in [None, Some(HexAxis::Q)] {
+            for rotation in 0..6 {
+                let layout = LayoutTransform { rotation, reflect, center: (1, 1), offset: (5, -3) };
+                let transform_json = format!(
+                    r#"{{"rotation":{},{}"centerQ":1,"centerR":1,"dq":5,"dr":-3}}"#,
+                    rotation,
+                    if reflect.is_some() { r#""reflect":"q","# } else { "" }
+                );
+                let transformed = anchors_and_facings(&transform_buildings(buildings_json.clone(), transform_json));
+                assert_eq!(transformed.len(), roads.len());
+                for (&((q, r), facing), &road) in transformed.iter().zip(&roads) {
+                    let step = CUBE_DIRECTIONS[facing];
+                    assert_eq!((q + step.q, r + step.r), layout.apply(road), "rotation {} reflect {:?}", rotation, reflect);
+                }
+            }
+        }
+    }
+}
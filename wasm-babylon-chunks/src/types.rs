@@ -14,6 +14,82 @@ pub enum TileType {
     Water = 4,
 }
 
+impl TileType {
+    /// Convert an i32 (as passed from TypeScript) into a TileType
+    /// Returns None for values outside 0-4
+    pub fn from_i32(value: i32) -> Option<TileType> {
+        match value {
+            0 => Some(TileType::Grass),
+            1 => Some(TileType::Building),
+            2 => Some(TileType::Road),
+            3 => Some(TileType::Forest),
+            4 => Some(TileType::Water),
+            _ => None,
+        }
+    }
+    
+    /// Convert a tile type name (as used by TileType['type'] in TypeScript) into a TileType
+    pub fn from_name(name: &str) -> Option<TileType> {
+        match name {
+            "grass" => Some(TileType::Grass),
+            "building" => Some(TileType::Building),
+            "road" => Some(TileType::Road),
+            "forest" => Some(TileType::Forest),
+            "water" => Some(TileType::Water),
+            _ => None,
+        }
+    }
+    
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TileType::Grass => "grass",
+            TileType::Building => "building",
+            TileType::Road => "road",
+            TileType::Forest => "forest",
+            TileType::Water => "water",
+        }
+    }
+}
+
+/// District zone enumeration for the zoning layer
+/// 
+/// **Learning Point**: Zones give land a purpose on top of its tile type. Unzoned
+/// covers water and wilderness; every other zone scales how densely it is built up.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(i32)]
+pub enum Zone {
+    Unzoned = 0,
+    Residential = 1,
+    Market = 2,
+    Farmland = 3,
+    Park = 4,
+}
+
+impl Zone {
+    /// Convert an i32 (as passed from TypeScript) into a Zone
+    /// Returns None for values outside 0-4
+    pub fn from_i32(value: i32) -> Option<Zone> {
+        match value {
+            0 => Some(Zone::Unzoned),
+            1 => Some(Zone::Residential),
+            2 => Some(Zone::Market),
+            3 => Some(Zone::Farmland),
+            4 => Some(Zone::Park),
+            _ => None,
+        }
+    }
+    
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Zone::Unzoned => "unzoned",
+            Zone::Residential => "residential",
+            Zone::Market => "market",
+            Zone::Farmland => "farmland",
+            Zone::Park => "park",
+        }
+    }
+}
+
 /// Hex coordinate structure for Voronoi generation
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct HexCoord {
@@ -29,6 +105,28 @@ pub struct CubeCoord {
     pub s: i32,
 }
 
+/// Mirror axis of the hex grid
+/// 
+/// Reflecting across an axis keeps that cube component and swaps the other two,
+/// e.g. across the q axis (q, r, s) -> (q, s, r)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum HexAxis {
+    Q,
+    R,
+    S,
+}
+
+impl HexAxis {
+    pub fn from_str(value: &str) -> Option<HexAxis> {
+        match value {
+            "q" => Some(HexAxis::Q),
+            "r" => Some(HexAxis::R),
+            "s" => Some(HexAxis::S),
+            _ => None,
+        }
+    }
+}
+
 /// Seed point for Voronoi region generation
 #[derive(Clone, Copy, Debug)]
 pub struct VoronoiSeed {
@@ -4,6 +4,16 @@ use wasm_bindgen::prelude::*;
 use std::collections::HashSet;
 use crate::state::WFC_STATE;
 use crate::hex_utils::{parse_valid_terrain_json, get_hex_neighbors};
+use crate::seed::{mix64, SeededRng};
+
+/// Derive a shuffle seed from the world seed and the coordinates being shuffled
+pub fn content_seed(world_seed: u64, coords: &[(i32, i32)]) -> u64 {
+    let mut seed: u64 = 0;
+    for (q, r) in coords {
+        seed = seed.wrapping_mul(31).wrapping_add((*q as u64).wrapping_mul(17).wrapping_add(*r as u64));
+    }
+    mix64(world_seed ^ seed)
+}
 
 /// Batch query tile types for multiple hex coordinates
 /// Returns JSON array with tile types: [{"q":0,"r":0,"tileType":1},...]
@@ -99,23 +109,10 @@ pub fn shuffle_array(array_json: String) -> String {
         i += 1;
     }
     
-    // Fisher-Yates shuffle using a simple PRNG
-    // Use a deterministic seed based on array content for reproducibility
-    let mut seed: u64 = 0;
-    for (q, r) in &coords {
-        seed = seed.wrapping_mul(31).wrapping_add((*q as u64).wrapping_mul(17).wrapping_add(*r as u64));
-    }
-    
-    let mut rng_state = seed;
-    let mut rng = || {
-        rng_state = rng_state.wrapping_mul(1103515245).wrapping_add(12345);
-        rng_state
-    };
-    
-    for i in (1..coords.len()).rev() {
-        let j = (rng() % (i as u64 + 1)) as usize;
-        coords.swap(i, j);
-    }
+    // Fisher-Yates shuffle seeded from the world seed and the array content
+    // Same world seed + same input always gives the same order
+    let world_seed = WFC_STATE.lock().unwrap().world_seed();
+    SeededRng::new(content_seed(world_seed, &coords)).shuffle(&mut coords);
     
     // Convert back to JSON
     let mut json_parts = Vec::new();
@@ -294,25 +291,10 @@ pub fn generate_building_placement(
         }
     }
     
-    // Shuffle available building hexes
-    if available_building_hexes.len() > 1 {
-        // Use deterministic seed based on content
-        let mut seed: u64 = 0;
-        for (q, r) in &available_building_hexes {
-            seed = seed.wrapping_mul(31).wrapping_add((*q as u64).wrapping_mul(17).wrapping_add(*r as u64));
-        }
-        
-        let mut rng_state = seed;
-        let mut rng = || {
-            rng_state = rng_state.wrapping_mul(1103515245).wrapping_add(12345);
-            rng_state
-        };
-        
-        for i in (1..available_building_hexes.len()).rev() {
-            let j = (rng() % (i as u64 + 1)) as usize;
-            available_building_hexes.swap(i, j);
-        }
-    }
+    // Shuffle available building hexes (sorted first so set iteration order never leaks in)
+    available_building_hexes.sort();
+    let world_seed = WFC_STATE.lock().unwrap().world_seed();
+    SeededRng::new(content_seed(world_seed, &available_building_hexes)).shuffle(&mut available_building_hexes);
     
     // Limit to target count
     let building_count = target_count.min(available_building_hexes.len() as i32);
@@ -326,36 +308,3 @@ pub fn generate_building_placement(
     
     format!("[{}]", json_parts.join(","))
 }
-
-/// Batch convert hex coordinates to world positions
-/// 
-/// @param hex_coords_json - JSON array of hex coordinates: [{"q":0,"r":0},...]
-/// @param hex_size - Size of hexagon for coordinate conversion
-/// @returns JSON array with world positions: [{"q":0,"r":0,"x":0.0,"z":0.0},...]
-#[wasm_bindgen]
-pub fn batch_hex_to_world(hex_coords_json: String, hex_size: f64) -> String {
-    let hex_coords = parse_valid_terrain_json(&hex_coords_json);
-    
-    // Formula for pointy-top hexagons:
-    // x = size * (√3 * q + √3/2 * r)
-    // z = size * (3/2 * r)
-    // Adjusted for the scaling factor used in TypeScript (hexSize / 1.34)
-    let adjusted_hex_size = hex_size / 1.34;
-    let sqrt3 = 3.0_f64.sqrt();
-    
-    let mut json_parts = Vec::new();
-    for (q, r) in hex_coords {
-        let q_f = q as f64;
-        let r_f = r as f64;
-        let x = adjusted_hex_size * (sqrt3 * 2.0 * q_f + sqrt3 * r_f);
-        let z = adjusted_hex_size * (3.0 * r_f);
-        
-        json_parts.push(format!(
-            r#"{{"q":{},"r":{},"x":{},"z":{}}}"#,
-            q, r, x, z
-        ));
-    }
-    
-    format!("[{}]", json_parts.join(","))
-}
-
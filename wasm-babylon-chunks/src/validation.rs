@@ -0,0 +1,313 @@
+/// Layout validation module
+/// 
+/// **Learning Point**: Instead of a single pass/fail flag, validation returns every
+/// violated rule together with the hexes involved, so the runtime test panel and CI
+/// regression tests can point at exactly what went wrong.
+
+use wasm_bindgen::prelude::*;
+use std::collections::{HashMap, HashSet};
+use crate::state::{WfcState, WFC_STATE};
+use crate::types::TileType;
+use crate::hex_utils::{generate_hex_grid, get_hex_neighbors, connected_components};
+use crate::chunks::chunk_border_hexes;
+use crate::json::{json_bool_field, json_int_field, json_number_field, coords_to_json};
+
+/// Tile types with the JSON name suffix used by the ratio rules ("minGrass", "maxWater", ...)
+const RATIO_TILE_TYPES: [(TileType, &str); 5] = [
+    (TileType::Grass, "Grass"),
+    (TileType::Building, "Building"),
+    (TileType::Road, "Road"),
+    (TileType::Forest, "Forest"),
+    (TileType::Water, "Water"),
+];
+
+/// Validation rules
+/// 
+/// Parsed from JSON: {"centerQ":0,"centerR":0,"rings":5,"buildingsAdjacentToRoad":true,
+///                    "roadsConnected":true,"noBuildingOnWater":true,"borderExits":true,
+///                    "roadsPerBorder":1,"minGrass":0.1,"maxWater":0.5,...}
+/// Without "rings" the whole layout is checked and border exits are skipped.
+pub struct ValidationRules {
+    /// Chunk to check: (center, rings); None checks every tile in the layout
+    pub chunk: Option<((i32, i32), i32)>,
+    pub buildings_adjacent_to_road: bool,
+    pub roads_connected: bool,
+    pub no_building_on_water: bool,
+    pub border_exits: bool,
+    pub roads_per_border: usize,
+    /// Minimum fraction of checked tiles per tile type
+    pub min_ratios: Vec<(TileType, f64)>,
+    /// Maximum fraction of checked tiles per tile type
+    pub max_ratios: Vec<(TileType, f64)>,
+}
+
+impl ValidationRules {
+    pub fn from_json(rules_json: &str) -> Self {
+        let chunk = json_int_field(rules_json, "rings").map(|rings| {
+            let center_q = json_int_field(rules_json, "centerQ").unwrap_or(0);
+            let center_r = json_int_field(rules_json, "centerR").unwrap_or(0);
+            ((center_q, center_r), rings.max(0))
+        });
+        let ratios = |prefix: &str| -> Vec<(TileType, f64)> {
+            RATIO_TILE_TYPES
+                .iter()
+                .filter_map(|&(tile_type, name)| {
+                    json_number_field(rules_json, &format!("{}{}", prefix, name)).map(|ratio| (tile_type, ratio))
+                })
+                .collect()
+        };
+        
+        ValidationRules {
+            chunk,
+            buildings_adjacent_to_road: json_bool_field(rules_json, "buildingsAdjacentToRoad").unwrap_or(true),
+            roads_connected: json_bool_field(rules_json, "roadsConnected").unwrap_or(true),
+            no_building_on_water: json_bool_field(rules_json, "noBuildingOnWater").unwrap_or(true),
+            border_exits: json_bool_field(rules_json, "borderExits").unwrap_or(chunk.is_some()),
+            roads_per_border: json_int_field(rules_json, "roadsPerBorder")
+                .map(|value| value.max(0) as usize)
+                .unwrap_or(1),
+            min_ratios: ratios("min"),
+            max_ratios: ratios("max"),
+        }
+    }
+}
+
+/// A violated rule and the hexes involved (sorted)
+pub struct Violation {
+    pub rule: &'static str,
+    pub message: String,
+    pub hexes: Vec<(i32, i32)>,
+}
+
+impl Violation {
+    fn new(rule: &'static str, message: String, mut hexes: Vec<(i32, i32)>) -> Self {
+        hexes.sort();
+        Violation { rule, message, hexes }
+    }
+    
+    pub fn to_json(&self) -> String {
+        format!(
+            r#"{{"rule":"{}","message":"{}","hexes":{}}}"#,
+            self.rule, self.message, coords_to_json(&self.hexes)
+        )
+    }
+}
+
+/// Check tiles against the rules
+/// 
+/// `tiles` is the whole layout (so adjacency can look across chunk borders);
+/// `scope` lists the hexes being validated; `water` holds the hexes whose underlying
+/// terrain is water (rivers and lakes under roads and buildings, water pre-constraints).
+/// Buildings on water are buildings on such a hex, or that stand in open water (every
+/// neighbor is water) for tiles no chunk recorded terrain for.
+pub fn validate_tiles(
+    tiles: &HashMap<(i32, i32), TileType>,
+    scope: &[(i32, i32)],
+    water: &HashSet<(i32, i32)>,
+    rules: &ValidationRules,
+) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    let scoped: Vec<((i32, i32), TileType)> = scope
+        .iter()
+        .filter_map(|hex| tiles.get(hex).map(|&tile_type| (*hex, tile_type)))
+        .collect();
+    let of_type = |wanted: TileType| -> Vec<(i32, i32)> {
+        scoped.iter().filter(|(_, tile_type)| *tile_type == wanted).map(|(hex, _)| *hex).collect()
+    };
+    let neighbors_of_type = |(q, r): (i32, i32), wanted: TileType| {
+        get_hex_neighbors(q, r)
+            .into_iter()
+            .filter(|neighbor| tiles.get(neighbor) == Some(&wanted))
+            .count()
+    };
+    let buildings = of_type(TileType::Building);
+    
+    if rules.buildings_adjacent_to_road {
+        // Multi-hex footprints only need one hex on the street, so check whole building blocks
+        let building_set: HashSet<(i32, i32)> = buildings.iter().copied().collect();
+        let isolated: Vec<(i32, i32)> = connected_components(&building_set)
+            .into_iter()
+            .filter(|block| block.iter().all(|&hex| neighbors_of_type(hex, TileType::Road) == 0))
+            .flatten()
+            .collect();
+        if !isolated.is_empty() {
+            violations.push(Violation::new(
+                "buildingsAdjacentToRoad",
+                format!("{} building hexes have no road access", isolated.len()),
+                isolated,
+            ));
+        }
+    }
+    
+    if rules.no_building_on_water {
+        let on_water: Vec<(i32, i32)> = buildings
+            .iter()
+            .copied()
+            .filter(|&hex| water.contains(&hex) || neighbors_of_type(hex, TileType::Water) == 6)
+            .collect();
+        if !on_water.is_empty() {
+            violations.push(Violation::new(
+                "noBuildingOnWater",
+                format!("{} building hexes are on water", on_water.len()),
+                on_water,
+            ));
+        }
+    }
+    
+    if rules.roads_connected {
+        let roads: HashSet<(i32, i32)> = of_type(TileType::Road).into_iter().collect();
+        let components = connected_components(&roads);
+        for component in components.into_iter().skip(1) {
+            violations.push(Violation::new(
+                "roadsConnected",
+                format!("{} road hexes are disconnected from the main network", component.len()),
+                component,
+            ));
+        }
+    }
+    
+    if !scoped.is_empty() {
+        let total = scoped.len() as f64;
+        for &(tile_type, min_ratio) in &rules.min_ratios {
+            let ratio = of_type(tile_type).len() as f64 / total;
+            if ratio < min_ratio {
+                violations.push(Violation::new(
+                    "minRatio",
+                    format!("{:?} ratio {:.3} is below {:.3}", tile_type, ratio, min_ratio),
+                    Vec::new(),
+                ));
+            }
+        }
+        for &(tile_type, max_ratio) in &rules.max_ratios {
+            let hexes = of_type(tile_type);
+            let ratio = hexes.len() as f64 / total;
+            if ratio > max_ratio {
+                violations.push(Violation::new(
+                    "maxRatio",
+                    format!("{:?} ratio {:.3} is above {:.3}", tile_type, ratio, max_ratio),
+                    hexes,
+                ));
+            }
+        }
+    }
+    
+    if let (true, Some(((center_q, center_r), rings))) = (rules.border_exits, rules.chunk) {
+        for side in 0..6 {
+            let border = chunk_border_hexes(center_q, center_r, rings, side);
+            let exits = border.iter().filter(|hex| tiles.get(hex) == Some(&TileType::Road)).count();
+            if exits < rules.roads_per_border {
+                violations.push(Violation::new(
+                    "borderExits",
+                    format!("side {} has {} road exits, expected {}", side, exits, rules.roads_per_border),
+                    border,
+                ));
+            }
+        }
+    }
+    
+    violations
+}
+
+/// Hexes whose underlying terrain is water: the water each chunk recorded (kept in step
+/// with edits) plus water pre-constraints
+pub fn underlying_water(state: &WfcState) -> HashSet<(i32, i32)> {
+    let mut water: HashSet<(i32, i32)> = state
+        .pre_constraints()
+        .filter(|&(_, tile_type)| tile_type == TileType::Water)
+        .map(|(hex, _)| hex)
+        .collect();
+    for center in state.chunk_centers() {
+        if let Some(record) = state.chunk(center) {
+            water.extend(record.water.iter().copied());
+        }
+    }
+    water
+}
+
+/// Validate the current layout (or one chunk of it) against a set of rules
+/// 
+/// **Learning Point**: Used by testManagement.ts and CI regression tests; each
+/// violation names the rule, explains it and lists the offending hexes (border
+/// violations list the whole side, ratio minimums list none).
+/// 
+/// @param rules_json - JSON object (see ValidationRules::from_json), "{}" for defaults
+/// @returns JSON string: {"valid":true,"checked":0,"violations":[{"rule":"roadsConnected",
+///          "message":"...","hexes":[{"q":0,"r":0},...]},...]}
+#[wasm_bindgen]
+pub fn validate_layout(rules_json: String) -> String {
+    let rules = ValidationRules::from_json(&rules_json);
+    let state = WFC_STATE.lock().unwrap();
+    let tiles: HashMap<(i32, i32), TileType> = state.tiles().collect();
+    let water = underlying_water(&state);
+    
+    let scope: Vec<(i32, i32)> = match rules.chunk {
+        Some(((center_q, center_r), rings)) => generate_hex_grid(rings, center_q, center_r)
+            .iter()
+            .map(|hex| (hex.q, hex.r))
+            .filter(|hex| tiles.contains_key(hex))
+            .collect(),
+        None => {
+            let mut all: Vec<(i32, i32)> = tiles.keys().copied().collect();
+            all.sort();
+            all
+        },
+    };
+    
+    let violations = validate_tiles(&tiles, &scope, &water, &rules);
+    let json_parts: Vec<String> = violations.iter().map(|violation| violation.to_json()).collect();
+    
+    format!(
+        r#"{{"valid":{},"checked":{},"violations":[{}]}}"#,
+        violations.is_empty(),
+        scope.len(),
+        json_parts.join(",")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::edit::{run_edit, EditOptions};
+    use crate::generation::{generate_chunk_tiles, ChunkGenerationParams};
+    
+    fn on_water(state: &WfcState) -> Vec<(i32, i32)> {
+        let tiles: HashMap<(i32, i32), TileType> = state.tiles().collect();
+        let scope: Vec<(i32, i32)> = tiles.keys().copied().collect();
+        let rules = ValidationRules::from_json(r#"{"buildingsAdjacentToRoad":false,"roadsConnected":false}"#);
+        validate_tiles(&tiles, &scope, &underlying_water(state), &rules)
+            .into_iter()
+            .filter(|violation| violation.rule == "noBuildingOnWater")
+            .flat_map(|violation| violation.hexes)
+            .collect()
+    }
+    
+    #[test]
+    fn edited_water_is_no_longer_water_underneath() {
+        let params = ChunkGenerationParams::from_json(r#"{"terrain":"noise","rivers":true}"#);
+        let chunk = generate_chunk_tiles(7, 0, 0, 5, &params, &HashMap::new());
+        let mut state = WfcState::new();
+        state.insert_chunk((0, 0), 5, chunk.tiles, chunk.buildings, chunk.water.iter().copied().collect());
+        // A water hex on the shore, so the open-water fallback does not apply
+        let &(q, r) = chunk
+            .water
+            .iter()
+            .find(|&&(q, r)| get_hex_neighbors(q, r).iter().any(|hex| state.get_tile(hex.0, hex.1).is_some_and(|tile| tile != TileType::Water)))
+            .expect("chunk has a shore");
+        
+        // A building dropped straight into the water is caught
+        state.insert_tile(q, r, TileType::Building);
+        assert_eq!(on_water(&state), vec![(q, r)]);
+        state.insert_tile(q, r, TileType::Water);
+        
+        // Filling the water in first makes it land
+        let options = EditOptions::from_json("{}");
+        run_edit(&mut state, "fill", &[((q, r), Some(TileType::Grass))], Vec::new(), &options);
+        run_edit(&mut state, "build", &[((q, r), Some(TileType::Building))], Vec::new(), &options);
+        assert!(on_water(&state).is_empty());
+        
+        // Undoing both edits brings the water back
+        state.undo_edit();
+        state.undo_edit();
+        assert!(state.chunk((0, 0)).unwrap().water.contains(&(q, r)));
+    }
+}
@@ -0,0 +1,267 @@
+/// Chunk visibility module
+/// 
+/// **Learning Point**: Rust owns the set of live chunks, keyed by chunk center. Each
+/// viewer update walks only the chunk lattice around the viewer and returns the delta
+/// (chunks to load, enable, disable and unload) instead of re-evaluating a JSON list of
+/// every chunk. Separate enter/exit radii (hysteresis) stop chunks on the edge of the
+/// view from flickering when the viewer moves back and forth across a threshold.
+
+use wasm_bindgen::prelude::*;
+use std::sync::{LazyLock, Mutex};
+use std::collections::{HashMap, HashSet, VecDeque};
+use crate::hex_utils::{hex_distance, axial_to_plane};
+use crate::chunks::{chunk_center_for_tile, chunk_neighbor_centers};
+use crate::json::{json_int_field, coords_to_json};
+
+/// Distance thresholds (hex distance from the viewer tile to a chunk center)
+/// 
+/// Parsed from JSON: {"enableRadius":0,"disableRadius":0,"loadRadius":0,"unloadRadius":0}
+/// Exit radii are never smaller than their enter radii, and chunks are always loaded
+/// before they are enabled.
+pub struct VisibilityConfig {
+    pub rings: i32,
+    /// Loaded chunks within this distance become enabled
+    pub enable_radius: i32,
+    /// Enabled chunks beyond this distance become disabled
+    pub disable_radius: i32,
+    /// Chunks within this distance are loaded
+    pub load_radius: i32,
+    /// Loaded chunks beyond this distance are unloaded
+    pub unload_radius: i32,
+}
+
+impl VisibilityConfig {
+    /// Defaults scale with the chunk spacing (2 * rings + 1): the viewer's chunk and its
+    /// six neighbors are enabled, one more ring of chunks is kept loaded
+    pub fn new(rings: i32) -> Self {
+        let rings = rings.max(0);
+        let spacing = 2 * rings + 1;
+        VisibilityConfig {
+            rings,
+            enable_radius: spacing + rings,
+            disable_radius: spacing + 2 * rings,
+            load_radius: 2 * spacing + rings,
+            unload_radius: 2 * spacing + 2 * rings,
+        }
+    }
+    
+    pub fn from_json(rings: i32, config_json: &str) -> Self {
+        let defaults = VisibilityConfig::new(rings);
+        let enable_radius = json_int_field(config_json, "enableRadius")
+            .unwrap_or(defaults.enable_radius)
+            .max(0);
+        let disable_radius = json_int_field(config_json, "disableRadius")
+            .unwrap_or(defaults.disable_radius)
+            .max(enable_radius);
+        let load_radius = json_int_field(config_json, "loadRadius")
+            .unwrap_or(defaults.load_radius)
+            .max(enable_radius);
+        let unload_radius = json_int_field(config_json, "unloadRadius")
+            .unwrap_or(defaults.unload_radius)
+            .max(load_radius)
+            .max(disable_radius);
+        
+        VisibilityConfig {
+            rings: defaults.rings,
+            enable_radius,
+            disable_radius,
+            load_radius,
+            unload_radius,
+        }
+    }
+}
+
+/// Chunk changes produced by one viewer update
+/// Loads are ordered by priority (nearest and most in front of the viewer first)
+pub struct VisibilityDelta {
+    pub load: Vec<(i32, i32)>,
+    pub enable: Vec<(i32, i32)>,
+    pub disable: Vec<(i32, i32)>,
+    pub unload: Vec<(i32, i32)>,
+}
+
+/// Live chunk set: chunk center -> enabled flag
+pub struct ChunkIndex {
+    config: VisibilityConfig,
+    chunks: HashMap<(i32, i32), bool>,
+}
+
+impl ChunkIndex {
+    pub fn new() -> Self {
+        ChunkIndex {
+            config: VisibilityConfig::new(0),
+            chunks: HashMap::new(),
+        }
+    }
+    
+    /// Replace the thresholds; changing the ring count drops all live chunks
+    /// because chunk centers move with it
+    pub fn configure(&mut self, config: VisibilityConfig) {
+        if config.rings != self.config.rings {
+            self.chunks.clear();
+        }
+        self.config = config;
+    }
+    
+    pub fn clear(&mut self) {
+        self.chunks.clear();
+    }
+    
+    /// Live chunks and their enabled flag
+    pub fn chunks(&self) -> impl Iterator<Item = ((i32, i32), bool)> + '_ {
+        self.chunks.iter().map(|(center, enabled)| (*center, *enabled))
+    }
+    
+    /// Chunk centers within `radius` of the viewer
+    /// 
+    /// **Learning Point**: Walks the chunk lattice outward from the viewer's own chunk
+    /// (breadth first over chunk neighbors), so the cost depends on the radius, not on
+    /// how many chunks exist. The walk may pass one chunk spacing beyond the radius so
+    /// centers near the edge are still reached.
+    fn chunks_within(&self, viewer: (i32, i32), radius: i32) -> Vec<(i32, i32)> {
+        let rings = self.config.rings;
+        let walk_radius = radius + 2 * rings + 1;
+        let start = chunk_center_for_tile(viewer.0, viewer.1, rings);
+        
+        let mut visited = HashSet::from([start]);
+        let mut queue = VecDeque::from([start]);
+        let mut found = Vec::new();
+        
+        while let Some(center) = queue.pop_front() {
+            if hex_distance(viewer.0, viewer.1, center.0, center.1) <= radius {
+                found.push(center);
+            }
+            for neighbor in chunk_neighbor_centers(center.0, center.1, rings) {
+                if hex_distance(viewer.0, viewer.1, neighbor.0, neighbor.1) <= walk_radius && visited.insert(neighbor) {
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        
+        found
+    }
+    
+    /// Move the viewer and compute the chunk delta
+    /// 
+    /// `facing` is the view direction in axial units (dq, dr); (0, 0) disables the
+    /// direction bias so loads are ordered by distance only.
+    pub fn update_viewer(&mut self, viewer: (i32, i32), facing: (f64, f64)) -> VisibilityDelta {
+        let config = &self.config;
+        let distance = |center: (i32, i32)| hex_distance(viewer.0, viewer.1, center.0, center.1);
+        let mut delta = VisibilityDelta {
+            load: Vec::new(),
+            enable: Vec::new(),
+            disable: Vec::new(),
+            unload: Vec::new(),
+        };
+        
+        // Exit thresholds first: only live chunks can leave
+        for (&center, enabled) in self.chunks.iter_mut() {
+            let chunk_distance = distance(center);
+            if chunk_distance > config.unload_radius {
+                delta.unload.push(center);
+            } else if *enabled && chunk_distance > config.disable_radius {
+                *enabled = false;
+                delta.disable.push(center);
+            }
+        }
+        for center in &delta.unload {
+            self.chunks.remove(center);
+        }
+        
+        // Enter thresholds: walk the lattice around the viewer
+        for center in self.chunks_within(viewer, config.load_radius) {
+            let enabled = self.chunks.entry(center).or_insert_with(|| {
+                delta.load.push(center);
+                false
+            });
+            if !*enabled && distance(center) <= config.enable_radius {
+                *enabled = true;
+                delta.enable.push(center);
+            }
+        }
+        
+        // Load priority: distance, discounted for chunks in front of the viewer
+        let (facing_x, facing_y) = axial_to_plane(facing.0, facing.1);
+        let facing_length = facing_x.hypot(facing_y);
+        let priority = |center: &(i32, i32)| -> f64 {
+            let (dx, dy) = axial_to_plane((center.0 - viewer.0) as f64, (center.1 - viewer.1) as f64);
+            let length = dx.hypot(dy);
+            let alignment = if facing_length > 0.0 && length > 0.0 {
+                (dx * facing_x + dy * facing_y) / (length * facing_length)
+            } else {
+                0.0
+            };
+            // alignment in [-1, 1]: straight ahead costs half, straight behind 1.5x
+            distance(*center) as f64 * (1.0 - 0.5 * alignment)
+        };
+        delta.load.sort_by(|a, b| priority(a).total_cmp(&priority(b)).then(a.cmp(b)));
+        delta.enable.sort_by_key(|center| (distance(*center), *center));
+        delta.disable.sort();
+        delta.unload.sort();
+        
+        delta
+    }
+}
+
+/// Global chunk visibility index (thread-safe)
+pub static CHUNK_INDEX: LazyLock<Mutex<ChunkIndex>> = LazyLock::new(|| Mutex::new(ChunkIndex::new()));
+
+/// Configure the chunk visibility thresholds
+/// 
+/// @param rings - Number of rings per chunk (changing it drops all live chunks)
+/// @param config_json - JSON object (see VisibilityConfig::from_json), "{}" for defaults
+#[wasm_bindgen]
+pub fn configure_chunk_visibility(rings: i32, config_json: String) {
+    let mut index = CHUNK_INDEX.lock().unwrap();
+    index.configure(VisibilityConfig::from_json(rings, &config_json));
+}
+
+/// Move the viewer and get the chunk changes since the previous update
+/// 
+/// **Learning Point**: Only the delta is returned, so TypeScript applies each list
+/// directly: create meshes for "load", show "enable", hide "disable", dispose "unload"
+/// (and call evict_chunk for it). Loaded chunks start disabled.
+/// 
+/// @param q - Viewer hex q coordinate
+/// @param r - Viewer hex r coordinate
+/// @param facing_q - View direction q component (axial), 0 with facing_r = 0 for none
+/// @param facing_r - View direction r component (axial)
+/// @returns JSON string: {"load":[{"q":0,"r":0},...],"enable":[...],"disable":[...],"unload":[...]}
+#[wasm_bindgen]
+pub fn update_viewer(q: i32, r: i32, facing_q: f64, facing_r: f64) -> String {
+    let mut index = CHUNK_INDEX.lock().unwrap();
+    let delta = index.update_viewer((q, r), (facing_q, facing_r));
+    
+    format!(
+        r#"{{"load":{},"enable":{},"disable":{},"unload":{}}}"#,
+        coords_to_json(&delta.load),
+        coords_to_json(&delta.enable),
+        coords_to_json(&delta.disable),
+        coords_to_json(&delta.unload)
+    )
+}
+
+/// Get all live chunks tracked by the visibility index
+/// 
+/// @returns JSON array sorted by (q, r): [{"q":0,"r":0,"enabled":true},...]
+#[wasm_bindgen]
+pub fn get_live_chunks() -> String {
+    let index = CHUNK_INDEX.lock().unwrap();
+    let mut chunks: Vec<((i32, i32), bool)> = index.chunks().collect();
+    chunks.sort();
+    
+    let mut json_parts = Vec::new();
+    for ((q, r), enabled) in chunks {
+        json_parts.push(format!(r#"{{"q":{},"r":{},"enabled":{}}}"#, q, r, enabled));
+    }
+    
+    format!("[{}]", json_parts.join(","))
+}
+
+/// Forget all live chunks (thresholds are kept)
+#[wasm_bindgen]
+pub fn clear_chunk_visibility() {
+    let mut index = CHUNK_INDEX.lock().unwrap();
+    index.clear();
+}
@@ -1,14 +1,64 @@
 /// Voronoi region generation module
 
 use wasm_bindgen::prelude::*;
-use crate::types::{TileType, VoronoiSeed};
+use crate::state::WFC_STATE;
+use crate::types::{HexCoord, TileType, VoronoiSeed};
 use crate::hex_utils::{generate_hex_grid, hex_distance};
+use crate::seed::{chunk_seed, SeededRng};
+use crate::grid_shape::PlacedShape;
+
+/// Pick Voronoi seed points for each region type from the given hexes
+/// Seeds are sampled with the supplied RNG; negative counts are treated as zero
+pub fn pick_voronoi_seeds(
+    hexes: &[(i32, i32)],
+    forest_seeds: i32,
+    water_seeds: i32,
+    grass_seeds: i32,
+    rng: &mut SeededRng,
+) -> Vec<VoronoiSeed> {
+    let mut seeds = Vec::new();
+    if hexes.is_empty() {
+        return seeds;
+    }
+    
+    for (count, tile_type) in [
+        (forest_seeds, TileType::Forest),
+        (water_seeds, TileType::Water),
+        (grass_seeds, TileType::Grass),
+    ] {
+        for _ in 0..count.max(0) {
+            let (q, r) = hexes[rng.next_index(hexes.len())];
+            seeds.push(VoronoiSeed { q, r, tile_type });
+        }
+    }
+    
+    seeds
+}
+
+/// Assign each hex to the tile type of its nearest seed
+/// Ties go to the earliest seed, so the result only depends on the seed order
+pub fn assign_voronoi_regions(hex_grid: &[HexCoord], seeds: &[VoronoiSeed]) -> Vec<((i32, i32), TileType)> {
+    let mut tiles = Vec::with_capacity(hex_grid.len());
+    for hex in hex_grid {
+        let nearest_seed = seeds.iter()
+            .min_by_key(|seed| hex_distance(hex.q, hex.r, seed.q, seed.r));
+        
+        if let Some(seed) = nearest_seed {
+            tiles.push(((hex.q, hex.r), seed.tile_type));
+        }
+    }
+    tiles
+}
 
 /// Generate Voronoi regions for specified tile types
 /// 
 /// **Learning Point**: Generates seed points for each region type and assigns
 /// each hex tile to the nearest seed point, creating Voronoi regions.
+/// Seed points are drawn from the chunk seed (world seed + center), so the same
+/// chunk always produces the same regions.
 /// Returns JSON string with array of {q, r, tileType} objects.
+/// Always a hexagon (the signature is fixed by the TypeScript callers); other grid shapes
+/// go through generate_voronoi_regions_in_shape.
 /// 
 /// @param max_layer - Maximum layer of hexagon (determines grid size)
 /// @param center_q - Center q coordinate
@@ -48,59 +98,11 @@ pub fn generate_voronoi_regions(
     }
     
     // Generate seed points by sampling from actual hex grid coordinates
-    // Use deterministic selection with prime multiplier for good distribution
-    // This ensures seeds are ALWAYS generated reliably
-    let mut seeds: Vec<VoronoiSeed> = Vec::new();
-    let mut seed_counter: usize = 0;
-    
-    // Generate forest seeds
-    // Ensure we have at least 0 seeds (handle negative values)
-    let forest_count = if forest_seeds > 0 { forest_seeds as usize } else { 0 };
-    for i in 0..forest_count {
-        seed_counter += 1;
-        // Use deterministic selection: (counter * prime) % count for good distribution
-        // Prime 7919 provides good pseudo-random distribution
-        let index = ((seed_counter * 7919) + (i * 997)) % hex_count;
-        // Bounds check (should always pass due to modulo, but be safe)
-        if index < hex_vec.len() {
-            let (q, r) = hex_vec[index];
-            seeds.push(VoronoiSeed {
-                q,
-                r,
-                tile_type: TileType::Forest,
-            });
-        }
-    }
-    
-    // Generate water seeds
-    let water_count = if water_seeds > 0 { water_seeds as usize } else { 0 };
-    for i in 0..water_count {
-        seed_counter += 1;
-        let index = ((seed_counter * 7919) + (i * 997)) % hex_count;
-        if index < hex_vec.len() {
-            let (q, r) = hex_vec[index];
-            seeds.push(VoronoiSeed {
-                q,
-                r,
-                tile_type: TileType::Water,
-            });
-        }
-    }
-    
-    // Generate grass seeds
-    let grass_count = if grass_seeds > 0 { grass_seeds as usize } else { 0 };
-    for i in 0..grass_count {
-        seed_counter += 1;
-        let index = ((seed_counter * 7919) + (i * 997)) % hex_count;
-        if index < hex_vec.len() {
-            let (q, r) = hex_vec[index];
-            seeds.push(VoronoiSeed {
-                q,
-                r,
-                tile_type: TileType::Grass,
-            });
-        }
-    }
+    // Seeds are drawn from the chunk's RNG (world seed + chunk center) so a chunk
+    // regenerated later gets exactly the same regions
+    let world_seed = WFC_STATE.lock().unwrap().world_seed();
+    let mut rng = SeededRng::new(chunk_seed(world_seed, center_q, center_r));
+    let mut seeds = pick_voronoi_seeds(&hex_vec, forest_seeds, water_seeds, grass_seeds, &mut rng);
     
     // CRITICAL: If no seeds were generated, force generation of at least one grass seed
     // This should never happen with positive seed counts, but ensures function always works
@@ -128,19 +130,11 @@ pub fn generate_voronoi_regions(
     };
     
     let mut json_parts = Vec::new();
-    for hex in &hex_grid {
-        let nearest_seed = seeds_ref.iter()
-            .min_by_key(|seed| hex_distance(hex.q, hex.r, seed.q, seed.r));
-        
-        match nearest_seed {
-            Some(seed) => {
-                json_parts.push(format!(
-                    r#"{{"q":{},"r":{},"tileType":{}}}"#,
-                    hex.q, hex.r, seed.tile_type as i32
-                ));
-            },
-            None => {},
-        }
+    for ((q, r), tile_type) in assign_voronoi_regions(&hex_grid, seeds_ref) {
+        json_parts.push(format!(
+            r#"{{"q":{},"r":{},"tileType":{}}}"#,
+            q, r, tile_type as i32
+        ));
     }
     
     // If json_parts is empty (shouldn't happen), return at least one entry from first seed
@@ -175,3 +169,42 @@ pub fn generate_voronoi_regions(
     }
 }
 
+/// Generate Voronoi regions over a rectangle, parallelogram, triangle or hexagon
+/// 
+/// **Learning Point**: Same seeding and assignment as generate_voronoi_regions, but the
+/// hexes come from a grid shape, so rectangular offset maps get regions too. Seeds are
+/// drawn from the chunk seed of the shape origin.
+/// 
+/// @param shape_json - JSON object (see PlacedShape::from_json)
+/// @param forest_seeds - Number of forest region seeds
+/// @param water_seeds - Number of water region seeds
+/// @param grass_seeds - Number of grass region seeds
+/// @returns JSON string with array of pre-constraints: [{"q":0,"r":0,"tileType":3},...]
+///          (empty for an unknown or empty shape)
+#[wasm_bindgen]
+pub fn generate_voronoi_regions_in_shape(
+    shape_json: String,
+    forest_seeds: i32,
+    water_seeds: i32,
+    grass_seeds: i32,
+) -> String {
+    let Some(shape) = PlacedShape::from_json(&shape_json) else {
+        return "[]".to_string();
+    };
+    let hexes = shape.hexes();
+    let hex_grid: Vec<HexCoord> = hexes.iter().map(|&(q, r)| HexCoord { q, r }).collect();
+    
+    let world_seed = WFC_STATE.lock().unwrap().world_seed();
+    let mut rng = SeededRng::new(chunk_seed(world_seed, shape.origin.0, shape.origin.1));
+    let mut seeds = pick_voronoi_seeds(&hexes, forest_seeds, water_seeds, grass_seeds, &mut rng);
+    if seeds.is_empty() {
+        seeds = pick_voronoi_seeds(&hexes, 0, 0, 1, &mut rng);
+    }
+    
+    let mut json_parts = Vec::new();
+    for ((q, r), tile_type) in assign_voronoi_regions(&hex_grid, &seeds) {
+        json_parts.push(format!(r#"{{"q":{},"r":{},"tileType":{}}}"#, q, r, tile_type as i32));
+    }
+    
+    format!("[{}]", json_parts.join(","))
+}
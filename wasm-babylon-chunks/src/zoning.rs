@@ -0,0 +1,190 @@
+/// Zoning module
+/// 
+/// **Learning Point**: A zoning pass partitions a chunk's land into districts after
+/// roads are laid and before buildings are placed. Road density around a hex and its
+/// distance from the chunk center decide the district, and the district then scales how
+/// many buildings it receives: markets cluster on busy roads near the center, farmland
+/// spreads over the sparsely connected outskirts.
+
+use wasm_bindgen::prelude::*;
+use std::collections::HashMap;
+use crate::state::WFC_STATE;
+use crate::types::{TileType, Zone};
+use crate::hex_utils::{generate_hex_grid, hex_distance, parse_valid_terrain_json};
+use crate::json::{json_int_field, json_number_field};
+
+/// Zoning thresholds
+/// 
+/// Parsed from JSON: {"marketRadius":0.35,"residentialRadius":0.65,"marketRoadDensity":0.3,
+///                    "residentialRoadDensity":0.15,"densityRadius":2}
+/// Radii are fractions of the chunk radius; road densities are the fraction of road
+/// hexes within `densityRadius` of a hex.
+pub struct ZoningConfig {
+    pub market_radius: f64,
+    pub residential_radius: f64,
+    pub market_road_density: f64,
+    pub residential_road_density: f64,
+    pub density_radius: i32,
+}
+
+impl Default for ZoningConfig {
+    fn default() -> Self {
+        ZoningConfig {
+            market_radius: 0.35,
+            residential_radius: 0.65,
+            market_road_density: 0.3,
+            residential_road_density: 0.15,
+            density_radius: 2,
+        }
+    }
+}
+
+impl ZoningConfig {
+    pub fn from_json(config_json: &str) -> Self {
+        let defaults = ZoningConfig::default();
+        ZoningConfig {
+            market_radius: json_number_field(config_json, "marketRadius").unwrap_or(defaults.market_radius),
+            residential_radius: json_number_field(config_json, "residentialRadius")
+                .unwrap_or(defaults.residential_radius),
+            market_road_density: json_number_field(config_json, "marketRoadDensity")
+                .unwrap_or(defaults.market_road_density),
+            residential_road_density: json_number_field(config_json, "residentialRoadDensity")
+                .unwrap_or(defaults.residential_road_density),
+            density_radius: json_int_field(config_json, "densityRadius")
+                .map(|radius| radius.clamp(1, 5))
+                .unwrap_or(defaults.density_radius),
+        }
+    }
+}
+
+/// Building density multiplier for a zone (applied to the chunk's building density)
+pub fn zone_building_factor(zone: Zone) -> f64 {
+    match zone {
+        Zone::Market => 1.5,
+        Zone::Residential => 1.0,
+        Zone::Farmland => 0.2,
+        Zone::Park | Zone::Unzoned => 0.0,
+    }
+}
+
+/// Fraction of known hexes within `radius` of (q, r) that are roads
+fn road_density(terrain: &HashMap<(i32, i32), TileType>, q: i32, r: i32, radius: i32) -> f64 {
+    let mut known = 0;
+    let mut roads = 0;
+    for hex in generate_hex_grid(radius, q, r) {
+        match terrain.get(&(hex.q, hex.r)) {
+            Some(TileType::Road) => {
+                known += 1;
+                roads += 1;
+            },
+            Some(_) => known += 1,
+            None => {},
+        }
+    }
+    
+    if known == 0 {
+        0.0
+    } else {
+        roads as f64 / known as f64
+    }
+}
+
+/// Assign a zone to every hex of a chunk
+/// 
+/// - Water is never zoned
+/// - Forest near the center becomes park, further out it stays wilderness
+/// - Other land near the center with no road in reach becomes park (green space)
+/// - Busy roads near the center make a market, moderate roads or closeness make
+///   residential, everything else is farmland
+pub fn assign_zones(
+    center_q: i32,
+    center_r: i32,
+    rings: i32,
+    terrain: &HashMap<(i32, i32), TileType>,
+    config: &ZoningConfig,
+) -> HashMap<(i32, i32), Zone> {
+    let mut zones = HashMap::new();
+    
+    for hex in generate_hex_grid(rings.max(0), center_q, center_r) {
+        let Some(&tile_type) = terrain.get(&(hex.q, hex.r)) else {
+            continue;
+        };
+        let center_fraction = hex_distance(center_q, center_r, hex.q, hex.r) as f64 / rings.max(1) as f64;
+        let near_center = center_fraction <= config.residential_radius;
+        
+        let zone = match tile_type {
+            TileType::Water => Zone::Unzoned,
+            TileType::Forest if near_center => Zone::Park,
+            TileType::Forest => Zone::Unzoned,
+            _ => {
+                let density = road_density(terrain, hex.q, hex.r, config.density_radius);
+                if density >= config.market_road_density && center_fraction <= config.market_radius {
+                    Zone::Market
+                } else if density == 0.0 && near_center {
+                    Zone::Park
+                } else if density >= config.residential_road_density || near_center {
+                    Zone::Residential
+                } else {
+                    Zone::Farmland
+                }
+            },
+        };
+        zones.insert((hex.q, hex.r), zone);
+    }
+    
+    zones
+}
+
+/// Get the zone at a specific hex grid position
+/// 
+/// @param q - Hex q coordinate
+/// @param r - Hex r coordinate
+/// @returns Zone id as i32 (0-4, matching the Zone enum), 0 if the hex is not zoned
+#[wasm_bindgen]
+pub fn get_zone_at(q: i32, r: i32) -> i32 {
+    let state = WFC_STATE.lock().unwrap();
+    state.get_zone(q, r).unwrap_or(Zone::Unzoned) as i32
+}
+
+/// Batch query tile types and zones for multiple hex coordinates
+/// Same shape as batch_get_tile_types with a zone id added to each entry
+/// 
+/// @param hex_coords_json - JSON array of hex coordinates: [{"q":0,"r":0},...]
+/// @returns JSON array: [{"q":0,"r":0,"tileType":1,"zone":2},...]
+#[wasm_bindgen]
+pub fn batch_get_tile_zones(hex_coords_json: String) -> String {
+    let state = WFC_STATE.lock().unwrap();
+    let mut hex_coords: Vec<(i32, i32)> = parse_valid_terrain_json(&hex_coords_json).into_iter().collect();
+    hex_coords.sort();
+    
+    let mut json_parts = Vec::new();
+    for (q, r) in hex_coords {
+        if let Some(tile) = state.get_tile(q, r) {
+            let zone = state.get_zone(q, r).unwrap_or(Zone::Unzoned);
+            json_parts.push(format!(
+                r#"{{"q":{},"r":{},"tileType":{},"zone":{}}}"#,
+                q, r, tile as i32, zone as i32
+            ));
+        }
+    }
+    
+    format!("[{}]", json_parts.join(","))
+}
+
+/// Get zone statistics for the tiles of the current layout
+/// 
+/// @returns JSON string: {"unzoned":0,"residential":0,"market":0,"farmland":0,"park":0}
+#[wasm_bindgen]
+pub fn get_zone_stats() -> String {
+    let state = WFC_STATE.lock().unwrap();
+    let mut counts = [0usize; 5];
+    for ((q, r), _) in state.tiles() {
+        counts[state.get_zone(q, r).unwrap_or(Zone::Unzoned) as usize] += 1;
+    }
+    
+    let json_parts: Vec<String> = (0..5)
+        .filter_map(|index| Zone::from_i32(index as i32).map(|zone| (index, zone)))
+        .map(|(index, zone)| format!(r#""{}":{}"#, zone.as_str(), counts[index]))
+        .collect();
+    format!("{{{}}}", json_parts.join(","))
+}